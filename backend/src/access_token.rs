@@ -0,0 +1,316 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+
+use super::*;
+
+const TOKEN_PREFIX: &str = "tw_";
+const TOKEN_LEN: usize = 40;
+
+/// What a personal access token is allowed to do,
+/// every scope includes all scopes ordered before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TokenScope {
+    Read,
+    Count,
+    Edit,
+    Admin,
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "read",
+            TokenScope::Count => "count",
+            TokenScope::Edit => "edit",
+            TokenScope::Admin => "admin",
+        }
+    }
+}
+
+impl From<String> for TokenScope {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "count" => Self::Count,
+            "edit" => Self::Edit,
+            "admin" => Self::Admin,
+            _ => Self::Read,
+        }
+    }
+}
+
+impl std::fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DbAccessToken {
+    pub uuid: uuid::Uuid,
+    pub user_uuid: uuid::Uuid,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub expire_on: Option<chrono::NaiveDateTime>,
+    pub last_used: Option<chrono::NaiveDateTime>,
+}
+
+impl DbAccessToken {
+    pub fn scopes(&self) -> Vec<TokenScope> {
+        self.scopes.iter().cloned().map(TokenScope::from).collect()
+    }
+
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes().into_iter().any(|s| s >= scope)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expire_on
+            .is_some_and(|expire| expire < chrono::Utc::now().naive_utc())
+    }
+}
+
+pub(crate) fn hash_token(token: &str) -> String {
+    use std::fmt::Write;
+
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .fold(String::new(), |mut hex, b| {
+            let _ = write!(hex, "{b:02x}");
+            hex
+        })
+}
+
+/// Creates a new token for `user`,
+/// the returned `String` is the only time the plain token is available
+pub async fn create(
+    pool: &PgPool,
+    user: uuid::Uuid,
+    name: &str,
+    scopes: &[TokenScope],
+    expire_on: Option<chrono::NaiveDateTime>,
+) -> Result<(DbAccessToken, String), BackendError> {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LEN)
+        .map(char::from)
+        .collect();
+    let token = format!("{TOKEN_PREFIX}{secret}");
+
+    let scopes = scopes
+        .iter()
+        .map(|s| s.as_str().to_string())
+        .collect::<Vec<_>>();
+
+    let db_token = sqlx::query_as!(
+        DbAccessToken,
+        r#"
+        INSERT INTO access_tokens (user_uuid, name, token_hash, scopes, expire_on)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING uuid, user_uuid, name, scopes, created_at, expire_on, last_used
+        "#,
+        user,
+        name,
+        hash_token(&token),
+        &scopes,
+        expire_on,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok((db_token, token))
+}
+
+pub async fn all_by_user(
+    pool: &PgPool,
+    user: uuid::Uuid,
+) -> Result<Vec<DbAccessToken>, BackendError> {
+    let tokens = sqlx::query_as!(
+        DbAccessToken,
+        r#"
+        SELECT uuid, user_uuid, name, scopes, created_at, expire_on, last_used
+        FROM access_tokens
+        WHERE user_uuid = $1
+        ORDER BY created_at
+        "#,
+        user,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(tokens)
+}
+
+pub async fn revoke(pool: &PgPool, user: uuid::Uuid, key: uuid::Uuid) -> Result<(), BackendError> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM access_tokens
+        WHERE uuid = $1 AND user_uuid = $2
+        "#,
+        key,
+        user,
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        Err(BackendError::DataNotFound(String::from("access token")))?
+    }
+
+    Ok(())
+}
+
+/// Looks up the plain `token` and marks it as used
+///
+/// # Returns
+/// The token together with the username of its owner
+///
+/// # Errors
+/// `AuthorizationError::InvalidToken` when no such token exists
+/// and `AuthorizationError::ExpiredToken` when it is past its expiry date
+pub async fn authenticate(
+    pool: &PgPool,
+    token: &str,
+) -> Result<(DbAccessToken, String), AuthorizationError> {
+    let db_token = sqlx::query_as!(
+        DbAccessToken,
+        r#"
+        UPDATE access_tokens
+        SET last_used = $2
+        WHERE token_hash = $1
+        RETURNING uuid, user_uuid, name, scopes, created_at, expire_on, last_used
+        "#,
+        hash_token(token),
+        chrono::Utc::now().naive_utc(),
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::RowNotFound => AuthorizationError::InvalidToken,
+        err => AuthorizationError::Internal(err.to_string()),
+    })?;
+
+    if db_token.is_expired() {
+        return Err(AuthorizationError::ExpiredToken);
+    }
+
+    let user = sqlx::query!(
+        r#"
+        SELECT username FROM users
+        WHERE uuid = $1
+        "#,
+        db_token.user_uuid,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(|_| AuthorizationError::UserNotFound)?;
+
+    Ok((db_token, user.username))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(scopes: &[TokenScope], expire_on: Option<chrono::NaiveDateTime>) -> DbAccessToken {
+        DbAccessToken {
+            uuid: uuid::Uuid::new_v4(),
+            user_uuid: uuid::Uuid::new_v4(),
+            name: String::from("Token"),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            created_at: chrono::Utc::now().naive_utc(),
+            expire_on,
+            last_used: None,
+        }
+    }
+
+    #[test]
+    fn scopes_are_ordered() {
+        assert!(TokenScope::Read < TokenScope::Count);
+        assert!(TokenScope::Count < TokenScope::Edit);
+        assert!(TokenScope::Edit < TokenScope::Admin);
+
+        for scope in [
+            TokenScope::Read,
+            TokenScope::Count,
+            TokenScope::Edit,
+            TokenScope::Admin,
+        ] {
+            assert_eq!(TokenScope::from(scope.to_string()), scope);
+        }
+    }
+
+    #[test]
+    fn scopes_include_lower_ones() {
+        let count = token(&[TokenScope::Count], None);
+        assert!(count.has_scope(TokenScope::Read));
+        assert!(count.has_scope(TokenScope::Count));
+        assert!(!count.has_scope(TokenScope::Edit));
+        assert!(!count.has_scope(TokenScope::Admin));
+
+        let admin = token(&[TokenScope::Admin], None);
+        assert!(admin.has_scope(TokenScope::Edit));
+        assert!(admin.has_scope(TokenScope::Admin));
+
+        assert!(!token(&[], None).has_scope(TokenScope::Read));
+    }
+
+    #[test]
+    fn tokens_expire() {
+        let now = chrono::Utc::now().naive_utc();
+        assert!(!token(&[], None).is_expired());
+        assert!(!token(&[], Some(now + chrono::Duration::hours(1))).is_expired());
+        assert!(token(&[], Some(now - chrono::Duration::hours(1))).is_expired());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn authenticates_tokens(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (created, secret) = create(&pool, user, "Token", &[TokenScope::Count], None)
+            .await
+            .unwrap();
+        assert!(secret.starts_with(TOKEN_PREFIX));
+
+        let (db_token, _) = authenticate(&pool, &secret).await.unwrap();
+        assert_eq!(db_token.uuid, created.uuid);
+        assert_eq!(db_token.scopes(), vec![TokenScope::Count]);
+        assert!(db_token.last_used.is_some());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn revoked_tokens_are_invalid(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (created, secret) = create(&pool, user, "Token", &[TokenScope::Read], None)
+            .await
+            .unwrap();
+
+        // tokens can only be revoked by their owner
+        let other = testing::user(&pool).await;
+        assert!(revoke(&pool, other, created.uuid).await.is_err());
+        assert!(authenticate(&pool, &secret).await.is_ok());
+
+        revoke(&pool, user, created.uuid).await.unwrap();
+        assert!(matches!(
+            authenticate(&pool, &secret).await,
+            Err(AuthorizationError::InvalidToken)
+        ));
+        assert!(matches!(
+            authenticate(&pool, "tw_unknown").await,
+            Err(AuthorizationError::InvalidToken)
+        ));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn expired_tokens_are_rejected(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let expired = chrono::Utc::now().naive_utc() - chrono::Duration::minutes(1);
+        let (_, secret) = create(&pool, user, "Token", &[TokenScope::Admin], Some(expired))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            authenticate(&pool, &secret).await,
+            Err(AuthorizationError::ExpiredToken)
+        ));
+    }
+}
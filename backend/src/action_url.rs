@@ -0,0 +1,329 @@
+use rand::{distributions::Alphanumeric, Rng};
+
+use super::{access_token::hash_token, *};
+
+const SECRET_LEN: usize = 32;
+
+/// The single change an action url applies to its countable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionKind {
+    Increment,
+    Decrement,
+    ToggleTimer,
+}
+
+impl ActionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionKind::Increment => "increment",
+            ActionKind::Decrement => "decrement",
+            ActionKind::ToggleTimer => "toggle_timer",
+        }
+    }
+}
+
+impl From<String> for ActionKind {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "decrement" => Self::Decrement,
+            "toggle_timer" => Self::ToggleTimer,
+            _ => Self::Increment,
+        }
+    }
+}
+
+impl std::fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DbActionUrl {
+    pub uuid: uuid::Uuid,
+    pub user_uuid: uuid::Uuid,
+    pub countable_uuid: uuid::Uuid,
+    pub action: String,
+    pub timer_started: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used: Option<chrono::NaiveDateTime>,
+}
+
+impl DbActionUrl {
+    pub fn kind(&self) -> ActionKind {
+        self.action.clone().into()
+    }
+}
+
+/// State of the phase an action url was applied to
+#[derive(Debug)]
+pub struct ActionOutcome {
+    pub action: ActionKind,
+    pub phase: DbPhase,
+    pub timer_running: bool,
+}
+
+/// Creates a new action url for `countable`,
+/// the returned `String` is the secret part of the url and only available once
+pub async fn create(
+    pool: &PgPool,
+    user: uuid::Uuid,
+    countable: uuid::Uuid,
+    action: ActionKind,
+) -> Result<(DbActionUrl, String), BackendError> {
+    let mut tx = pool.begin().await?;
+    check_owner(&mut tx, user, countable).await?;
+
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(SECRET_LEN)
+        .map(char::from)
+        .collect();
+
+    let action_url = sqlx::query_as!(
+        DbActionUrl,
+        r#"
+        INSERT INTO action_urls (user_uuid, countable_uuid, action, token_hash)
+        VALUES ($1, $2, $3, $4)
+        RETURNING uuid, user_uuid, countable_uuid, action, timer_started, created_at, last_used
+        "#,
+        user,
+        countable,
+        action.as_str(),
+        hash_token(&secret),
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok((action_url, secret))
+}
+
+pub async fn all_by_countable(
+    pool: &PgPool,
+    user: uuid::Uuid,
+    countable: uuid::Uuid,
+) -> Result<Vec<DbActionUrl>, BackendError> {
+    let action_urls = sqlx::query_as!(
+        DbActionUrl,
+        r#"
+        SELECT uuid, user_uuid, countable_uuid, action, timer_started, created_at, last_used
+        FROM action_urls
+        WHERE user_uuid = $1 AND countable_uuid = $2
+        ORDER BY created_at
+        "#,
+        user,
+        countable,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(action_urls)
+}
+
+pub async fn revoke(pool: &PgPool, user: uuid::Uuid, key: uuid::Uuid) -> Result<(), BackendError> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM action_urls
+        WHERE uuid = $1 AND user_uuid = $2
+        "#,
+        key,
+        user,
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        Err(BackendError::DataNotFound(String::from("action url")))?
+    }
+
+    Ok(())
+}
+
+/// Applies the action behind `secret` to its countable,
+/// counters apply the action to their newest phase
+///
+/// Timers only run in the browser, so `ToggleTimer` remembers when it was started
+/// and adds the elapsed time to the phase when it is toggled again
+///
+/// # Errors
+/// `BackendError::InvalidToken` when no action url with this secret exists
+pub async fn trigger(pool: &PgPool, secret: &str) -> Result<ActionOutcome, BackendError> {
+    let mut tx = pool.begin().await?;
+    let now = chrono::Utc::now().naive_utc();
+
+    let action_url = sqlx::query_as!(
+        DbActionUrl,
+        r#"
+        UPDATE action_urls
+        SET last_used = $2
+        WHERE token_hash = $1
+        RETURNING uuid, user_uuid, countable_uuid, action, timer_started, created_at, last_used
+        "#,
+        hash_token(secret),
+        now,
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::RowNotFound => BackendError::InvalidToken,
+        err => err.into(),
+    })?;
+
+    check_owner(&mut tx, action_url.user_uuid, action_url.countable_uuid).await?;
+
+    let phase = match phase::get(&mut tx, action_url.countable_uuid).await {
+        Ok(phase) => phase,
+        Err(BackendError::DataNotFound(_)) => {
+            counter::get_children(&mut tx, action_url.countable_uuid)
+                .await?
+                .pop()
+                .ok_or(BackendError::DataNotFound(String::from("phase")))?
+        }
+        Err(err) => return Err(err),
+    };
+
+    let user = action_url.user_uuid;
+    let mut timer_running = action_url.timer_started.is_some();
+    match action_url.kind() {
+        ActionKind::Increment => {
+            let kind = operation::OperationKind::AddCount(phase.step_size);
+            operation::record(&mut tx, user, phase.uuid, kind).await?
+        }
+        ActionKind::Decrement => {
+            let kind = operation::OperationKind::AddCount(-phase.step_size);
+            operation::record(&mut tx, user, phase.uuid, kind).await?
+        }
+        ActionKind::ToggleTimer => {
+            let timer_started = match action_url.timer_started {
+                Some(started) => {
+                    let elapsed = (now - started).num_milliseconds().max(0);
+                    let kind = operation::OperationKind::AddTime(elapsed);
+                    operation::record(&mut tx, user, phase.uuid, kind).await?;
+                    None
+                }
+                None => Some(now),
+            };
+            timer_running = timer_started.is_some();
+
+            sqlx::query!(
+                r#"
+                UPDATE action_urls
+                SET timer_started = $2
+                WHERE uuid = $1
+                "#,
+                action_url.uuid,
+                timer_started,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+    counter::edited(&mut tx, phase.parent_uuid).await?;
+
+    let phase = phase::get(&mut tx, phase.uuid).await?;
+    tx.commit().await?;
+
+    Ok(ActionOutcome {
+        action: action_url.kind(),
+        phase,
+        timer_running,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn secret(
+        pool: &PgPool,
+        user: uuid::Uuid,
+        countable: uuid::Uuid,
+        action: ActionKind,
+    ) -> String {
+        create(pool, user, countable, action).await.unwrap().1
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn counts_by_step_size(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, user).await;
+        let mut tx = pool.begin().await.unwrap();
+        phase::set_step(&mut tx, key, 3).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let increment = secret(&pool, user, key, ActionKind::Increment).await;
+        let decrement = secret(&pool, user, key, ActionKind::Decrement).await;
+
+        let outcome = trigger(&pool, &increment).await.unwrap();
+        assert_eq!(outcome.action, ActionKind::Increment);
+        assert_eq!(outcome.phase.count, 3);
+        assert!(!outcome.timer_running);
+        trigger(&pool, &increment).await.unwrap();
+
+        let outcome = trigger(&pool, &decrement).await.unwrap();
+        assert_eq!(outcome.action, ActionKind::Decrement);
+        assert_eq!(outcome.phase.count, 3);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn toggling_the_timer_adds_the_elapsed_time(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, user).await;
+        let toggle = secret(&pool, user, key, ActionKind::ToggleTimer).await;
+
+        let outcome = trigger(&pool, &toggle).await.unwrap();
+        assert!(outcome.timer_running);
+        assert_eq!(outcome.phase.time, 0);
+
+        sqlx::query("UPDATE action_urls SET timer_started = timer_started - INTERVAL '1 minute'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let outcome = trigger(&pool, &toggle).await.unwrap();
+        assert!(!outcome.timer_running);
+        assert!(outcome.phase.time >= 60_000);
+        assert!(outcome.phase.time < 120_000);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn counters_apply_to_their_newest_phase(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (counter, first) = testing::hunt(&pool, user).await;
+        let mut newer = testing::phase(user, counter);
+        newer.created_at += chrono::Duration::seconds(1);
+        let newer_key = newer.uuid;
+        let mut tx = pool.begin().await.unwrap();
+        phase::update(&mut tx, newer).await.unwrap();
+        tx.commit().await.unwrap();
+
+        let increment = secret(&pool, user, counter, ActionKind::Increment).await;
+        let outcome = trigger(&pool, &increment).await.unwrap();
+        assert_eq!(outcome.phase.uuid, newer_key);
+        assert_eq!(outcome.phase.count, 1);
+
+        let mut tx = pool.begin().await.unwrap();
+        assert_eq!(phase::get(&mut tx, first).await.unwrap().count, 0);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn unknown_secrets_are_invalid(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, user).await;
+        let (action_url, increment) = create(&pool, user, key, ActionKind::Increment)
+            .await
+            .unwrap();
+        revoke(&pool, user, action_url.uuid).await.unwrap();
+
+        assert!(matches!(
+            trigger(&pool, &increment).await,
+            Err(BackendError::InvalidToken)
+        ));
+        assert!(matches!(
+            trigger(&pool, "unknown").await,
+            Err(BackendError::InvalidToken)
+        ));
+    }
+}
@@ -61,7 +61,7 @@ async fn new_token(
 
     let token_uuid = uuid::Uuid::new_v4();
 
-    check_pass(pool, username, password).await?;
+    verify_pass(pool, username, password).await?;
 
     let id = query_as!(
         UserId,
@@ -97,33 +97,10 @@ pub async fn login_user(
     pool: &PgPool,
     username: String,
     password: String,
+    remote_addr: Option<String>,
     token_dur: chrono::Duration,
 ) -> Result<DbUser, BackendError> {
-    struct PassUser {
-        password: String,
-    }
-
-    let pass = match query_as!(
-        PassUser,
-        r#"
-        select users.password from users
-        where username = $1
-        "#,
-        username,
-    )
-    .fetch_one(pool)
-    .await
-    {
-        Ok(user) => user,
-        Err(_) => return Err(BackendError::InvalidUsername),
-    };
-
-    let parsed_hash = PasswordHash::new(&pass.password).unwrap();
-    // Trait objects for algorithms to support
-    let algs: &[&dyn PasswordVerifier] = &[&Pbkdf2];
-    if let Err(_err) = parsed_hash.verify_password(algs, &password) {
-        return Err(BackendError::InvalidPassword);
-    };
+    check_pass_from(pool, &username, &password, remote_addr).await?;
 
     let token = new_token(pool, &username, &password, token_dur).await?;
     let user = get_user(pool, &username, token.uuid).await?;
@@ -136,32 +113,8 @@ pub async fn change_password(
     username: String,
     old_pass: String,
     new_pass: String,
-) -> Result<(), impl DatabaseError> {
-    struct PassUser {
-        password: String,
-    }
-
-    let user = match query_as!(
-        PassUser,
-        r#"
-        select users.password from users
-        where username = $1
-        "#,
-        username,
-    )
-    .fetch_one(pool)
-    .await
-    {
-        Ok(user) => user,
-        Err(_) => return Err(AuthorizationError::InvalidUsername),
-    };
-
-    let parsed_hash = PasswordHash::new(&user.password).unwrap();
-    // Trait objects for algorithms to support
-    let algs: &[&dyn PasswordVerifier] = &[&Pbkdf2];
-    if let Err(_err) = parsed_hash.verify_password(algs, &old_pass) {
-        return Err(AuthorizationError::InvalidPassword);
-    };
+) -> Result<(), BackendError> {
+    check_pass(pool, &username, &old_pass).await?;
 
     // generate salt
     let salt = SaltString::generate(&mut OsRng);
@@ -190,8 +143,8 @@ pub async fn change_password(
     .await
     {
         Ok(_) => {}
-        Err(sqlx::Error::RowNotFound) => return Err(AuthorizationError::InvalidPassword)?,
-        Err(err) => Err(AuthorizationError::Internal(err.to_string()))?,
+        Err(sqlx::Error::RowNotFound) => Err(BackendError::InvalidPassword)?,
+        Err(err) => Err(err)?,
     };
 
     Ok(())
@@ -239,7 +192,38 @@ pub async fn change_username(
     Ok(user)
 }
 
+/// Verifies the password of `username`, every attempt counts towards the login limits of the user
 pub async fn check_pass(pool: &PgPool, username: &str, password: &str) -> Result<(), BackendError> {
+    check_pass_from(pool, username, password, None).await
+}
+
+/// Same as [check_pass], attempts also count towards the limits of `remote_addr`
+pub async fn check_pass_from(
+    pool: &PgPool,
+    username: &str,
+    password: &str,
+    remote_addr: Option<String>,
+) -> Result<(), BackendError> {
+    let config = rate_limit::LimiterConfig::from_env();
+    let mut keys = vec![rate_limit::AttemptKey::Username(username.to_string())];
+    if let Some(addr) = remote_addr {
+        keys.push(rate_limit::AttemptKey::Ip(addr))
+    }
+
+    rate_limit::check(pool, &config, &keys).await?;
+
+    match verify_pass(pool, username, password).await {
+        Ok(()) => rate_limit::register_success(pool, &keys).await,
+        Err(err @ (BackendError::InvalidUsername | BackendError::InvalidPassword)) => {
+            rate_limit::register_failure(pool, &config, &keys).await?;
+            Err(err)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Checks the password without any limits, only for callers that limit attempts themselves
+async fn verify_pass(pool: &PgPool, username: &str, password: &str) -> Result<(), BackendError> {
     struct Pass {
         password: String,
     }
@@ -0,0 +1,190 @@
+//! Notifies listeners about changed counters using Postgres `LISTEN/NOTIFY`
+//!
+//! Changes are also tracked per countable with the id of the transaction that made them,
+//! clients pull them incrementally with a [`cursor`] instead of comparing timestamps.
+
+use sqlx::postgres::PgListener;
+
+use super::*;
+
+pub const CHANNEL: &str = "countable_changes";
+/// prefix of notifications about removed countables, followed by the id of their owner
+const REMOVED: &str = "removed:";
+
+/// Notification received by a [`ChangeListener`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    /// counter with this id or one of its phases changed
+    Counter(uuid::Uuid),
+    /// countables of the user with this id were removed,
+    /// they can not be loaded anymore so the user has to pull the changes
+    Removed(uuid::Uuid),
+}
+
+impl Change {
+    fn parse(payload: &str) -> Option<Self> {
+        match payload.strip_prefix(REMOVED) {
+            Some(owner) => uuid::Uuid::parse_str(owner).ok().map(Self::Removed),
+            None => uuid::Uuid::parse_str(payload).ok().map(Self::Counter),
+        }
+    }
+}
+
+/// Queues a notification for the counter `key`, it is only sent once the transaction commits
+/// and identical notifications within one transaction are only sent once
+pub(crate) async fn notify(tx: &mut PgTx, key: uuid::Uuid) -> Result<(), BackendError> {
+    // `pg_notify` returns `void` which can not be checked by `query!`
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(CHANNEL)
+        .bind(key.to_string())
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Queues a notification that countables of `user` were removed, see [`notify`]
+pub(crate) async fn notify_removed(tx: &mut PgTx, user: uuid::Uuid) -> Result<(), BackendError> {
+    sqlx::query("SELECT pg_notify($1, $2)")
+        .bind(CHANNEL)
+        .bind(format!("{REMOVED}{user}"))
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(())
+}
+
+/// Remembers that countable `key` was changed by the current transaction,
+/// for counters all of their phases are included since some changes apply to every phase
+pub(crate) async fn track(tx: &mut PgTx, key: uuid::Uuid) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO countable_changes (uuid, owner_uuid, xid)
+        SELECT uuid, owner_uuid, pg_current_xact_id()::text::bigint FROM counters WHERE uuid = $1
+        UNION ALL
+        SELECT uuid, owner_uuid, pg_current_xact_id()::text::bigint FROM phases
+        WHERE uuid = $1 OR parent_uuid = $1
+        ON CONFLICT (uuid) DO UPDATE
+        SET xid = EXCLUDED.xid, removed = false
+        "#,
+        key,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Leaves a tombstone for removed countables so incremental pulls can drop them
+pub(crate) async fn track_removed(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    keys: &[uuid::Uuid],
+) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO countable_changes (uuid, owner_uuid, xid, removed)
+        SELECT key, $1, pg_current_xact_id()::text::bigint, true FROM UNNEST($2::uuid[]) AS key
+        ON CONFLICT (uuid) DO UPDATE
+        SET xid = EXCLUDED.xid, removed = true
+        "#,
+        user,
+        keys,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Position up to which every change is visible to the transaction.
+/// Transactions still running when it is taken have a higher id,
+/// so their changes are included when pulling with this cursor later.
+pub async fn cursor(tx: &mut PgTx) -> Result<i64, BackendError> {
+    let cursor = sqlx::query_scalar!(
+        r#"SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint AS "cursor!""#
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(cursor)
+}
+
+/// Countables of `user` removed at or after `cursor`
+pub async fn removed_since(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    cursor: i64,
+) -> Result<Vec<uuid::Uuid>, BackendError> {
+    let removed = sqlx::query_scalar!(
+        r#"
+        SELECT uuid FROM countable_changes
+        WHERE owner_uuid = $1 AND xid >= $2 AND removed
+        "#,
+        user,
+        cursor,
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(removed)
+}
+
+/// Dedicated connection listening on [`CHANNEL`]
+pub struct ChangeListener(PgListener);
+
+impl ChangeListener {
+    pub async fn connect(pool: &PgPool) -> Result<Self, BackendError> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(CHANNEL).await?;
+
+        Ok(Self(listener))
+    }
+
+    /// Waits for the next change,
+    /// the connection is reestablished automatically when it is lost
+    pub async fn recv(&mut self) -> Result<Change, BackendError> {
+        loop {
+            let notification = self.0.recv().await?;
+            if let Some(change) = Change::parse(notification.payload()) {
+                return Ok(change);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_notifications() {
+        let key = uuid::Uuid::new_v4();
+        assert_eq!(Change::parse(&key.to_string()), Some(Change::Counter(key)));
+        assert_eq!(
+            Change::parse(&format!("{REMOVED}{key}")),
+            Some(Change::Removed(key))
+        );
+        assert_eq!(Change::parse("removed:"), None);
+        assert_eq!(Change::parse("unknown"), None);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn removing_a_phase_notifies(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (counter, phase) = testing::hunt(&pool, user).await;
+        let mut listener = ChangeListener::connect(&pool).await.unwrap();
+
+        remove_phase(&pool, user, phase).await.unwrap();
+
+        let mut received = vec![
+            listener.recv().await.unwrap(),
+            listener.recv().await.unwrap(),
+        ];
+        received.sort_by_key(|change| matches!(change, Change::Counter(_)));
+        assert_eq!(
+            received,
+            vec![Change::Removed(user), Change::Counter(counter)]
+        );
+    }
+}
@@ -15,6 +15,60 @@ pub async fn all_by_user(tx: &mut PgTx, user: uuid::Uuid) -> Result<Vec<DbCounte
     Ok(counters)
 }
 
+/// Counters of `user` changed at or after `cursor`,
+/// including counters of which a phase was changed, see [`changes::cursor`]
+pub async fn changed_since(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    cursor: i64,
+) -> Result<Vec<DbCounter>, BackendError> {
+    let counters = sqlx::query_as!(
+        DbCounter,
+        r#"
+        SELECT * FROM counters
+        WHERE owner_uuid = $1
+          AND (
+            uuid IN (
+                SELECT uuid FROM countable_changes
+                WHERE owner_uuid = $1 AND xid >= $2
+            )
+            OR uuid IN (
+                SELECT parent_uuid FROM phases
+                WHERE uuid IN (
+                    SELECT uuid FROM countable_changes
+                    WHERE owner_uuid = $1 AND xid >= $2
+                )
+            )
+          );
+        "#,
+        user,
+        cursor,
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(counters)
+}
+
+pub async fn get(tx: &mut PgTx, key: uuid::Uuid) -> Result<DbCounter, BackendError> {
+    let counter = sqlx::query_as!(
+        DbCounter,
+        r#"
+        SELECT * FROM counters
+        WHERE uuid = $1
+        "#,
+        key,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::RowNotFound => BackendError::CounterNotFound,
+        err => err.into(),
+    })?;
+
+    Ok(counter)
+}
+
 pub async fn get_children(tx: &mut PgTx, key: uuid::Uuid) -> Result<Vec<DbPhase>, BackendError> {
     let last_child = sqlx::query_as!(
         DbPhase,
@@ -28,12 +82,18 @@ pub async fn get_children(tx: &mut PgTx, key: uuid::Uuid) -> Result<Vec<DbPhase>
             time,
             has_charm,
             hunt_type as "hunt_type: Hunttype",
+            game as "game: Game",
+            research_level,
+            outbreak_cleared,
+            sparkling_power,
+            custom_method,
             dexnav_encounters,
             success,
             last_edit,
             created_at,
             is_deleted,
-            step_size
+            step_size,
+            revision
             FROM phases
         WHERE parent_uuid = $1
         ORDER BY created_at;
@@ -50,7 +110,9 @@ pub async fn edited(tx: &mut PgTx, key: uuid::Uuid) -> Result<(), BackendError>
     sqlx::query!(
         r#"
         UPDATE counters
-        SET last_edit = $2
+        SET
+            last_edit = $2,
+            revision = revision + 1
         WHERE uuid = $1
         "#,
         key,
@@ -59,6 +121,9 @@ pub async fn edited(tx: &mut PgTx, key: uuid::Uuid) -> Result<(), BackendError>
     .execute(&mut **tx)
     .await?;
 
+    changes::track(tx, key).await?;
+    changes::notify(tx, key).await?;
+
     Ok(())
 }
 
@@ -80,6 +145,28 @@ pub async fn set_name(tx: &mut PgTx, key: uuid::Uuid, name: &str) -> Result<(),
     Ok(())
 }
 
+pub async fn set_target(
+    tx: &mut PgTx,
+    key: uuid::Uuid,
+    target: Option<i32>,
+) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        UPDATE counters
+        SET target = $2
+        WHERE uuid = $1
+        "#,
+        key,
+        target,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    edited(tx, key).await?;
+
+    Ok(())
+}
+
 pub async fn get_count(tx: &mut PgTx, key: uuid::Uuid) -> Result<i32, BackendError> {
     struct Count {
         count: Option<i64>,
@@ -179,7 +266,8 @@ pub async fn set_hunttype(
         UPDATE phases
         SET 
             hunt_type = $2,
-            last_edit = $3
+            last_edit = $3,
+            revision = revision + 1
         WHERE parent_uuid = $1
         "#,
         key,
@@ -189,6 +277,40 @@ pub async fn set_hunttype(
     .execute(&mut **tx)
     .await?;
 
+    changes::track(tx, key).await?;
+
+    Ok(())
+}
+
+pub async fn set_method_params(
+    tx: &mut PgTx,
+    key: uuid::Uuid,
+    params: MethodParams,
+) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        UPDATE phases
+        SET
+            research_level = $2,
+            outbreak_cleared = $3,
+            sparkling_power = $4,
+            custom_method = $6,
+            last_edit = $5,
+            revision = revision + 1
+        WHERE parent_uuid = $1
+        "#,
+        key,
+        params.research_level,
+        params.outbreak_cleared,
+        params.sparkling_power,
+        chrono::Utc::now().naive_utc(),
+        params.custom_method,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    changes::track(tx, key).await?;
+
     Ok(())
 }
 
@@ -202,7 +324,8 @@ pub async fn set_charm(
         UPDATE phases
         SET 
             has_charm = $2,
-            last_edit = $3
+            last_edit = $3,
+            revision = revision + 1
         WHERE parent_uuid = $1
         "#,
         key,
@@ -212,27 +335,78 @@ pub async fn set_charm(
     .execute(&mut **tx)
     .await?;
 
+    changes::track(tx, key).await?;
+
     Ok(())
 }
 
-pub async fn update(tx: &mut PgTx, counter: DbCounter) -> Result<(), BackendError> {
+pub async fn set_game(
+    tx: &mut PgTx,
+    key: uuid::Uuid,
+    game: Option<Game>,
+) -> Result<(), BackendError> {
     sqlx::query!(
         r#"
-        INSERT INTO counters (uuid, owner_uuid, name, created_at, is_deleted)
-        VALUES ($1, $2, $3, $4, $5)
+        UPDATE phases
+        SET
+            game = $2,
+            last_edit = $3,
+            revision = revision + 1
+        WHERE parent_uuid = $1
+        "#,
+        key,
+        game as Option<Game>,
+        chrono::Utc::now().naive_utc(),
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    changes::track(tx, key).await?;
+
+    Ok(())
+}
+
+/// Inserts `counter` or updates it when `counter.revision` is still the current revision
+///
+/// # Errors
+/// `BackendError::Conflict` with the current counter when it was changed in the meantime,
+/// `BackendError::Unauthorized` when a counter of another user has the same uuid
+pub async fn update(tx: &mut PgTx, counter: DbCounter) -> Result<(), BackendError> {
+    let updated = sqlx::query!(
+        r#"
+        INSERT INTO counters (uuid, owner_uuid, name, created_at, is_deleted, target)
+        VALUES ($1, $2, $3, $4, $5, $7)
         ON CONFLICT (uuid) DO UPDATE
         SET
             name = $3,
-            is_deleted = $5
+            is_deleted = $5,
+            target = $7
+        WHERE counters.revision = $6
+          AND counters.owner_uuid = EXCLUDED.owner_uuid
         "#,
         counter.uuid,
         counter.owner_uuid,
         counter.name,
         counter.created_at,
         counter.is_deleted,
+        counter.revision,
+        counter.target,
     )
     .execute(&mut **tx)
-    .await?;
+    .await?
+    .rows_affected()
+        > 0;
+
+    if !updated {
+        let current = get(tx, counter.uuid).await?;
+        // the uuid is taken by another user, their counter must not be sent back
+        if current.owner_uuid != counter.owner_uuid {
+            return Err(BackendError::Unauthorized);
+        }
+        return Err(BackendError::Conflict(Box::new(ServerVersion::Counter(
+            current,
+        ))));
+    }
 
     edited(tx, counter.uuid).await?;
 
@@ -256,7 +430,8 @@ pub async fn archive(tx: &mut PgTx, key: uuid::Uuid) -> Result<(), BackendError>
         UPDATE phases
         SET
             is_deleted = true,
-            last_edit = $2
+            last_edit = $2,
+            revision = revision + 1
         WHERE parent_uuid = $1
         "#,
         key,
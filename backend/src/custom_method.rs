@@ -0,0 +1,89 @@
+use super::*;
+
+/// Hunting method defined by a user, see `Hunttype::Custom`
+#[derive(Debug, Clone)]
+pub struct DbCustomMethod {
+    pub uuid: uuid::Uuid,
+    pub user_uuid: uuid::Uuid,
+    pub name: String,
+    pub odds: i32,
+    pub rolls: i32,
+    pub charm_rolls: i32,
+    /// counts from which the rolls of the same index in `table_rolls` apply
+    pub table_counts: Vec<i32>,
+    pub table_rolls: Vec<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+pub async fn all_by_user(
+    pool: &PgPool,
+    user: uuid::Uuid,
+) -> Result<Vec<DbCustomMethod>, BackendError> {
+    let methods = sqlx::query_as!(
+        DbCustomMethod,
+        r#"
+        SELECT uuid, user_uuid, name, odds, rolls, charm_rolls, table_counts, table_rolls, created_at
+        FROM custom_methods
+        WHERE user_uuid = $1
+        ORDER BY created_at
+        "#,
+        user,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(methods)
+}
+
+pub async fn create(
+    pool: &PgPool,
+    user: uuid::Uuid,
+    name: &str,
+    odds: i32,
+    rolls: i32,
+    charm_rolls: i32,
+    table: &[(i32, i32)],
+) -> Result<DbCustomMethod, BackendError> {
+    let (table_counts, table_rolls): (Vec<i32>, Vec<i32>) = table.iter().copied().unzip();
+
+    let method = sqlx::query_as!(
+        DbCustomMethod,
+        r#"
+        INSERT INTO custom_methods (user_uuid, name, odds, rolls, charm_rolls, table_counts, table_rolls)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        RETURNING uuid, user_uuid, name, odds, rolls, charm_rolls, table_counts, table_rolls, created_at
+        "#,
+        user,
+        name,
+        odds,
+        rolls,
+        charm_rolls,
+        &table_counts,
+        &table_rolls,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(method)
+}
+
+/// Deletes the method, phases that used it keep their count
+/// but lose the reference to the method
+pub async fn delete(pool: &PgPool, user: uuid::Uuid, key: uuid::Uuid) -> Result<(), BackendError> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM custom_methods
+        WHERE uuid = $1 AND user_uuid = $2
+        "#,
+        key,
+        user,
+    )
+    .execute(pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        Err(BackendError::DataNotFound(String::from("custom method")))?
+    }
+
+    Ok(())
+}
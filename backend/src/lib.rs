@@ -5,14 +5,22 @@ use dotenvy::dotenv;
 use sqlx::postgres::PgPoolOptions;
 pub use sqlx::PgPool;
 
+pub mod access_token;
+pub mod action_url;
 pub mod auth;
+pub mod changes;
 mod types;
 pub use types::*;
 mod counter_data;
 pub use counter_data::*;
 
 pub mod counter;
+pub mod custom_method;
+pub mod operation;
 pub mod phase;
+pub mod rate_limit;
+#[cfg(test)]
+mod testing;
 
 pub type PgTx = sqlx::Transaction<'static, sqlx::Postgres>;
 
@@ -40,6 +48,10 @@ pub enum BackendError {
     InvalidUsername,
     #[error("Could not find {0} data for user")]
     DataNotFound(String),
+    #[error(transparent)]
+    Login(#[from] LoginError),
+    #[error("Data was changed in the meantime")]
+    Conflict(Box<ServerVersion>),
 }
 
 pub trait DatabaseError: Error {}
@@ -78,6 +90,10 @@ pub enum LoginError {
     InvalidSecrets,
     #[error("Internal Server error when logging in user\nGot Error: {0}")]
     Internal(String),
+    #[error("Too many failed login attempts, try again in {} seconds", .retry_after.num_seconds().max(1))]
+    TooManyAttempts { retry_after: chrono::Duration },
+    #[error("Login is temporarily locked, try again in {} minutes", .retry_after.num_minutes().max(1))]
+    LockedOut { retry_after: chrono::Duration },
 }
 
 impl DatabaseError for LoginError {}
@@ -222,6 +238,8 @@ pub async fn update_counter(
     Ok(())
 }
 
+/// Deletes a counter and its phases,
+/// their ids stay known as removed so other devices drop them, see [`changes::removed_since`]
 pub async fn remove_counter(
     pool: &PgPool,
     username: &str,
@@ -229,42 +247,90 @@ pub async fn remove_counter(
     counter_uuid: uuid::Uuid,
 ) -> Result<(), BackendError> {
     let user = auth::get_user(pool, username, token).await?;
+    let mut tx = pool.begin().await?;
 
-    sqlx::query!(
+    let mut removed = sqlx::query_scalar!(
         r#"
         delete from counters
         where owner_uuid = $1 AND uuid = $2
+        returning uuid
         "#,
         user.uuid,
         counter_uuid,
     )
-    .execute(pool)
+    .fetch_all(&mut *tx)
     .await?;
 
-    sqlx::query!(
-        r#"
-        DELETE FROM phases
-        WHERE owner_uuid = $1 AND parent_uuid = $2
-        "#,
-        user.uuid,
-        counter_uuid,
-    )
-    .execute(pool)
-    .await?;
+    if removed.is_empty() {
+        return Ok(());
+    }
+
+    removed.extend(
+        sqlx::query_scalar!(
+            r#"
+            DELETE FROM phases
+            WHERE owner_uuid = $1 AND parent_uuid = $2
+            RETURNING uuid
+            "#,
+            user.uuid,
+            counter_uuid,
+        )
+        .fetch_all(&mut *tx)
+        .await?,
+    );
+
+    changes::track_removed(&mut tx, user.uuid, &removed).await?;
+    changes::notify_removed(&mut tx, user.uuid).await?;
+    tx.commit().await?;
 
     Ok(())
 }
 
-pub async fn remove_phase(pool: &PgPool, phase_id: uuid::Uuid) -> Result<(), sqlx::error::Error> {
-    sqlx::query!(
+/// Deletes a phase of `user`, its id stays known as removed like in [`remove_counter`]
+pub async fn remove_phase(
+    pool: &PgPool,
+    user: uuid::Uuid,
+    phase_id: uuid::Uuid,
+) -> Result<(), BackendError> {
+    let mut tx = pool.begin().await?;
+
+    let parent = sqlx::query_scalar!(
         r#"
         delete from phases
-        where uuid = $1
+        where uuid = $1 AND owner_uuid = $2
+        returning parent_uuid
         "#,
-        phase_id
+        phase_id,
+        user,
     )
-    .execute(pool)
+    .fetch_optional(&mut *tx)
     .await?;
 
+    if let Some(parent) = parent {
+        changes::track_removed(&mut tx, user, &[phase_id]).await?;
+        changes::notify_removed(&mut tx, user).await?;
+        counter::edited(&mut tx, parent).await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Errors with `Unauthorized` unless `countable` is a counter or phase owned by `user`
+pub(crate) async fn check_owner(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    countable: uuid::Uuid,
+) -> Result<(), BackendError> {
+    let owner = match counter::get(tx, countable).await {
+        Ok(counter) => counter.owner_uuid,
+        Err(BackendError::CounterNotFound) => phase::get(tx, countable).await?.owner_uuid,
+        Err(err) => return Err(err),
+    };
+
+    if owner != user {
+        Err(BackendError::Unauthorized)?
+    }
+
     Ok(())
 }
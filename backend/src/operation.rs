@@ -0,0 +1,502 @@
+//! Append only log of changes made to counters and phases
+//!
+//! Devices record operations while they are offline and send them once they are back.
+//! `AddCount` and `AddTime` add up no matter in which order they arrive,
+//! all other operations only take effect when no operation of the same kind
+//! with a higher `(clock, device_id)` was applied to the countable before.
+
+use super::*;
+
+#[derive(Debug)]
+pub enum OperationKind {
+    CreateCounter(DbCounter),
+    CreatePhase(DbPhase),
+    AddCount(i32),
+    /// milliseconds
+    AddTime(i64),
+    Rename(String),
+    SetHunttype(Hunttype, MethodParams),
+    SetSuccess(bool),
+    SetStepSize(i32),
+    SetCharm(bool),
+    SetGame(Option<Game>),
+    SetTarget(Option<i32>),
+    Archive,
+}
+
+impl OperationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::CreateCounter(_) | OperationKind::CreatePhase(_) => "create",
+            OperationKind::AddCount(_) => "add_count",
+            OperationKind::AddTime(_) => "add_time",
+            OperationKind::Rename(_) => "rename",
+            OperationKind::SetHunttype(..) => "set_hunttype",
+            OperationKind::SetSuccess(_) => "set_success",
+            OperationKind::SetStepSize(_) => "set_step_size",
+            OperationKind::SetCharm(_) => "set_charm",
+            OperationKind::SetGame(_) => "set_game",
+            OperationKind::SetTarget(_) => "set_target",
+            OperationKind::Archive => "archive",
+        }
+    }
+
+    fn amount(&self) -> Option<i64> {
+        match self {
+            OperationKind::AddCount(amount) => Some((*amount).into()),
+            OperationKind::AddTime(amount) => Some(*amount),
+            _ => None,
+        }
+    }
+
+    fn value(&self) -> Option<String> {
+        match self {
+            OperationKind::Rename(name) => Some(name.clone()),
+            OperationKind::SetHunttype(hunttype, _) => Some(hunttype.to_string()),
+            OperationKind::SetSuccess(success) => Some(success.to_string()),
+            OperationKind::SetStepSize(step) => Some(step.to_string()),
+            OperationKind::SetCharm(has_charm) => Some(has_charm.to_string()),
+            OperationKind::SetGame(game) => game.map(|g| g.to_string()),
+            OperationKind::SetTarget(target) => target.map(|t| t.to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Operation {
+    pub uuid: uuid::Uuid,
+    pub countable_uuid: uuid::Uuid,
+    pub device_id: uuid::Uuid,
+    pub clock: i64,
+    pub kind: OperationKind,
+}
+
+/// Device of operations that the server records itself, like counts from the REST API
+pub const SERVER_DEVICE: uuid::Uuid = uuid::Uuid::nil();
+
+/// Records and applies `kind` on `countable` as a new operation of [SERVER_DEVICE],
+/// it orders after all operations known so far
+///
+/// # Errors
+/// `BackendError::Unauthorized` when the countable belongs to another user
+pub async fn record(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    countable: uuid::Uuid,
+    kind: OperationKind,
+) -> Result<(), BackendError> {
+    let clock = max_clock(tx, user).await? + 1;
+    let operation = Operation {
+        uuid: uuid::Uuid::new_v4(),
+        countable_uuid: countable,
+        device_id: SERVER_DEVICE,
+        clock,
+        kind,
+    };
+    apply(tx, user, operation).await?;
+
+    Ok(())
+}
+
+/// Highest clock of all operations of `user`,
+/// devices continue counting from here so their operations order after the known ones
+pub async fn max_clock(tx: &mut PgTx, user: uuid::Uuid) -> Result<i64, BackendError> {
+    let clock = sqlx::query_scalar!(
+        r#"
+        SELECT MAX(clock) FROM operations
+        WHERE owner_uuid = $1
+        "#,
+        user,
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(clock.unwrap_or_default())
+}
+
+/// Records `operation` and applies it
+///
+/// # Returns
+/// `false` when the operation was already applied before
+///
+/// # Errors
+/// `BackendError::Unauthorized` when the countable belongs to another user
+pub async fn apply(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    operation: Operation,
+) -> Result<bool, BackendError> {
+    match &operation.kind {
+        OperationKind::CreateCounter(counter) if counter.owner_uuid != user => {
+            Err(BackendError::Unauthorized)?
+        }
+        OperationKind::CreatePhase(phase) if phase.owner_uuid != user => {
+            Err(BackendError::Unauthorized)?
+        }
+        OperationKind::CreatePhase(phase) => check_owner(tx, user, phase.parent_uuid).await?,
+        OperationKind::CreateCounter(_) => {}
+        _ => check_owner(tx, user, operation.countable_uuid).await?,
+    }
+
+    let inserted = sqlx::query!(
+        r#"
+        INSERT INTO operations (uuid, owner_uuid, countable_uuid, device_id, clock, kind, amount, value)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (uuid) DO NOTHING
+        "#,
+        operation.uuid,
+        user,
+        operation.countable_uuid,
+        operation.device_id,
+        operation.clock,
+        operation.kind.as_str(),
+        operation.kind.amount(),
+        operation.kind.value(),
+    )
+    .execute(&mut **tx)
+    .await?
+    .rows_affected()
+        > 0;
+
+    if !inserted {
+        return Ok(false);
+    }
+
+    let key = operation.countable_uuid;
+    match operation.kind {
+        OperationKind::CreateCounter(counter) => {
+            if let Err(BackendError::CounterNotFound) = counter::get(tx, key).await {
+                counter::update(tx, counter).await?
+            }
+        }
+        OperationKind::CreatePhase(phase) => {
+            if let Err(BackendError::DataNotFound(_)) = phase::get(tx, key).await {
+                let parent = phase.parent_uuid;
+                phase::update(tx, phase).await?;
+                counter::edited(tx, parent).await?;
+            }
+        }
+        OperationKind::AddCount(amount) => {
+            let phase = target_phase(tx, key).await?;
+            phase::add_count(tx, phase, amount).await?
+        }
+        OperationKind::AddTime(amount) => {
+            let phase = target_phase(tx, key).await?;
+            phase::add_time(tx, phase, amount).await?
+        }
+        kind if is_superseded(tx, &operation.uuid, key, kind.as_str()).await? => {}
+        OperationKind::Rename(name) => match counter::get(tx, key).await {
+            Ok(_) => counter::set_name(tx, key, &name).await?,
+            Err(_) => phase::set_name(tx, key, &name).await?,
+        },
+        OperationKind::SetHunttype(hunttype, params) => match counter::get(tx, key).await {
+            Ok(_) => {
+                counter::set_hunttype(tx, key, hunttype).await?;
+                counter::set_method_params(tx, key, params).await?;
+                counter::edited(tx, key).await?;
+            }
+            Err(_) => {
+                phase::set_hunttype(tx, key, hunttype).await?;
+                phase::set_method_params(tx, key, params).await?
+            }
+        },
+        OperationKind::SetSuccess(success) => {
+            let phase = target_phase(tx, key).await?;
+            phase::set_success(tx, phase, success).await?
+        }
+        OperationKind::SetStepSize(step) => {
+            let phase = target_phase(tx, key).await?;
+            phase::set_step(tx, phase, step).await?
+        }
+        OperationKind::SetCharm(has_charm) => match counter::get(tx, key).await {
+            Ok(_) => {
+                counter::set_charm(tx, key, has_charm).await?;
+                counter::edited(tx, key).await?;
+            }
+            Err(_) => phase::set_charm(tx, key, has_charm).await?,
+        },
+        OperationKind::SetGame(game) => match counter::get(tx, key).await {
+            Ok(_) => {
+                counter::set_game(tx, key, game).await?;
+                counter::edited(tx, key).await?;
+            }
+            Err(_) => phase::set_game(tx, key, game).await?,
+        },
+        OperationKind::SetTarget(target) => {
+            // only counters have a target
+            counter::get(tx, key).await?;
+            counter::set_target(tx, key, target).await?
+        }
+        OperationKind::Archive => match counter::get(tx, key).await {
+            Ok(_) => counter::archive(tx, key).await?,
+            Err(_) => phase::archive(tx, key).await?,
+        },
+    }
+
+    Ok(true)
+}
+
+/// Applies `operation` inside a savepoint so an operation that fails
+/// only undoes its own changes and the transaction can go on
+///
+/// # Returns
+/// The result of [`apply`], `Err` when this operation could not be applied
+///
+/// # Errors
+/// When the savepoint can not be created or rolled back
+pub async fn apply_isolated(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    operation: Operation,
+) -> Result<Result<bool, BackendError>, BackendError> {
+    sqlx::query("SAVEPOINT apply_operation")
+        .execute(&mut **tx)
+        .await?;
+
+    match apply(tx, user, operation).await {
+        Ok(applied) => {
+            sqlx::query("RELEASE SAVEPOINT apply_operation")
+                .execute(&mut **tx)
+                .await?;
+            Ok(Ok(applied))
+        }
+        Err(err) => {
+            sqlx::query("ROLLBACK TO SAVEPOINT apply_operation")
+                .execute(&mut **tx)
+                .await?;
+            Ok(Err(err))
+        }
+    }
+}
+
+/// Phases are changed directly, counters change their newest phase
+async fn target_phase(tx: &mut PgTx, key: uuid::Uuid) -> Result<uuid::Uuid, BackendError> {
+    match counter::get(tx, key).await {
+        Ok(_) => counter::get_children(tx, key)
+            .await?
+            .pop()
+            .map(|p| p.uuid)
+            .ok_or(BackendError::DataNotFound(String::from("phase"))),
+        Err(BackendError::CounterNotFound) => Ok(key),
+        Err(err) => Err(err),
+    }
+}
+
+/// Whether an operation of the same `kind` that orders after `operation` was already recorded
+async fn is_superseded(
+    tx: &mut PgTx,
+    operation: &uuid::Uuid,
+    countable: uuid::Uuid,
+    kind: &str,
+) -> Result<bool, BackendError> {
+    let superseded = sqlx::query_scalar!(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM operations AS later, operations AS this
+            WHERE this.uuid = $1
+              AND later.countable_uuid = $2
+              AND later.kind = $3
+              AND (later.clock, later.device_id) > (this.clock, this.device_id)
+        )
+        "#,
+        operation,
+        countable,
+        kind,
+    )
+    .fetch_one(&mut **tx)
+    .await?;
+
+    Ok(superseded.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn operation(
+        countable: uuid::Uuid,
+        device: uuid::Uuid,
+        clock: i64,
+        kind: OperationKind,
+    ) -> Operation {
+        Operation {
+            uuid: uuid::Uuid::new_v4(),
+            countable_uuid: countable,
+            device_id: device,
+            clock,
+            kind,
+        }
+    }
+
+    async fn phase(pool: &PgPool, key: uuid::Uuid) -> DbPhase {
+        let mut tx = pool.begin().await.unwrap();
+        phase::get(&mut tx, key).await.unwrap()
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn concurrent_counts_add_up(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, user).await;
+        let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+
+        // both devices were offline and counted from the same clock
+        let mut tx = pool.begin().await.unwrap();
+        apply(
+            &mut tx,
+            user,
+            operation(key, a, 1, OperationKind::AddCount(3)),
+        )
+        .await
+        .unwrap();
+        apply(
+            &mut tx,
+            user,
+            operation(key, b, 1, OperationKind::AddCount(4)),
+        )
+        .await
+        .unwrap();
+        apply(
+            &mut tx,
+            user,
+            operation(key, b, 2, OperationKind::AddTime(500)),
+        )
+        .await
+        .unwrap();
+        apply(
+            &mut tx,
+            user,
+            operation(key, a, 1, OperationKind::AddTime(250)),
+        )
+        .await
+        .unwrap();
+        tx.commit().await.unwrap();
+
+        let phase = phase(&pool, key).await;
+        assert_eq!(phase.count, 7);
+        assert_eq!(phase.time, 750);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn later_writer_wins(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, user).await;
+        let (low, high) = {
+            let mut devices = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()];
+            devices.sort();
+            (devices[0], devices[1])
+        };
+
+        let mut tx = pool.begin().await.unwrap();
+        let rename = |device, clock, name: &str| {
+            operation(key, device, clock, OperationKind::Rename(name.into()))
+        };
+        apply(&mut tx, user, rename(low, 2, "newest"))
+            .await
+            .unwrap();
+        // arrives later but was made before
+        apply(&mut tx, user, rename(high, 1, "older"))
+            .await
+            .unwrap();
+        assert_eq!(phase::get(&mut tx, key).await.unwrap().name, "newest");
+
+        // the same clock is decided by the device
+        apply(&mut tx, user, rename(high, 2, "higher device"))
+            .await
+            .unwrap();
+        apply(&mut tx, user, rename(low, 2, "lower device"))
+            .await
+            .unwrap();
+        assert_eq!(
+            phase::get(&mut tx, key).await.unwrap().name,
+            "higher device"
+        );
+
+        // other kinds do not supersede each other
+        apply(
+            &mut tx,
+            user,
+            operation(key, low, 1, OperationKind::SetSuccess(true)),
+        )
+        .await
+        .unwrap();
+        assert!(phase::get(&mut tx, key).await.unwrap().success);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn replaying_is_a_no_op(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, user).await;
+        let device = uuid::Uuid::new_v4();
+        let count = operation(key, device, 1, OperationKind::AddCount(5));
+        let replay = Operation {
+            kind: OperationKind::AddCount(5),
+            ..count
+        };
+        let replay_again = Operation {
+            kind: OperationKind::AddCount(5),
+            ..replay
+        };
+
+        let mut tx = pool.begin().await.unwrap();
+        assert!(apply(&mut tx, user, count).await.unwrap());
+        assert!(!apply(&mut tx, user, replay).await.unwrap());
+        assert!(!apply_isolated(&mut tx, user, replay_again)
+            .await
+            .unwrap()
+            .unwrap());
+        tx.commit().await.unwrap();
+
+        assert_eq!(phase(&pool, key).await.count, 5);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn counters_change_their_newest_phase(pool: PgPool) {
+        let user = testing::user(&pool).await;
+        let (counter, first) = testing::hunt(&pool, user).await;
+
+        let mut tx = pool.begin().await.unwrap();
+        let mut newer = testing::phase(user, counter);
+        newer.created_at += chrono::Duration::seconds(1);
+        let create = operation(
+            newer.uuid,
+            SERVER_DEVICE,
+            1,
+            OperationKind::CreatePhase(newer),
+        );
+        let newer = create.countable_uuid;
+        apply(&mut tx, user, create).await.unwrap();
+
+        assert_eq!(target_phase(&mut tx, counter).await.unwrap(), newer);
+        assert_eq!(target_phase(&mut tx, first).await.unwrap(), first);
+        record(&mut tx, user, counter, OperationKind::AddCount(2))
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        assert_eq!(phase(&pool, newer).await.count, 2);
+        assert_eq!(phase(&pool, first).await.count, 0);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn countables_of_others_are_rejected(pool: PgPool) {
+        let owner = testing::user(&pool).await;
+        let other = testing::user(&pool).await;
+        let (_, key) = testing::hunt(&pool, owner).await;
+
+        let mut tx = pool.begin().await.unwrap();
+        let count = operation(key, uuid::Uuid::new_v4(), 1, OperationKind::AddCount(1));
+        assert!(matches!(
+            apply_isolated(&mut tx, other, count).await.unwrap(),
+            Err(BackendError::Unauthorized)
+        ));
+        // the transaction goes on after the rejected operation
+        record(&mut tx, owner, key, OperationKind::AddCount(1))
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        assert_eq!(phase(&pool, key).await.count, 1);
+    }
+}
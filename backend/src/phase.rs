@@ -1,18 +1,27 @@
 use super::*;
 
 pub async fn edited(tx: &mut PgTx, key: uuid::Uuid) -> Result<(), BackendError> {
-    sqlx::query!(
+    let parent = sqlx::query_scalar!(
         r#"
         UPDATE phases
-        SET last_edit = $2
+        SET
+            last_edit = $2,
+            revision = revision + 1
         WHERE uuid = $1
+        RETURNING parent_uuid
         "#,
         key,
         chrono::Utc::now().naive_utc(),
     )
-    .execute(&mut **tx)
+    .fetch_optional(&mut **tx)
     .await?;
 
+    changes::track(tx, key).await?;
+    // listeners receive the whole counter so notify the parent instead
+    if let Some(parent) = parent {
+        changes::notify(tx, parent).await?;
+    }
+
     Ok(())
 }
 
@@ -29,12 +38,18 @@ pub async fn all_by_user(tx: &mut PgTx, user: uuid::Uuid) -> Result<Vec<DbPhase>
             time,
             has_charm,
             hunt_type as "hunt_type: Hunttype",
+            game as "game: Game",
+            research_level,
+            outbreak_cleared,
+            sparkling_power,
+            custom_method,
             dexnav_encounters,
             success,
             last_edit,
             created_at,
             is_deleted,
-            step_size
+            step_size,
+            revision
             FROM phases
         where owner_uuid = $1;
         "#,
@@ -46,6 +61,92 @@ pub async fn all_by_user(tx: &mut PgTx, user: uuid::Uuid) -> Result<Vec<DbPhase>
     Ok(phases)
 }
 
+/// Phases of `user` changed at or after `cursor`, see [`changes::cursor`]
+pub async fn changed_since(
+    tx: &mut PgTx,
+    user: uuid::Uuid,
+    cursor: i64,
+) -> Result<Vec<DbPhase>, BackendError> {
+    let phases = sqlx::query_as!(
+        DbPhase,
+        r#"
+        SELECT 
+            uuid,
+            owner_uuid,
+            parent_uuid,
+            name,
+            count,
+            time,
+            has_charm,
+            hunt_type as "hunt_type: Hunttype",
+            game as "game: Game",
+            research_level,
+            outbreak_cleared,
+            sparkling_power,
+            custom_method,
+            dexnav_encounters,
+            success,
+            last_edit,
+            created_at,
+            is_deleted,
+            step_size,
+            revision
+            FROM phases
+        WHERE owner_uuid = $1
+          AND uuid IN (
+            SELECT uuid FROM countable_changes
+            WHERE owner_uuid = $1 AND xid >= $2
+          );
+        "#,
+        user,
+        cursor,
+    )
+    .fetch_all(&mut **tx)
+    .await?;
+
+    Ok(phases)
+}
+
+pub async fn get(tx: &mut PgTx, key: uuid::Uuid) -> Result<DbPhase, BackendError> {
+    let phase = sqlx::query_as!(
+        DbPhase,
+        r#"
+        SELECT 
+            uuid,
+            owner_uuid,
+            parent_uuid,
+            name,
+            count,
+            time,
+            has_charm,
+            hunt_type as "hunt_type: Hunttype",
+            game as "game: Game",
+            research_level,
+            outbreak_cleared,
+            sparkling_power,
+            custom_method,
+            dexnav_encounters,
+            success,
+            last_edit,
+            created_at,
+            is_deleted,
+            step_size,
+            revision
+            FROM phases
+        WHERE uuid = $1;
+        "#,
+        key,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::RowNotFound => BackendError::DataNotFound(String::from("phase")),
+        err => err.into(),
+    })?;
+
+    Ok(phase)
+}
+
 pub async fn set_name(tx: &mut PgTx, key: uuid::Uuid, name: &str) -> Result<(), BackendError> {
     sqlx::query!(
         r#"
@@ -100,6 +201,66 @@ pub async fn set_time(tx: &mut PgTx, key: uuid::Uuid, time: i64) -> Result<(), B
     Ok(())
 }
 
+/// Adds `amount` to the count, the count does not go below zero
+pub async fn add_count(tx: &mut PgTx, key: uuid::Uuid, amount: i32) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        UPDATE phases
+        SET count = GREATEST(count + $2, 0)
+        WHERE uuid = $1
+        "#,
+        key,
+        amount,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    edited(tx, key).await?;
+
+    Ok(())
+}
+
+/// Adds `amount` milliseconds to the time, the time does not go below zero
+pub async fn add_time(tx: &mut PgTx, key: uuid::Uuid, amount: i64) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        UPDATE phases
+        SET time = GREATEST(time + $2, 0)
+        WHERE uuid = $1
+        "#,
+        key,
+        amount,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    edited(tx, key).await?;
+
+    Ok(())
+}
+
+pub async fn set_success(
+    tx: &mut PgTx,
+    key: uuid::Uuid,
+    success: bool,
+) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        UPDATE phases
+        SET success = $2
+        WHERE uuid = $1
+        "#,
+        key,
+        success,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    edited(tx, key).await?;
+
+    Ok(())
+}
+
 pub async fn set_step(tx: &mut PgTx, key: uuid::Uuid, step: i32) -> Result<(), BackendError> {
     sqlx::query!(
         r#"
@@ -140,6 +301,35 @@ pub async fn set_hunttype(
     Ok(())
 }
 
+pub async fn set_method_params(
+    tx: &mut PgTx,
+    key: uuid::Uuid,
+    params: MethodParams,
+) -> Result<(), BackendError> {
+    sqlx::query!(
+        r#"
+        UPDATE phases
+        SET
+            research_level = $2,
+            outbreak_cleared = $3,
+            sparkling_power = $4,
+            custom_method = $5
+        WHERE uuid = $1
+        "#,
+        key,
+        params.research_level,
+        params.outbreak_cleared,
+        params.sparkling_power,
+        params.custom_method,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    edited(tx, key).await?;
+
+    Ok(())
+}
+
 pub async fn set_charm(
     tx: &mut PgTx,
     key: uuid::Uuid,
@@ -162,11 +352,39 @@ pub async fn set_charm(
     Ok(())
 }
 
-pub async fn update(tx: &mut PgTx, phase: DbPhase) -> Result<(), BackendError> {
+pub async fn set_game(
+    tx: &mut PgTx,
+    key: uuid::Uuid,
+    game: Option<Game>,
+) -> Result<(), BackendError> {
     sqlx::query!(
         r#"
-        INSERT INTO phases (uuid, owner_uuid, parent_uuid, name, count, time, hunt_type, has_charm, success, dexnav_encounters, created_at, is_deleted)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        UPDATE phases
+        SET game = $2
+        WHERE uuid = $1
+        "#,
+        key,
+        game as Option<Game>,
+    )
+    .execute(&mut **tx)
+    .await?;
+
+    edited(tx, key).await?;
+
+    Ok(())
+}
+
+/// Inserts `phase` or updates it when `phase.revision` is still the current revision
+///
+/// # Errors
+/// `BackendError::Conflict` with the current phase when it was changed in the meantime,
+/// `BackendError::Unauthorized` when a phase of another user has the same uuid
+pub async fn update(tx: &mut PgTx, phase: DbPhase) -> Result<(), BackendError> {
+    let key = phase.uuid;
+    let updated = sqlx::query!(
+        r#"
+        INSERT INTO phases (uuid, owner_uuid, parent_uuid, name, count, time, hunt_type, has_charm, success, dexnav_encounters, created_at, is_deleted, game, research_level, outbreak_cleared, sparkling_power, custom_method)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $14, $15, $16, $17, $18)
         ON CONFLICT (uuid) DO UPDATE
         SET
             name = $4,
@@ -176,7 +394,14 @@ pub async fn update(tx: &mut PgTx, phase: DbPhase) -> Result<(), BackendError> {
             has_charm = $8,
             success = $9,
             dexnav_encounters = $10,
-            is_deleted = $12
+            is_deleted = $12,
+            game = $14,
+            research_level = $15,
+            outbreak_cleared = $16,
+            sparkling_power = $17,
+            custom_method = $18
+        WHERE phases.revision = $13
+          AND phases.owner_uuid = EXCLUDED.owner_uuid
         "#,
         phase.uuid,
         phase.owner_uuid,
@@ -190,11 +415,30 @@ pub async fn update(tx: &mut PgTx, phase: DbPhase) -> Result<(), BackendError> {
         phase.dexnav_encounters,
         phase.created_at,
         phase.is_deleted,
+        phase.revision,
+        phase.game as Option<Game>,
+        phase.research_level,
+        phase.outbreak_cleared,
+        phase.sparkling_power,
+        phase.custom_method,
     )
     .execute(&mut **tx)
-    .await?;
+    .await?
+    .rows_affected()
+        > 0;
 
-    edited(tx, phase.uuid).await?;
+    if !updated {
+        let current = get(tx, key).await?;
+        // the uuid is taken by another user, their phase must not be sent back
+        if current.owner_uuid != phase.owner_uuid {
+            return Err(BackendError::Unauthorized);
+        }
+        return Err(BackendError::Conflict(Box::new(ServerVersion::Phase(
+            current,
+        ))));
+    }
+
+    edited(tx, key).await?;
 
     Ok(())
 }
@@ -0,0 +1,328 @@
+use super::*;
+
+/// Tuning for the login attempt limiter
+///
+/// All values can be overridden with environment variables,
+/// see [LimiterConfig::from_env] for the variable names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimiterConfig {
+    /// failed attempts allowed before any backoff is applied
+    pub free_attempts: i32,
+    /// wait after the first failure past `free_attempts`, it doubles with every next failure
+    pub base_backoff: chrono::Duration,
+    pub max_backoff: chrono::Duration,
+    /// failed attempts after which a key is locked out for `lockout`
+    pub lockout_attempts: i32,
+    pub lockout: chrono::Duration,
+    /// failures older than this are forgotten
+    pub reset_after: chrono::Duration,
+    /// use the `Forwarded`/`X-Forwarded-For` headers for the client ip,
+    /// only enable this when running behind a trusted reverse proxy
+    pub trust_forwarded: bool,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            free_attempts: 3,
+            base_backoff: chrono::Duration::seconds(1),
+            max_backoff: chrono::Duration::minutes(5),
+            lockout_attempts: 10,
+            lockout: chrono::Duration::minutes(15),
+            reset_after: chrono::Duration::hours(24),
+            trust_forwarded: false,
+        }
+    }
+}
+
+impl LimiterConfig {
+    /// Reads the following environment variables falling back to [LimiterConfig::default]
+    ///   * `LOGIN_FREE_ATTEMPTS`
+    ///   * `LOGIN_BACKOFF_SECS`
+    ///   * `LOGIN_MAX_BACKOFF_SECS`
+    ///   * `LOGIN_LOCKOUT_ATTEMPTS`
+    ///   * `LOGIN_LOCKOUT_MINS`
+    ///   * `LOGIN_RESET_HOURS`
+    ///   * `LOGIN_TRUST_FORWARDED`
+    pub fn from_env() -> Self {
+        fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+            std::env::var(key)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        }
+
+        let default = Self::default();
+
+        Self {
+            free_attempts: env_or("LOGIN_FREE_ATTEMPTS", default.free_attempts),
+            base_backoff: chrono::Duration::seconds(env_or(
+                "LOGIN_BACKOFF_SECS",
+                default.base_backoff.num_seconds(),
+            )),
+            max_backoff: chrono::Duration::seconds(env_or(
+                "LOGIN_MAX_BACKOFF_SECS",
+                default.max_backoff.num_seconds(),
+            )),
+            lockout_attempts: env_or("LOGIN_LOCKOUT_ATTEMPTS", default.lockout_attempts),
+            lockout: chrono::Duration::minutes(env_or(
+                "LOGIN_LOCKOUT_MINS",
+                default.lockout.num_minutes(),
+            )),
+            reset_after: chrono::Duration::hours(env_or(
+                "LOGIN_RESET_HOURS",
+                default.reset_after.num_hours(),
+            )),
+            trust_forwarded: env_or("LOGIN_TRUST_FORWARDED", default.trust_forwarded),
+        }
+    }
+
+    /// The wait required after `failures` failed attempts before the next attempt is allowed
+    pub fn backoff(&self, failures: i32) -> chrono::Duration {
+        let over = failures - self.free_attempts;
+        if over <= 0 {
+            return chrono::Duration::zero();
+        }
+
+        let factor = 2_i32.saturating_pow((over - 1).min(30) as u32);
+        (self.base_backoff * factor).min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptKey {
+    Ip(String),
+    Username(String),
+}
+
+impl std::fmt::Display for AttemptKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttemptKey::Ip(ip) => write!(f, "ip:{ip}"),
+            AttemptKey::Username(name) => write!(f, "user:{name}"),
+        }
+    }
+}
+
+struct DbLoginAttempt {
+    failures: i32,
+    last_failure: chrono::NaiveDateTime,
+    locked_until: Option<chrono::NaiveDateTime>,
+}
+
+fn to_db_keys(keys: &[AttemptKey]) -> Vec<String> {
+    keys.iter().map(|k| k.to_string()).collect()
+}
+
+/// Returns an error when any of the `keys` is currently locked out or still backing off
+pub async fn check(
+    pool: &PgPool,
+    config: &LimiterConfig,
+    keys: &[AttemptKey],
+) -> Result<(), BackendError> {
+    let now = chrono::Utc::now().naive_utc();
+
+    let attempts = sqlx::query_as!(
+        DbLoginAttempt,
+        r#"
+        SELECT failures, last_failure, locked_until FROM login_attempts
+        WHERE key = ANY($1) AND last_failure > $2
+        "#,
+        &to_db_keys(keys),
+        now - config.reset_after,
+    )
+    .fetch_all(pool)
+    .await?;
+
+    evaluate(config, now, &attempts).map_err(BackendError::from)
+}
+
+/// The outcome of [check] for the stored `attempts` of all keys at `now`,
+/// the longest wait of all keys is reported
+fn evaluate(
+    config: &LimiterConfig,
+    now: chrono::NaiveDateTime,
+    attempts: &[DbLoginAttempt],
+) -> Result<(), LoginError> {
+    let mut result = Ok(());
+
+    for attempt in attempts
+        .iter()
+        .filter(|a| a.last_failure > now - config.reset_after)
+    {
+        match attempt.locked_until {
+            Some(until) if until > now => Err(LoginError::LockedOut {
+                retry_after: until - now,
+            })?,
+            _ => {}
+        }
+
+        let retry_after = attempt.last_failure + config.backoff(attempt.failures) - now;
+        if retry_after > chrono::Duration::zero() {
+            result = match result {
+                Err(LoginError::TooManyAttempts { retry_after: other }) if other > retry_after => {
+                    result
+                }
+                _ => Err(LoginError::TooManyAttempts { retry_after }),
+            }
+        }
+    }
+
+    result
+}
+
+/// Counts a failed attempt for all `keys`, locking them out once `lockout_attempts` is reached
+pub async fn register_failure(
+    pool: &PgPool,
+    config: &LimiterConfig,
+    keys: &[AttemptKey],
+) -> Result<(), BackendError> {
+    let now = chrono::Utc::now().naive_utc();
+
+    for key in to_db_keys(keys) {
+        let attempt = sqlx::query_as!(
+            DbLoginAttempt,
+            r#"
+            INSERT INTO login_attempts (key, failures, last_failure)
+            VALUES ($1, 1, $2)
+            ON CONFLICT (key) DO UPDATE
+            SET
+                failures = CASE
+                    WHEN login_attempts.last_failure < $3 THEN 1
+                    ELSE login_attempts.failures + 1
+                END,
+                last_failure = $2
+            RETURNING failures, last_failure, locked_until
+            "#,
+            key,
+            now,
+            now - config.reset_after,
+        )
+        .fetch_one(pool)
+        .await?;
+
+        if attempt.failures >= config.lockout_attempts {
+            sqlx::query!(
+                r#"
+                UPDATE login_attempts
+                SET
+                    failures = 0,
+                    locked_until = $2
+                WHERE key = $1
+                "#,
+                key,
+                now + config.lockout,
+            )
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Forgets earlier failures after a successful login,
+/// only username keys are cleared so one valid account can not reset an ip
+pub async fn register_success(pool: &PgPool, keys: &[AttemptKey]) -> Result<(), BackendError> {
+    let keys = keys
+        .iter()
+        .filter(|k| matches!(k, AttemptKey::Username(_)))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    sqlx::query!(
+        r#"
+        DELETE FROM login_attempts
+        WHERE key = ANY($1)
+        "#,
+        &to_db_keys(&keys),
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let config = LimiterConfig::default();
+
+        assert_eq!(config.backoff(0), chrono::Duration::zero());
+        assert_eq!(config.backoff(3), chrono::Duration::zero());
+        assert_eq!(config.backoff(4), chrono::Duration::seconds(1));
+        assert_eq!(config.backoff(5), chrono::Duration::seconds(2));
+        assert_eq!(config.backoff(8), chrono::Duration::seconds(16));
+        assert_eq!(config.backoff(40), config.max_backoff);
+    }
+
+    fn attempt(
+        failures: i32,
+        failed_ago: chrono::Duration,
+        locked_for: Option<chrono::Duration>,
+        now: chrono::NaiveDateTime,
+    ) -> DbLoginAttempt {
+        DbLoginAttempt {
+            failures,
+            last_failure: now - failed_ago,
+            locked_until: locked_for.map(|d| now + d),
+        }
+    }
+
+    #[test]
+    fn lockout_holds_until_its_window_ends() {
+        let config = LimiterConfig::default();
+        let now = chrono::Utc::now().naive_utc();
+
+        let locked = attempt(0, chrono::Duration::zero(), Some(config.lockout), now);
+        assert!(matches!(
+            evaluate(&config, now, &[locked]),
+            Err(LoginError::LockedOut { retry_after }) if retry_after == config.lockout
+        ));
+
+        let almost = attempt(0, config.lockout, Some(chrono::Duration::seconds(1)), now);
+        assert!(matches!(
+            evaluate(&config, now, &[almost]),
+            Err(LoginError::LockedOut { .. })
+        ));
+
+        let expired = attempt(0, config.lockout, Some(chrono::Duration::seconds(-1)), now);
+        assert!(evaluate(&config, now, &[expired]).is_ok());
+    }
+
+    #[test]
+    fn backoff_waits_for_the_longest_key() {
+        let config = LimiterConfig::default();
+        let now = chrono::Utc::now().naive_utc();
+
+        let free = attempt(config.free_attempts, chrono::Duration::zero(), None, now);
+        assert!(evaluate(&config, now, &[free]).is_ok());
+
+        let short = attempt(4, chrono::Duration::zero(), None, now);
+        let long = attempt(6, chrono::Duration::zero(), None, now);
+        assert!(matches!(
+            evaluate(&config, now, &[short, long]),
+            Err(LoginError::TooManyAttempts { retry_after }) if retry_after == config.backoff(6)
+        ));
+
+        let waited = attempt(6, config.backoff(6), None, now);
+        assert!(evaluate(&config, now, &[waited]).is_ok());
+    }
+
+    #[test]
+    fn old_failures_are_forgotten() {
+        let config = LimiterConfig::default();
+        let now = chrono::Utc::now().naive_utc();
+
+        let old = attempt(
+            config.lockout_attempts,
+            config.reset_after + chrono::Duration::seconds(1),
+            Some(config.lockout),
+            now,
+        );
+        assert!(evaluate(&config, now, &[old]).is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+//! Fixtures for tests that run against a database created by `sqlx::test`
+
+use super::*;
+
+/// A user without password, only for owning data
+pub async fn user(pool: &PgPool) -> uuid::Uuid {
+    sqlx::query_scalar("INSERT INTO users (username, password) VALUES ($1, '') RETURNING uuid")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .fetch_one(pool)
+        .await
+        .unwrap()
+}
+
+pub fn counter(owner: uuid::Uuid) -> DbCounter {
+    let now = chrono::Utc::now().naive_utc();
+    DbCounter {
+        uuid: uuid::Uuid::new_v4(),
+        owner_uuid: owner,
+        name: String::from("Counter"),
+        created_at: now,
+        last_edit: now,
+        is_deleted: false,
+        revision: 0,
+        target: None,
+    }
+}
+
+pub fn phase(owner: uuid::Uuid, parent: uuid::Uuid) -> DbPhase {
+    let now = chrono::Utc::now().naive_utc();
+    DbPhase {
+        uuid: uuid::Uuid::new_v4(),
+        owner_uuid: owner,
+        parent_uuid: parent,
+        name: String::from("Phase"),
+        count: 0,
+        time: 0,
+        hunt_type: Hunttype::NewOdds,
+        game: None,
+        research_level: 0,
+        outbreak_cleared: 0,
+        sparkling_power: 0,
+        custom_method: None,
+        has_charm: false,
+        dexnav_encounters: None,
+        success: false,
+        created_at: now,
+        last_edit: now,
+        is_deleted: false,
+        step_size: 1,
+        revision: 0,
+    }
+}
+
+/// Stores a counter of `owner` with a single phase and returns both ids
+pub async fn hunt(pool: &PgPool, owner: uuid::Uuid) -> (uuid::Uuid, uuid::Uuid) {
+    let mut tx = pool.begin().await.unwrap();
+    let counter = counter(owner);
+    let phase = phase(owner, counter.uuid);
+    let keys = (counter.uuid, phase.uuid);
+    counter::update(&mut tx, counter).await.unwrap();
+    phase::update(&mut tx, phase).await.unwrap();
+    tx.commit().await.unwrap();
+
+    keys
+}
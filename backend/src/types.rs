@@ -11,6 +11,15 @@ pub enum Hunttype {
     MasudaGenIV,
     MasudaGenV,
     MasudaGenVI,
+    MassOutbreak,
+    MassiveMassOutbreak,
+    Outbreak,
+    ChainFishing,
+    Horde,
+    RadarGenIV,
+    RadarGenVI,
+    RadarBDSP,
+    Custom,
 }
 
 impl From<String> for Hunttype {
@@ -23,11 +32,76 @@ impl From<String> for Hunttype {
             "MasudaGenIV" => Self::MasudaGenIV,
             "MasudaGenV" => Self::MasudaGenV,
             "MasudaGenVI" => Self::MasudaGenVI,
+            "MassOutbreak" => Self::MassOutbreak,
+            "MassiveMassOutbreak" => Self::MassiveMassOutbreak,
+            "Outbreak" => Self::Outbreak,
+            "ChainFishing" => Self::ChainFishing,
+            "Horde" => Self::Horde,
+            "RadarGenIV" => Self::RadarGenIV,
+            "RadarGenVI" => Self::RadarGenVI,
+            "RadarBDSP" => Self::RadarBDSP,
+            "Custom" => Self::Custom,
             _ => Self::NewOdds,
         }
     }
 }
 
+impl DbPhase {
+    pub fn method_params(&self) -> MethodParams {
+        MethodParams {
+            research_level: self.research_level,
+            outbreak_cleared: self.outbreak_cleared,
+            sparkling_power: self.sparkling_power,
+            custom_method: self.custom_method,
+        }
+    }
+}
+
+impl std::fmt::Display for Hunttype {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // same names as accepted by `From<String>`
+        write!(f, "{self:?}")
+    }
+}
+
+/// Values of hunting methods whose odds depend on more than the count
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MethodParams {
+    /// 0 below level 10, 1 level 10, 2 perfect
+    pub research_level: i16,
+    pub outbreak_cleared: i32,
+    pub sparkling_power: i16,
+    /// `custom_method::DbCustomMethod` of a `Custom` hunt type
+    pub custom_method: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, Clone, Copy, sqlx::Type)]
+#[sqlx(type_name = "game")]
+pub enum Game {
+    GoldSilverCrystal,
+    RubySapphireEmerald,
+    FireRedLeafGreen,
+    DiamondPearlPlatinum,
+    HeartGoldSoulSilver,
+    BlackWhite,
+    Black2White2,
+    XY,
+    OmegaRubyAlphaSapphire,
+    SunMoon,
+    UltraSunUltraMoon,
+    LetsGo,
+    SwordShield,
+    BrilliantDiamondShiningPearl,
+    LegendsArceus,
+    ScarletViolet,
+}
+
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 #[derive(Debug, sqlx::FromRow)]
 pub struct DbCounter {
     pub uuid: uuid::Uuid,
@@ -36,9 +110,13 @@ pub struct DbCounter {
     pub created_at: chrono::NaiveDateTime,
     pub last_edit: chrono::NaiveDateTime,
     pub is_deleted: bool,
+    /// incremented with every change, updates have to name the revision they are based on
+    pub revision: i32,
+    /// amount of shinies the hunt needs, `None` when every shiny is hunted on its own
+    pub target: Option<i32>,
 }
 
-#[derive(Debug, sqlx::FromRow)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct DbPhase {
     pub uuid: uuid::Uuid,
     pub owner_uuid: uuid::Uuid,
@@ -47,6 +125,11 @@ pub struct DbPhase {
     pub count: i32,
     pub time: i64,
     pub hunt_type: Hunttype,
+    pub game: Option<Game>,
+    pub research_level: i16,
+    pub outbreak_cleared: i32,
+    pub sparkling_power: i16,
+    pub custom_method: Option<uuid::Uuid>,
     pub has_charm: bool,
     pub dexnav_encounters: Option<i32>,
     pub success: bool,
@@ -54,6 +137,14 @@ pub struct DbPhase {
     pub last_edit: chrono::NaiveDateTime,
     pub is_deleted: bool,
     pub step_size: i32,
+    pub revision: i32,
+}
+
+/// Current state of a countable that was changed by someone else
+#[derive(Debug)]
+pub enum ServerVersion {
+    Counter(DbCounter),
+    Phase(DbPhase),
 }
 
 #[derive(Debug)]
@@ -130,6 +221,13 @@ pub struct DbPreferences {
     pub show_separator: bool,
     pub multi_select: bool,
     pub save_on_pause: bool,
+    pub milestone_half: bool,
+    pub milestone_odds: bool,
+    pub milestone_double_odds: bool,
+    pub milestone_counts: Vec<i32>,
+    pub milestone_notify: bool,
+    pub auto_advance: bool,
+    pub record_found: bool,
 }
 
 impl DbPreferences {
@@ -137,7 +235,21 @@ impl DbPreferences {
         let data = match query_as!(
             DbPreferences,
             r#"
-            select user_uuid, use_default_accent_color, accent_color, show_separator, multi_select, save_on_pause from preferences
+            select
+                user_uuid,
+                use_default_accent_color,
+                accent_color,
+                show_separator,
+                multi_select,
+                save_on_pause,
+                milestone_half,
+                milestone_odds,
+                milestone_double_odds,
+                milestone_counts,
+                milestone_notify,
+                auto_advance,
+                record_found
+            from preferences
             where user_uuid = $1
             "#,
             user_uuid,
@@ -162,6 +274,16 @@ impl DbPreferences {
         token: uuid::Uuid,
     ) -> Result<(), BackendError> {
         let user = auth::get_user(pool, username, token).await?;
+        Self {
+            user_uuid: user.uuid,
+            ..self
+        }
+        .db_save(pool)
+        .await
+    }
+
+    /// Same as [DbPreferences::db_set] for callers that already verified `user_uuid`
+    pub async fn db_save(self, pool: &PgPool) -> Result<(), BackendError> {
         query!(
             r#"
             INSERT INTO preferences (
@@ -170,22 +292,43 @@ impl DbPreferences {
                 accent_color,
                 show_separator,
                 multi_select,
-                save_on_pause
+                save_on_pause,
+                milestone_half,
+                milestone_odds,
+                milestone_double_odds,
+                milestone_counts,
+                milestone_notify,
+                auto_advance,
+                record_found
             )
-            VALUES ($1, $2, $3, $4, $5, $6)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             ON CONFLICT (user_uuid) DO UPDATE
                 SET use_default_accent_color = $2,
                     accent_color = $3,
                     show_separator = $4,
                     multi_select = $5,
-                    save_on_pause = $6
+                    save_on_pause = $6,
+                    milestone_half = $7,
+                    milestone_odds = $8,
+                    milestone_double_odds = $9,
+                    milestone_counts = $10,
+                    milestone_notify = $11,
+                    auto_advance = $12,
+                    record_found = $13
             "#,
-            user.uuid,
+            self.user_uuid,
             self.use_default_accent_color,
             self.accent_color,
             self.show_separator,
             self.multi_select,
             self.save_on_pause,
+            self.milestone_half,
+            self.milestone_odds,
+            self.milestone_double_odds,
+            &self.milestone_counts,
+            self.milestone_notify,
+            self.auto_advance,
+            self.record_found,
         )
         .execute(pool)
         .await?;
@@ -0,0 +1,359 @@
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use super::{config::Auth, CliError};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Counter {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub count: i32,
+    pub time: i64,
+    pub phases: Vec<uuid::Uuid>,
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Phase {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub count: i32,
+    pub time: i64,
+    pub hunt_type: String,
+    pub step_size: i32,
+    pub success: bool,
+    pub is_deleted: bool,
+}
+
+/// Counter or phase selected on the command line
+#[derive(Debug, Clone)]
+pub enum Countable {
+    Counter(Counter),
+    Phase(Phase),
+}
+
+impl Countable {
+    pub fn uuid(&self) -> uuid::Uuid {
+        match self {
+            Countable::Counter(c) => c.uuid,
+            Countable::Phase(p) => p.uuid,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Countable::Counter(c) => &c.name,
+            Countable::Phase(p) => &p.name,
+        }
+    }
+
+    pub fn count(&self) -> i32 {
+        match self {
+            Countable::Counter(c) => c.count,
+            Countable::Phase(p) => p.count,
+        }
+    }
+
+    pub fn time(&self) -> i64 {
+        match self {
+            Countable::Counter(c) => c.time,
+            Countable::Phase(p) => p.time,
+        }
+    }
+
+    fn path(&self) -> String {
+        match self {
+            Countable::Counter(c) => format!("counters/{}", c.uuid),
+            Countable::Phase(p) => format!("phases/{}", p.uuid),
+        }
+    }
+}
+
+/// Blocking client for the `/api/v1` routes
+pub struct Client {
+    agent: ureq::Agent,
+    server: String,
+    auth: Auth,
+}
+
+impl Client {
+    pub fn new(server: &str, auth: Auth) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().redirects(0).build(),
+            server: server.trim_end_matches('/').to_string(),
+            auth,
+        }
+    }
+
+    /// Logs in with a password and returns the `session` cookie to store
+    pub fn login(server: &str, username: &str, password: &str) -> Result<String, CliError> {
+        let agent = ureq::AgentBuilder::new().redirects(0).build();
+        let url = format!("{}/api/login_user", server.trim_end_matches('/'));
+
+        let resp = agent
+            .post(&url)
+            .send_form(&[
+                ("username", username),
+                ("password", password),
+                ("remember", "on"),
+            ])
+            .map_err(|err| match err {
+                ureq::Error::Status(_, resp) => {
+                    // server fn errors are encoded as `<Variant>|<message>`
+                    let body = resp.into_string().unwrap_or_default();
+                    let message = body.split_once('|').map(|(_, m)| m).unwrap_or(&body);
+                    CliError::Login(message.to_string())
+                }
+                err => err.into(),
+            })?;
+
+        resp.all("set-cookie")
+            .into_iter()
+            .find_map(|cookie| {
+                cookie
+                    .strip_prefix("session=")
+                    .map(|c| c.split(';').next().unwrap_or_default().to_string())
+            })
+            .ok_or(CliError::Login(String::from(
+                "Server did not return a session",
+            )))
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        self.authorized(
+            self.agent
+                .request(method, &format!("{}/api/v1/{path}", self.server)),
+        )
+    }
+
+    fn authorized(&self, req: ureq::Request) -> ureq::Request {
+        match &self.auth {
+            Auth::Token(token) => req.set("Authorization", &format!("Bearer {token}")),
+            Auth::Session(session) => req.set("Cookie", &format!("session={session}")),
+        }
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, CliError> {
+        Ok(self.request("GET", path).call()?.into_json()?)
+    }
+
+    fn send<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Value,
+    ) -> Result<T, CliError> {
+        Ok(self.request(method, path).send_json(body)?.into_json()?)
+    }
+
+    pub fn counters(&self, archived: bool) -> Result<Vec<Counter>, CliError> {
+        self.get(&format!("counters?archived={archived}"))
+    }
+
+    pub fn phases(&self, archived: bool) -> Result<Vec<Phase>, CliError> {
+        self.get(&format!("phases?archived={archived}"))
+    }
+
+    pub fn counter_phases(&self, counter: uuid::Uuid) -> Result<Vec<Phase>, CliError> {
+        self.get(&format!("counters/{counter}/phases"))
+    }
+
+    pub fn preferences(&self) -> Result<Value, CliError> {
+        self.get("preferences")
+    }
+
+    /// Finds a countable by uuid or by its (case insensitive) name,
+    /// counters take precedence over phases with the same name
+    pub fn resolve(&self, name_or_uuid: &str) -> Result<Countable, CliError> {
+        resolve_in(self.counters(false)?, self.phases(false)?, name_or_uuid)
+    }
+
+    /// Step size used by `increment` when no amount is given
+    pub fn step_size(&self, countable: &Countable) -> Result<i32, CliError> {
+        match countable {
+            Countable::Phase(p) => Ok(p.step_size),
+            Countable::Counter(c) => Ok(self
+                .counter_phases(c.uuid)?
+                .last()
+                .map(|p| p.step_size)
+                .unwrap_or(1)),
+        }
+    }
+
+    pub fn increment(
+        &self,
+        countable: &Countable,
+        amount: Option<i32>,
+    ) -> Result<Countable, CliError> {
+        let path = format!("{}/increment", countable.path());
+        let body = json!({ "amount": amount });
+        self.updated(countable, self.send("POST", &path, body)?)
+    }
+
+    /// Adds `millis` to the time of the countable
+    pub fn add_time(&self, countable: &Countable, millis: i64) -> Result<Countable, CliError> {
+        let path = format!("{}/time", countable.path());
+        let body = json!({ "millis": millis });
+        self.updated(countable, self.send("POST", &path, body)?)
+    }
+
+    /// Opens the server sent event stream that sends every counter of the user when it changes
+    pub fn events(&self) -> Result<impl std::io::BufRead, CliError> {
+        let req = self
+            .agent
+            .get(&format!("{}/api/session/events", self.server))
+            .set("Accept", "text/event-stream");
+
+        Ok(std::io::BufReader::new(
+            self.authorized(req).call()?.into_reader(),
+        ))
+    }
+
+    fn updated(&self, countable: &Countable, value: Value) -> Result<Countable, CliError> {
+        Ok(match countable {
+            Countable::Counter(_) => Countable::Counter(serde_json::from_value(value)?),
+            Countable::Phase(_) => Countable::Phase(serde_json::from_value(value)?),
+        })
+    }
+
+    /// Everything the account can read as a single json document
+    pub fn export(&self) -> Result<Value, CliError> {
+        let counters: Value = self.get("counters?archived=true")?;
+        let phases: Value = self.get("phases?archived=true")?;
+        let preferences = self.preferences()?;
+
+        Ok(json!({
+            "exported_at": chrono::Utc::now(),
+            "server": self.server,
+            "counters": counters,
+            "phases": phases,
+            "preferences": preferences,
+        }))
+    }
+}
+
+/// Name resolution of [`Client::resolve`] on already loaded countables
+fn resolve_in(
+    counters: Vec<Counter>,
+    phases: Vec<Phase>,
+    name_or_uuid: &str,
+) -> Result<Countable, CliError> {
+    if let Ok(uuid) = uuid::Uuid::parse_str(name_or_uuid) {
+        if let Some(c) = counters.iter().find(|c| c.uuid == uuid) {
+            return Ok(Countable::Counter(c.clone()));
+        }
+        if let Some(p) = phases.iter().find(|p| p.uuid == uuid) {
+            return Ok(Countable::Phase(p.clone()));
+        }
+        return Err(CliError::NotFound(name_or_uuid.to_string()));
+    }
+
+    let name = name_or_uuid.to_lowercase();
+    let mut matches = counters
+        .into_iter()
+        .filter(|c| c.name.to_lowercase() == name)
+        .map(Countable::Counter)
+        .collect::<Vec<_>>();
+    if matches.is_empty() {
+        matches = phases
+            .into_iter()
+            .filter(|p| p.name.to_lowercase() == name)
+            .map(Countable::Phase)
+            .collect();
+    }
+
+    match matches.len() {
+        0 => Err(CliError::NotFound(name_or_uuid.to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => Err(CliError::Ambiguous {
+            name: name_or_uuid.to_string(),
+            candidates: matches
+                .iter()
+                .map(|c| format!("  {} {}", c.uuid(), c.name()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counter(id: u128, name: &str) -> Counter {
+        Counter {
+            uuid: uuid::Uuid::from_u128(id),
+            name: name.to_string(),
+            count: 0,
+            time: 0,
+            phases: Vec::new(),
+            is_deleted: false,
+        }
+    }
+
+    fn phase(id: u128, name: &str) -> Phase {
+        Phase {
+            uuid: uuid::Uuid::from_u128(id),
+            name: name.to_string(),
+            count: 0,
+            time: 0,
+            hunt_type: String::from("NewOdds"),
+            step_size: 1,
+            success: false,
+            is_deleted: false,
+        }
+    }
+
+    fn resolve(name_or_uuid: &str) -> Result<Countable, CliError> {
+        resolve_in(
+            vec![
+                counter(1, "Shiny Eevee"),
+                counter(2, "Ralts"),
+                counter(3, "ralts"),
+            ],
+            vec![
+                phase(4, "Phase 1"),
+                phase(5, "Shiny Eevee"),
+                phase(6, "Outbreak"),
+            ],
+            name_or_uuid,
+        )
+    }
+
+    #[test]
+    fn resolves_uuids() {
+        let found = resolve(&uuid::Uuid::from_u128(2).to_string()).unwrap();
+        assert!(matches!(found, Countable::Counter(c) if c.name == "Ralts"));
+
+        let found = resolve(&uuid::Uuid::from_u128(5).to_string()).unwrap();
+        assert!(matches!(found, Countable::Phase(p) if p.name == "Shiny Eevee"));
+
+        let unknown = uuid::Uuid::from_u128(7).to_string();
+        assert!(matches!(resolve(&unknown), Err(CliError::NotFound(name)) if name == unknown));
+    }
+
+    #[test]
+    fn resolves_names_ignoring_case() {
+        let found = resolve("outbreak").unwrap();
+        assert_eq!(found.uuid(), uuid::Uuid::from_u128(6));
+        assert!(matches!(found, Countable::Phase(_)));
+
+        assert!(matches!(resolve("Phase 2"), Err(CliError::NotFound(_))));
+    }
+
+    #[test]
+    fn counters_take_precedence_over_phases() {
+        let found = resolve("shiny eevee").unwrap();
+        assert!(matches!(found, Countable::Counter(_)));
+        assert_eq!(found.uuid(), uuid::Uuid::from_u128(1));
+    }
+
+    #[test]
+    fn same_names_are_ambiguous() {
+        let Err(CliError::Ambiguous { candidates, .. }) = resolve("RALTS") else {
+            panic!("`RALTS` should match two counters");
+        };
+        assert!(candidates.contains(&uuid::Uuid::from_u128(2).to_string()));
+        assert!(candidates.contains(&uuid::Uuid::from_u128(3).to_string()));
+    }
+}
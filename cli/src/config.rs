@@ -0,0 +1,244 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{client::Countable, CliError};
+
+pub const DEFAULT_SERVER: &str = "http://127.0.0.1:3000";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Auth {
+    /// personal access token, sent as `Authorization: Bearer`
+    Token(String),
+    /// value of the `session` cookie returned by a password login
+    Session(String),
+}
+
+/// Persisted between invocations,
+/// stored in `$TALLYWEB_CONFIG` or `$XDG_CONFIG_HOME/tallyweb/cli.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub server: String,
+    pub auth: Option<Auth>,
+    /// start of every running timer, the elapsed time is added to the countable on stop
+    #[serde(default)]
+    pub timers: HashMap<uuid::Uuid, chrono::DateTime<chrono::Utc>>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            server: DEFAULT_SERVER.to_string(),
+            auth: None,
+            timers: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Result<PathBuf, CliError> {
+        if let Ok(path) = std::env::var("TALLYWEB_CONFIG") {
+            return Ok(PathBuf::from(path));
+        }
+
+        let dir = std::env::var("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::var("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .map_err(|_| CliError::Config(String::from("Could not find a config directory")))?;
+
+        Ok(dir.join("tallyweb").join("cli.json"))
+    }
+
+    pub fn load() -> Result<Self, CliError> {
+        Self::load_from(&Self::path()?)
+    }
+
+    pub fn save(&self) -> Result<(), CliError> {
+        self.save_to(&Self::path()?)
+    }
+
+    /// Falls back to the default config when there is no file at `path` yet
+    pub fn load_from(path: &Path) -> Result<Self, CliError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = std::fs::read_to_string(path)?;
+        serde_json::from_str(&data)
+            .map_err(|err| CliError::Config(format!("{}: {err}", path.display())))
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<(), CliError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        restrict_permissions(path)?;
+
+        Ok(())
+    }
+
+    pub fn start_timer(
+        &mut self,
+        countable: &Countable,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), CliError> {
+        if self.timers.contains_key(&countable.uuid()) {
+            return Err(CliError::TimerRunning(countable.name().to_string()));
+        }
+        self.timers.insert(countable.uuid(), now);
+
+        Ok(())
+    }
+
+    /// Milliseconds the timer of `countable` has been running for at `now`
+    pub fn elapsed(
+        &self,
+        countable: &Countable,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Result<i64, CliError> {
+        let started = self
+            .timers
+            .get(&countable.uuid())
+            .ok_or(CliError::TimerNotRunning(countable.name().to_string()))?;
+
+        Ok((now - *started).num_milliseconds())
+    }
+}
+
+/// the config holds credentials, keep it readable by the owner only
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<(), CliError> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_: &Path) -> Result<(), CliError> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::Phase;
+
+    /// Config file in a directory of its own that is removed again on drop
+    struct TempConfig(PathBuf);
+
+    impl TempConfig {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("tallyweb-cli-{}-{name}", std::process::id()));
+            Self(dir)
+        }
+
+        fn path(&self) -> PathBuf {
+            self.0.join("tallyweb").join("cli.json")
+        }
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn countable(id: u128) -> Countable {
+        Countable::Phase(Phase {
+            uuid: uuid::Uuid::from_u128(id),
+            name: format!("Phase {id}"),
+            count: 0,
+            time: 0,
+            hunt_type: String::from("NewOdds"),
+            step_size: 1,
+            success: false,
+            is_deleted: false,
+        })
+    }
+
+    #[test]
+    fn missing_config_is_the_default() {
+        let dir = TempConfig::new("missing");
+        let config = Config::load_from(&dir.path()).unwrap();
+        assert_eq!(config.server, DEFAULT_SERVER);
+        assert!(config.auth.is_none());
+        assert!(config.timers.is_empty());
+    }
+
+    #[test]
+    fn round_trip() {
+        let dir = TempConfig::new("round-trip");
+        let started = chrono::Utc::now();
+        let mut config = Config {
+            server: String::from("https://example.com"),
+            auth: Some(Auth::Token(String::from("tw_secret"))),
+            ..Default::default()
+        };
+        config.start_timer(&countable(1), started).unwrap();
+        config.save_to(&dir.path()).unwrap();
+
+        let loaded = Config::load_from(&dir.path()).unwrap();
+        assert_eq!(loaded.server, "https://example.com");
+        assert!(matches!(loaded.auth, Some(Auth::Token(token)) if token == "tw_secret"));
+        assert_eq!(loaded.timers, config.timers);
+
+        config.auth = Some(Auth::Session(String::from("cookie")));
+        config.save_to(&dir.path()).unwrap();
+        let loaded = Config::load_from(&dir.path()).unwrap();
+        assert!(matches!(loaded.auth, Some(Auth::Session(session)) if session == "cookie"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn only_the_owner_can_read_the_config() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempConfig::new("permissions");
+        Config::default().save_to(&dir.path()).unwrap();
+        let mode = std::fs::metadata(dir.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn invalid_config_is_an_error() {
+        let dir = TempConfig::new("invalid");
+        std::fs::create_dir_all(dir.path().parent().unwrap()).unwrap();
+        std::fs::write(dir.path(), "{").unwrap();
+        assert!(matches!(
+            Config::load_from(&dir.path()),
+            Err(CliError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn timers_run_until_stopped() {
+        let mut config = Config::default();
+        let started = chrono::Utc::now();
+        let running = countable(1);
+
+        config.start_timer(&running, started).unwrap();
+        assert!(matches!(
+            config.start_timer(&running, started),
+            Err(CliError::TimerRunning(_))
+        ));
+
+        let later = started + chrono::Duration::seconds(90);
+        assert_eq!(config.elapsed(&running, later).unwrap(), 90_000);
+        assert!(matches!(
+            config.elapsed(&countable(2), later),
+            Err(CliError::TimerNotRunning(name)) if name == "Phase 2"
+        ));
+
+        // stopping removes the timer so it can be started again
+        config.timers.remove(&running.uuid());
+        assert!(config.elapsed(&running, later).is_err());
+        config.start_timer(&running, later).unwrap();
+    }
+}
@@ -0,0 +1,51 @@
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    #[error("Not logged in, run `tallyweb-cli login` first or set TALLYWEB_TOKEN")]
+    NotLoggedIn,
+    #[error("Login failed: {0}")]
+    Login(String),
+    #[error("Server responded with {status}: {message}")]
+    Api { status: u16, message: String },
+    #[error("Could not reach server: {0}")]
+    Transport(String),
+    #[error("No countable named `{0}`")]
+    NotFound(String),
+    #[error("`{name}` matches multiple countables, use one of their uuids instead:\n{candidates}")]
+    Ambiguous { name: String, candidates: String },
+    #[error("Timer for `{0}` is not running")]
+    TimerNotRunning(String),
+    #[error("Timer for `{0}` is already running")]
+    TimerRunning(String),
+    #[error("Config error: {0}")]
+    Config(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Body of every `/api/v1` error response
+#[derive(Debug, serde::Deserialize)]
+struct ApiErrorBody {
+    message: String,
+}
+
+impl From<ureq::Error> for CliError {
+    fn from(value: ureq::Error) -> Self {
+        match value {
+            ureq::Error::Status(status, resp) => {
+                let message = resp
+                    .into_string()
+                    .ok()
+                    .map(|body| {
+                        serde_json::from_str::<ApiErrorBody>(&body)
+                            .map(|b| b.message)
+                            .unwrap_or(body)
+                    })
+                    .unwrap_or_default();
+                CliError::Api { status, message }
+            }
+            ureq::Error::Transport(err) => CliError::Transport(err.to_string()),
+        }
+    }
+}
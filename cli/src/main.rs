@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+use clap::{Parser, Subcommand};
+
+mod client;
+mod config;
+mod error;
+
+use client::{Client, Countable};
+use config::{Auth, Config};
+pub use error::CliError;
+
+/// Command line client for TallyWeb
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Args {
+    /// Server url, saved on `login`
+    #[arg(long, global = true, env = "TALLYWEB_SERVER")]
+    server: Option<String>,
+    /// Personal access token, takes precedence over a saved login
+    #[arg(long, global = true, env = "TALLYWEB_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Save credentials for later commands, asks for a password unless `--token` is given
+    Login {
+        #[arg(short, long)]
+        username: Option<String>,
+    },
+    /// Forget saved credentials
+    Logout,
+    /// List counters and their phases
+    List {
+        /// Include archived counters
+        #[arg(long)]
+        archived: bool,
+    },
+    /// Add to the count of a counter or phase, defaults to its step size
+    #[command(visible_alias = "inc")]
+    Increment {
+        /// Name or uuid of the counter or phase
+        countable: String,
+        amount: Option<i32>,
+    },
+    /// Subtract from the count of a counter or phase, defaults to its step size
+    #[command(visible_alias = "dec")]
+    Decrement {
+        /// Name or uuid of the counter or phase
+        countable: String,
+        amount: Option<i32>,
+    },
+    /// Start, stop or show the timer of a counter or phase
+    Timer {
+        #[command(subcommand)]
+        action: TimerAction,
+    },
+    /// Write all counters, phases and preferences as json
+    Export {
+        /// Output file, defaults to stdout
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// Print the count of every counter whenever it changes
+    Watch {
+        /// Only watch these counters or phases
+        countables: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TimerAction {
+    Start { countable: String },
+    Stop { countable: String },
+    Status { countable: String },
+}
+
+fn main() {
+    let args = Args::parse();
+
+    if let Err(err) = run(args) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), CliError> {
+    let mut config = Config::load()?;
+    let server = args.server.clone().unwrap_or(config.server.clone());
+
+    if let Command::Login { username } = &args.command {
+        config.server = server.clone();
+        config.auth = Some(match &args.token {
+            Some(token) => Auth::Token(token.clone()),
+            None => login(&server, username.clone())?,
+        });
+        config.save()?;
+        println!("Logged in to {server}");
+        return Ok(());
+    }
+
+    if let Command::Logout = &args.command {
+        config.auth = None;
+        config.save()?;
+        println!("Logged out");
+        return Ok(());
+    }
+
+    let auth = args
+        .token
+        .clone()
+        .map(Auth::Token)
+        .or(config.auth.clone())
+        .ok_or(CliError::NotLoggedIn)?;
+    let client = Client::new(&server, auth);
+
+    match args.command {
+        Command::Login { .. } | Command::Logout => unreachable!(),
+        Command::List { archived } => list(&client, archived),
+        Command::Increment { countable, amount } => {
+            let countable = client.resolve(&countable)?;
+            let updated = client.increment(&countable, amount)?;
+            print_countable(&updated);
+            Ok(())
+        }
+        Command::Decrement { countable, amount } => {
+            let countable = client.resolve(&countable)?;
+            let amount = match amount {
+                Some(amount) => amount,
+                None => client.step_size(&countable)?,
+            };
+            let updated = client.increment(&countable, Some(-amount))?;
+            print_countable(&updated);
+            Ok(())
+        }
+        Command::Timer { action } => timer(&client, &mut config, action),
+        Command::Export { output } => {
+            let data = serde_json::to_string_pretty(&client.export()?)?;
+            match output {
+                Some(path) => std::fs::write(path, data)?,
+                None => println!("{data}"),
+            }
+            Ok(())
+        }
+        Command::Watch { countables } => watch(&client, countables),
+    }
+}
+
+fn login(server: &str, username: Option<String>) -> Result<Auth, CliError> {
+    let username = match username {
+        Some(username) => username,
+        None => {
+            print!("Username: ");
+            std::io::stdout().flush()?;
+            let mut username = String::new();
+            std::io::stdin().read_line(&mut username)?;
+            username.trim().to_string()
+        }
+    };
+
+    let password = match std::env::var("TALLYWEB_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => rpassword::prompt_password("Password: ")?,
+    };
+
+    Ok(Auth::Session(Client::login(server, &username, &password)?))
+}
+
+fn format_time(millis: i64) -> String {
+    let secs = millis / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+fn print_countable(countable: &Countable) {
+    println!(
+        "{}: {} ({})",
+        countable.name(),
+        countable.count(),
+        format_time(countable.time())
+    );
+}
+
+fn list(client: &Client, archived: bool) -> Result<(), CliError> {
+    let phases = client
+        .phases(archived)?
+        .into_iter()
+        .map(|p| (p.uuid, p))
+        .collect::<HashMap<_, _>>();
+
+    for counter in client.counters(archived)? {
+        println!(
+            "{}  {:<24} {:>8}  {}{}",
+            counter.uuid,
+            counter.name,
+            counter.count,
+            format_time(counter.time),
+            if counter.is_deleted {
+                "  (archived)"
+            } else {
+                ""
+            },
+        );
+
+        for phase in counter.phases.iter().filter_map(|id| phases.get(id)) {
+            println!(
+                "{}    {:<22} {:>8}  {}  {}{}{}",
+                phase.uuid,
+                phase.name,
+                phase.count,
+                format_time(phase.time),
+                phase.hunt_type,
+                if phase.success { "  (success)" } else { "" },
+                if phase.is_deleted { "  (archived)" } else { "" },
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn timer(client: &Client, config: &mut Config, action: TimerAction) -> Result<(), CliError> {
+    match action {
+        TimerAction::Start { countable } => {
+            let countable = client.resolve(&countable)?;
+            config.start_timer(&countable, chrono::Utc::now())?;
+            config.save()?;
+            println!("Started timer for {}", countable.name());
+        }
+        TimerAction::Stop { countable } => {
+            let countable = client.resolve(&countable)?;
+            let elapsed = config.elapsed(&countable, chrono::Utc::now())?;
+            let updated = client.add_time(&countable, elapsed)?;
+
+            config.timers.remove(&countable.uuid());
+            config.save()?;
+            print_countable(&updated);
+        }
+        TimerAction::Status { countable } => {
+            let countable = client.resolve(&countable)?;
+            match config.elapsed(&countable, chrono::Utc::now()) {
+                Ok(elapsed) => {
+                    println!("{}: running for {}", countable.name(), format_time(elapsed))
+                }
+                Err(_) => println!("{}: stopped", countable.name()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn watch(client: &Client, countables: Vec<String>) -> Result<(), CliError> {
+    let filter = countables
+        .iter()
+        .map(|c| client.resolve(c).map(|c| c.uuid()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut last = HashMap::<uuid::Uuid, i32>::new();
+    let mut print_changes = || -> Result<(), CliError> {
+        let mut current = client
+            .counters(false)?
+            .into_iter()
+            .map(Countable::Counter)
+            .collect::<Vec<_>>();
+        if !filter.is_empty() {
+            current.extend(client.phases(false)?.into_iter().map(Countable::Phase));
+            current.retain(|c| filter.contains(&c.uuid()));
+        }
+
+        for countable in current {
+            if last.insert(countable.uuid(), countable.count()) != Some(countable.count()) {
+                println!(
+                    "{} {}: {}",
+                    chrono::Local::now().format("%H:%M:%S"),
+                    countable.name(),
+                    countable.count()
+                );
+            }
+        }
+
+        Ok(())
+    };
+
+    print_changes()?;
+
+    // every `store` event holds a changed counter with all of its phases,
+    // `resync` means the server dropped events so everything is loaded again
+    let mut event = String::new();
+    for line in client.events()?.lines() {
+        let line = line?;
+        if let Some(name) = line.strip_prefix("event:") {
+            event = name.trim().to_string();
+        } else if let Some(data) = line.strip_prefix("data:") {
+            let watched =
+                filter.is_empty() || filter.iter().any(|uuid| data.contains(&uuid.to_string()));
+            if event == "resync" || (event == "store" && watched) {
+                print_changes()?;
+            }
+        }
+    }
+
+    Err(CliError::Transport(String::from(
+        "Server closed the event stream",
+    )))
+}
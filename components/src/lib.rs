@@ -9,6 +9,7 @@ mod select;
 mod sidebar;
 mod slider;
 mod spinner;
+mod statusbar;
 mod time;
 mod tooltip;
 mod treeview;
@@ -22,6 +23,7 @@ pub use select::{Select, SelectOption};
 pub use sidebar::*;
 pub use slider::*;
 pub use spinner::*;
+pub use statusbar::{StatusBar, WifiOffIcon};
 pub use time::{Clock, Timer};
 pub use tooltip::*;
 pub use treeview::*;
@@ -4,6 +4,9 @@ use leptos::*;
 pub fn Progressbar<F, C>(
     progress: F,
     color: C,
+    /// lines drawn over the bar at a share between 0 and 1, with a label shown on hover
+    #[prop(optional, into)]
+    markers: MaybeSignal<Vec<(f64, String)>>,
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
     children: ChildrenFn,
 ) -> impl IntoView
@@ -27,7 +30,7 @@ where
             >
                 {children()}
             </div>
-            <through-bar style:width="100%" style:min-height="8px">
+            <through-bar style:width="100%" style:min-height="8px" style:position="relative">
                 <Show
                     when=move || { progress() > 0.0 }
                     fallback=move || {
@@ -46,6 +49,24 @@ where
                     </div>
 
                 </Show>
+                {move || {
+                    markers()
+                        .into_iter()
+                        .map(|(position, label)| {
+                            view! {
+                                <bar-marker
+                                    title=label
+                                    style:position="absolute"
+                                    style:top="0px"
+                                    style:bottom="0px"
+                                    style:width="2px"
+                                    style:background="rgba(255, 255, 255, 0.7)"
+                                    style:left=format!("{}%", position.clamp(0.0, 1.0) * 100.0)
+                                ></bar-marker>
+                            }
+                        })
+                        .collect_view()
+                }}
             </through-bar>
         </progress-bar>
     }
@@ -37,6 +37,9 @@ pub fn Select(
     #[prop(attrs)] attrs: Vec<(&'static str, Attribute)>,
     #[prop(into)] options: Vec<SelectOption>,
     #[prop(into)] selected: MaybeSignal<SelectOption>,
+    /// called with the value of the selected option
+    #[prop(optional, into)]
+    on_change: Option<Callback<String>>,
 ) -> impl IntoView {
     let attrs = store_value(attrs);
     let hidden_select_ref = create_node_ref::<html::Input>();
@@ -80,6 +83,13 @@ pub fn Select(
         }
     });
 
+    create_effect(move |_| {
+        let value = selection.with(|s| s.value.clone());
+        if let Some(on_change) = on_change {
+            on_change(value)
+        }
+    });
+
     view! {
         <Show
             when=show_custom
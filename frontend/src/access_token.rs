@@ -0,0 +1,142 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum TokenScope {
+    Read,
+    Count,
+    Edit,
+    Admin,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 4] = [
+        TokenScope::Read,
+        TokenScope::Count,
+        TokenScope::Edit,
+        TokenScope::Admin,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TokenScope::Read => "Read counters",
+            TokenScope::Count => "Increment counters and toggle timers",
+            TokenScope::Edit => "Edit and archive counters",
+            TokenScope::Admin => "Manage preferences and access tokens",
+        }
+    }
+}
+
+impl std::fmt::Display for TokenScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenScope::Read => write!(f, "read"),
+            TokenScope::Count => write!(f, "count"),
+            TokenScope::Edit => write!(f, "edit"),
+            TokenScope::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<backend::access_token::TokenScope> for TokenScope {
+    fn from(value: backend::access_token::TokenScope) -> Self {
+        match value {
+            backend::access_token::TokenScope::Read => Self::Read,
+            backend::access_token::TokenScope::Count => Self::Count,
+            backend::access_token::TokenScope::Edit => Self::Edit,
+            backend::access_token::TokenScope::Admin => Self::Admin,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<TokenScope> for backend::access_token::TokenScope {
+    fn from(value: TokenScope) -> Self {
+        match value {
+            TokenScope::Read => Self::Read,
+            TokenScope::Count => Self::Count,
+            TokenScope::Edit => Self::Edit,
+            TokenScope::Admin => Self::Admin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessToken {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub created_at: chrono::NaiveDateTime,
+    pub expire_on: Option<chrono::NaiveDateTime>,
+    pub last_used: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<backend::access_token::DbAccessToken> for AccessToken {
+    fn from(value: backend::access_token::DbAccessToken) -> Self {
+        Self {
+            uuid: value.uuid,
+            scopes: value.scopes().into_iter().map(TokenScope::from).collect(),
+            name: value.name,
+            created_at: value.created_at,
+            expire_on: value.expire_on,
+            last_used: value.last_used,
+        }
+    }
+}
+
+#[server(GetAccessTokens, "/api/session")]
+pub async fn get_access_tokens() -> Result<Vec<AccessToken>, ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Admin).await?;
+    let pool = api::extract_pool().await?;
+
+    let tokens = backend::access_token::all_by_user(&pool, session.user_uuid)
+        .await?
+        .into_iter()
+        .map(AccessToken::from)
+        .collect();
+
+    Ok(tokens)
+}
+
+/// Creates a new access token, the returned `String` is the plain token
+/// and can not be retrieved again later
+#[server(CreateAccessToken, "/api/session")]
+pub async fn create_access_token(
+    name: String,
+    scopes: Vec<TokenScope>,
+    expire_days: Option<i64>,
+) -> Result<(AccessToken, String), ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Admin).await?;
+    let pool = api::extract_pool().await?;
+
+    if name.trim().is_empty() || scopes.is_empty() {
+        Err(AppError::ServerError(String::from(
+            "Access token requires a name and at least one scope",
+        )))?
+    }
+
+    let scopes = scopes.into_iter().map(|s| s.into()).collect::<Vec<_>>();
+    let expire_on =
+        expire_days.map(|days| chrono::Utc::now().naive_utc() + chrono::Duration::days(days));
+
+    let (token, plain) =
+        backend::access_token::create(&pool, session.user_uuid, name.trim(), &scopes, expire_on)
+            .await?;
+
+    Ok((token.into(), plain))
+}
+
+#[server(RevokeAccessToken, "/api/session")]
+pub async fn revoke_access_token(token: uuid::Uuid) -> Result<(), ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Admin).await?;
+    let pool = api::extract_pool().await?;
+
+    backend::access_token::revoke(&pool, session.user_uuid, token).await?;
+
+    Ok(())
+}
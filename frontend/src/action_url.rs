@@ -0,0 +1,129 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "ssr")]
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ActionKind {
+    Increment,
+    Decrement,
+    ToggleTimer,
+}
+
+impl ActionKind {
+    pub const ALL: [ActionKind; 3] = [
+        ActionKind::Increment,
+        ActionKind::Decrement,
+        ActionKind::ToggleTimer,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ActionKind::Increment => "Increment",
+            ActionKind::Decrement => "Decrement",
+            ActionKind::ToggleTimer => "Start/stop timer",
+        }
+    }
+}
+
+impl std::fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionKind::Increment => write!(f, "increment"),
+            ActionKind::Decrement => write!(f, "decrement"),
+            ActionKind::ToggleTimer => write!(f, "toggle_timer"),
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<backend::action_url::ActionKind> for ActionKind {
+    fn from(value: backend::action_url::ActionKind) -> Self {
+        match value {
+            backend::action_url::ActionKind::Increment => Self::Increment,
+            backend::action_url::ActionKind::Decrement => Self::Decrement,
+            backend::action_url::ActionKind::ToggleTimer => Self::ToggleTimer,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<ActionKind> for backend::action_url::ActionKind {
+    fn from(value: ActionKind) -> Self {
+        match value {
+            ActionKind::Increment => Self::Increment,
+            ActionKind::Decrement => Self::Decrement,
+            ActionKind::ToggleTimer => Self::ToggleTimer,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionUrl {
+    pub uuid: uuid::Uuid,
+    pub countable: uuid::Uuid,
+    pub action: ActionKind,
+    pub timer_running: bool,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_used: Option<chrono::NaiveDateTime>,
+}
+
+#[cfg(feature = "ssr")]
+impl From<backend::action_url::DbActionUrl> for ActionUrl {
+    fn from(value: backend::action_url::DbActionUrl) -> Self {
+        Self {
+            uuid: value.uuid,
+            countable: value.countable_uuid,
+            action: value.kind().into(),
+            timer_running: value.timer_started.is_some(),
+            created_at: value.created_at,
+            last_used: value.last_used,
+        }
+    }
+}
+
+/// Path to call an action url with the secret returned by [`create_action_url`]
+pub fn action_path(secret: &str) -> String {
+    format!("/api/v1/actions/{secret}")
+}
+
+#[server(GetActionUrls, "/api/session")]
+pub async fn get_action_urls(countable: uuid::Uuid) -> Result<Vec<ActionUrl>, ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Admin).await?;
+    let pool = api::extract_pool().await?;
+
+    let action_urls = backend::action_url::all_by_countable(&pool, session.user_uuid, countable)
+        .await?
+        .into_iter()
+        .map(ActionUrl::from)
+        .collect();
+
+    Ok(action_urls)
+}
+
+/// Creates a new action url, the returned `String` is the secret of the url
+/// and can not be retrieved again later
+#[server(CreateActionUrl, "/api/session")]
+pub async fn create_action_url(
+    countable: uuid::Uuid,
+    action: ActionKind,
+) -> Result<(ActionUrl, String), ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Admin).await?;
+    let pool = api::extract_pool().await?;
+
+    let (action_url, secret) =
+        backend::action_url::create(&pool, session.user_uuid, countable, action.into()).await?;
+
+    Ok((action_url.into(), secret))
+}
+
+#[server(RevokeActionUrl, "/api/session")]
+pub async fn revoke_action_url(action_url: uuid::Uuid) -> Result<(), ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Admin).await?;
+    let pool = api::extract_pool().await?;
+
+    backend::action_url::revoke(&pool, session.user_uuid, action_url).await?;
+
+    Ok(())
+}
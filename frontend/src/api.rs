@@ -43,7 +43,18 @@ pub async fn login_user(
         chrono::Duration::days(1)
     };
 
-    let user = backend::auth::login_user(&pool, username, password, dur).await?;
+    let req = leptos_actix::extract::<actix_web::HttpRequest>().await?;
+    let remote_addr = {
+        let info = req.connection_info();
+        if backend::rate_limit::LimiterConfig::from_env().trust_forwarded {
+            info.realip_remote_addr()
+        } else {
+            info.peer_addr()
+        }
+        .map(|addr| addr.to_string())
+    };
+
+    let user = backend::auth::login_user(&pool, username, password, remote_addr, dur).await?;
 
     let session = UserSession {
         user_uuid: user.uuid,
@@ -57,6 +68,54 @@ pub async fn login_user(
     Ok(session)
 }
 
+/// Removes the session cookie, the account stays logged in on other devices
+#[server(LogoutUser, "/api")]
+pub async fn logout_user() -> Result<(), ServerFnError> {
+    use actix_web::cookie::{self, time::Duration};
+    use actix_web::http::header;
+
+    let resp = expect_context::<leptos_actix::ResponseOptions>();
+
+    let mut cookie = cookie::Cookie::new("session", "");
+    cookie.set_max_age(Duration::ZERO);
+    cookie.set_path("/");
+
+    resp.append_header(
+        header::SET_COOKIE,
+        header::HeaderValue::from_str(&cookie.to_string())?,
+    );
+
+    return Ok(());
+}
+
+/// Makes `session` the current session, `session` has to be a saved account that is still valid
+#[server(SwitchAccount, "/api")]
+pub async fn switch_account(session: UserSession) -> Result<(), ServerFnError> {
+    let pool = extract_pool().await?;
+    check_saved_session(&pool, &session).await?;
+
+    set_session_cookie(session).await?;
+
+    return Ok(());
+}
+
+/// Checks that an account saved in the browser still has a valid token of the same user
+#[cfg(feature = "ssr")]
+async fn check_saved_session(
+    pool: &backend::PgPool,
+    session: &UserSession,
+) -> Result<(), ServerFnError> {
+    let user = backend::auth::get_user(pool, &session.username, session.token).await?;
+    if user.uuid != session.user_uuid {
+        Err(AppError::InvalidToken)?
+    }
+    if user.token_expire < chrono::Utc::now().naive_utc() {
+        Err(AppError::ExpiredToken)?
+    }
+
+    Ok(())
+}
+
 #[server(CreateAccount, "/api", "Url", "create_account")]
 pub async fn create_account(
     username: String,
@@ -98,92 +157,67 @@ pub async fn change_password(
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-#[server(EditCountableForm)]
-pub async fn edit_countable_form(
-    session_user_uuid: uuid::Uuid,
-    session_username: String,
-    session_token: uuid::Uuid,
-
-    countable_key: uuid::Uuid,
-    countable_kind: CountableKind,
-    countable_name: String,
-    countable_count: i32,
-    countable_step: i32,
-    countable_hours: i64,
-    countable_mins: i64,
-    countable_secs: i64,
-    countable_millis: i64,
-    countable_hunttype: String,
-    countable_charm: Option<String>,
-) -> Result<(), ServerFnError> {
-    let session = UserSession {
-        user_uuid: session_user_uuid,
-        username: session_username,
-        token: session_token,
-    };
-    check_user(session).await?;
-
-    let countable_time =
-        ((countable_hours * 60 + countable_mins) * 60 + countable_secs) * 1000 + countable_millis;
-
-    let mut conn = extract_pool().await?.begin().await?;
-    match countable_kind {
-        CountableKind::Counter => {
-            backend::counter::set_name(&mut conn, countable_key, &countable_name).await?;
-            backend::counter::set_count(&mut conn, countable_key, countable_count).await?;
-            backend::counter::set_step(&mut conn, countable_key, countable_step).await?;
-            backend::counter::set_time(&mut conn, countable_key, countable_time).await?;
-            backend::counter::set_hunttype(&mut conn, countable_key, countable_hunttype.into())
-                .await?;
-            backend::counter::set_charm(&mut conn, countable_key, countable_charm.is_some())
-                .await?;
-        }
-        CountableKind::Phase => {
-            backend::phase::set_name(&mut conn, countable_key, &countable_name).await?;
-            backend::phase::set_count(&mut conn, countable_key, countable_count).await?;
-            backend::phase::set_step(&mut conn, countable_key, countable_step).await?;
-            backend::phase::set_time(&mut conn, countable_key, countable_time).await?;
-            backend::phase::set_hunttype(&mut conn, countable_key, countable_hunttype.into())
-                .await?;
-            backend::phase::set_charm(&mut conn, countable_key, countable_charm.is_some()).await?;
-        }
-        _ => (),
-    }
-
-    conn.commit().await?;
-
-    return Ok(());
-}
-
+/// Saves `list` and returns the new revision of every countable,
+/// the whole list is rejected with [`AppError::Conflict`] when one of them
+/// was changed on the server in the meantime
+///
+/// Every field of the countables is written, so this needs the `Edit` scope,
+/// tokens that may only count go through [`countable::server::ApplyOperations`]
 #[server(UpdateCountable, "/api/session")]
-pub async fn update_countable_many(list: Vec<countable::Countable>) -> Result<(), ServerFnError> {
+pub async fn update_countable_many(
+    list: Vec<countable::Countable>,
+) -> Result<Vec<(uuid::Uuid, i32)>, ServerFnError> {
     let pool = extract_pool().await?;
-    let session = session::actix_extract_user().await?;
+    let session = session::require_scope(backend::access_token::TokenScope::Edit).await?;
 
     let mut tx = pool.begin().await?;
+    let mut revisions = Vec::new();
 
     for countable in list {
-        match countable {
+        if let Err(err) = countable::validate::countable(&countable) {
+            return Err(ServerFnError::ServerError(serde_json::to_string(&err)?));
+        }
+        let key = countable.uuid();
+        let res = match countable {
             countable::Countable::Counter(c) => {
                 if c.lock()?.owner_uuid != session.user_uuid {
                     Err(AppError::Unauthorized)?
                 }
-                backend::counter::update(&mut tx, c.lock()?.clone().into()).await?
+                backend::counter::update(&mut tx, c.lock()?.clone().into()).await
             }
             countable::Countable::Phase(p) => {
                 if p.lock()?.owner_uuid != session.user_uuid {
                     Err(AppError::Unauthorized)?
                 }
-                backend::phase::update(&mut tx, p.lock()?.clone().into()).await?
+                backend::phase::update(&mut tx, p.lock()?.clone().into()).await
+            }
+            countable::Countable::Chain(_) => Err(AppError::Unsupported("Chain".into()))?,
+        };
+
+        match res {
+            Ok(()) => {}
+            Err(backend::BackendError::Conflict(current)) => {
+                let current = match *current {
+                    backend::ServerVersion::Counter(c) => countable::Countable::from(c),
+                    backend::ServerVersion::Phase(p) => countable::Countable::from(p),
+                };
+                // the client needs the server version to merge, send it along as json
+                let err = AppError::Conflict(Box::new(current));
+                return Err(ServerFnError::ServerError(serde_json::to_string(&err)?));
             }
-            countable::Countable::Chain(_) => todo!(),
+            Err(err) => Err(err)?,
         }
+
+        let revision = match backend::counter::get(&mut tx, key).await {
+            Ok(counter) => counter.revision,
+            Err(_) => backend::phase::get(&mut tx, key).await?.revision,
+        };
+        revisions.push((key, revision));
     }
 
     tx.commit().await?;
 
-    return Ok(());
+    return Ok(revisions);
 }
 
 #[server(UpdateCounter, "/api")]
@@ -198,6 +232,7 @@ pub async fn update_counter(session: UserSession, counter: Counter) -> Result<()
 
 #[server(ArchiveCountable, "/api/session")]
 pub async fn archive_countable(countable: Countable) -> Result<(), ServerFnError> {
+    session::require_scope(backend::access_token::TokenScope::Edit).await?;
     let pool = extract_pool().await?;
     let mut tx = pool.begin().await?;
 
@@ -222,6 +257,8 @@ pub async fn remove_countable(
     session: UserSession,
     countable: Countable,
 ) -> Result<(), ServerFnError> {
+    session::require_scope(backend::access_token::TokenScope::Edit).await?;
+
     match countable {
         Countable::Counter(_) => remove_counter(session, countable.uuid()).await?,
         Countable::Phase(_) => remove_phase(session, countable.uuid()).await?,
@@ -247,8 +284,8 @@ pub async fn remove_counter(
 pub async fn remove_phase(session: UserSession, phase_id: uuid::Uuid) -> Result<(), ServerFnError> {
     let pool = extract_pool().await?;
 
-    let _ = backend::auth::get_user(&pool, &session.username, session.token).await?;
-    backend::remove_phase(&pool, phase_id).await?;
+    let user = backend::auth::get_user(&pool, &session.username, session.token).await?;
+    backend::remove_phase(&pool, user.uuid, phase_id).await?;
 
     Ok(())
 }
@@ -294,6 +331,8 @@ pub async fn save_preferences(
     session_token: uuid::Uuid,
     preferences: Preferences,
 ) -> Result<(), ServerFnError> {
+    session::require_scope(backend::access_token::TokenScope::Admin).await?;
+
     let session = UserSession {
         user_uuid: session_user_uuid,
         username: session_username,
@@ -303,6 +342,10 @@ pub async fn save_preferences(
 
     let user = backend::auth::get_user(&pool, &session.username, session.token).await?;
 
+    if let Err(err) = preferences.milestones.validate() {
+        return Err(ServerFnError::ServerError(serde_json::to_string(&err)?));
+    }
+
     let accent_color = if preferences.use_default_accent_color {
         None
     } else {
@@ -316,6 +359,13 @@ pub async fn save_preferences(
         show_separator: preferences.show_separator,
         multi_select: preferences.multi_select,
         save_on_pause: preferences.save_on_pause,
+        milestone_half: preferences.milestones.half,
+        milestone_odds: preferences.milestones.odds,
+        milestone_double_odds: preferences.milestones.double_odds,
+        milestone_counts: preferences.milestones.counts,
+        milestone_notify: preferences.milestones.notify,
+        auto_advance: preferences.auto_advance,
+        record_found: preferences.record_found,
     };
     db_prefs
         .db_set(&pool, &session.username, session.token)
@@ -364,3 +414,70 @@ async fn change_username(
 
     return Ok(session_user);
 }
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    async fn saved_session(
+        pool: &backend::PgPool,
+        username: &str,
+        expire_on: chrono::NaiveDateTime,
+    ) -> UserSession {
+        let user_uuid: uuid::Uuid = sqlx::query_scalar(
+            "INSERT INTO users (username, password) VALUES ($1, '') RETURNING uuid",
+        )
+        .bind(username)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let token: uuid::Uuid = sqlx::query_scalar(
+            "INSERT INTO auth_tokens (user_uuid, expire_on) VALUES ($1, $2) RETURNING uuid",
+        )
+        .bind(user_uuid)
+        .bind(expire_on)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        UserSession {
+            user_uuid,
+            username: username.to_string(),
+            token,
+        }
+    }
+
+    fn tomorrow() -> chrono::NaiveDateTime {
+        (chrono::Utc::now() + chrono::Duration::days(1)).naive_utc()
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn switching_to_a_saved_session(pool: backend::PgPool) {
+        let session = saved_session(&pool, "first", tomorrow()).await;
+        assert_eq!(check_saved_session(&pool, &session).await, Ok(()));
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn switching_to_another_users_token(pool: backend::PgPool) {
+        let mut session = saved_session(&pool, "first", tomorrow()).await;
+        session.user_uuid = saved_session(&pool, "second", tomorrow()).await.user_uuid;
+        assert_eq!(
+            check_saved_session(&pool, &session).await,
+            Err(AppError::InvalidToken.into())
+        );
+
+        let mut session = saved_session(&pool, "third", tomorrow()).await;
+        session.token = uuid::Uuid::new_v4();
+        assert!(check_saved_session(&pool, &session).await.is_err());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn switching_to_an_expired_session(pool: backend::PgPool) {
+        let yesterday = (chrono::Utc::now() - chrono::Duration::days(1)).naive_utc();
+        let session = saved_session(&pool, "first", yesterday).await;
+        assert_eq!(
+            check_saved_session(&pool, &session).await,
+            Err(AppError::ExpiredToken.into())
+        );
+    }
+}
@@ -83,6 +83,11 @@ pub fn App() -> impl IntoView {
                             view=move || view! { <ChangeAccountInfo /> }
                         />
                         <Route path="/change-password" view=ChangePassword />
+                        <Route path="/access-tokens" view=AccessTokensWindow />
+                        <Route path="/custom-methods" view=CustomMethodsWindow />
+                        <Route path="/statistics" view=AccountStatisticsWindow />
+                        <Route path="/action-urls/:key" view=ActionUrlsWindow />
+                        <Route path="/simulate/:key" view=SimulateWindow />
                     </Route>
                     <TestRoutes />
                     <Route path="/login" view=LoginPage />
@@ -308,16 +313,10 @@ fn TreeViewRow(key: uuid::Uuid) -> impl IntoView {
     let click_new_phase = move |ev: ev::MouseEvent| {
         ev.stop_propagation();
 
-        let phase_number = store.get_untracked().children(&key.into()).len();
-        let name = format!("Phase {}", phase_number + 1);
-
         store.update(move |s| {
-            let id = s.new_countable(&name, CountableKind::Phase, Some(key.into()));
-            let _ = save_handler().save(
-                Box::new([s.get(&id).unwrap()].to_vec()),
-                Box::new(move |_| data_resource.refetch()),
-            );
+            s.new_phase(&key.into());
         });
+        let _ = save_handler().save(Box::new(store), Box::new(move |_| data_resource.refetch()));
 
         request_animation_frame(move || expand_node(key, true))
     };
@@ -408,6 +407,7 @@ fn UnsetCountable() -> impl IntoView {
 fn ProvideCountableSignals(children: ChildrenFn) -> impl IntoView {
     let msg = expect_context::<MessageJar>();
     let store = expect_context::<RwSignal<CountableStore>>();
+    let user = expect_context::<RwSignal<UserSession>>();
 
     let selection = SelectionModel::<uuid::Uuid, Countable>::new();
     let selection_signal = create_rw_signal(selection);
@@ -415,10 +415,18 @@ fn ProvideCountableSignals(children: ChildrenFn) -> impl IntoView {
 
     provide_context(create_rw_signal(SortMethod::default()));
 
+    outbox::provide_outbox();
+    provide_resume_timer();
+    let outbox = expect_context::<outbox::Outbox>();
+
     let save_handlers = create_rw_signal(SaveHandlers::new());
 
-    let server_handler = Box::new(ServerSaveHandler::new());
-    save_handlers.update(|sh| sh.connect_handler(server_handler.clone()));
+    // guests only save locally
+    let is_guest = user.get_untracked().is_guest();
+    if !is_guest {
+        let server_handler = Box::new(ServerSaveHandler::new());
+        save_handlers.update(|sh| sh.connect_handler(server_handler.clone()));
+    }
 
     // when the page closes, gets minimized or navigated away from save the store
     window_event_listener(ev::blur, move |_| {
@@ -431,14 +439,28 @@ fn ProvideCountableSignals(children: ChildrenFn) -> impl IntoView {
     });
 
     create_effect(move |_| {
+        let user = user.get_untracked().user_uuid;
+        operation::set_recording(!is_guest);
         spawn_local(async move {
-            let indexed_handler = indexed::IndexedSaveHandler::new().await;
+            let indexed_handler = indexed::IndexedSaveHandler::new(user).await;
+            outbox.indexed.set(Some(indexed_handler.is_ok()));
             match indexed_handler {
                 Ok(ih) => {
                     let mut s = store.get_untracked();
                     if let Err(err) = ih.sync_store(&mut s).await {
                         msg.set_err(err);
                     };
+                    if !is_guest {
+                        match guest::upgrade(user).await {
+                            Ok(moved) if !moved.is_empty() => {
+                                let moved = moved.into_iter().map(|c| (c.uuid().into(), c));
+                                s.merge(CountableStore::new(user, moved.collect()));
+                                msg.set_success("Counters of the guest session were added")
+                            }
+                            Ok(_) => {}
+                            Err(err) => msg.set_err(err),
+                        }
+                    }
                     if let Err(err) = save_handlers
                         .get_untracked()
                         .save(Box::new(s.clone()), Box::new(|_| ()))
@@ -454,10 +476,29 @@ fn ProvideCountableSignals(children: ChildrenFn) -> impl IntoView {
                         msg.set_err(err)
                     }
                 }
-                Err(err) => msg.set_msg(format!(
-                    "Local saving could not be initialised\nGot error: {}",
-                    err
-                )),
+                // the local data could not be upgraded, offer a copy of it so it is not lost
+                Err(err) => match indexed::export(user).await {
+                    Ok(backup) => {
+                        let href = format!(
+                            "data:application/json;charset=utf-8,{}",
+                            js_sys::encode_uri_component(&backup)
+                        );
+                        let file_name = format!(
+                            "tallyweb-backup-{}.json",
+                            chrono::Utc::now().format("%Y-%m-%d")
+                        );
+                        msg.without_timeout().set_err_view(view! {
+                            <b>{format!("Local saving could not be initialised\nGot error: {err}")}</b>
+                            <a href=href download=file_name>
+                                "Export local data"
+                            </a>
+                        })
+                    }
+                    Err(_) => msg.set_msg(format!(
+                        "Local saving could not be initialised\nGot error: {}",
+                        err
+                    )),
+                },
             }
         })
     });
@@ -0,0 +1,291 @@
+//! Games a phase can be hunted in.
+//!
+//! The game decides the base odds, whether the shiny charm exists
+//! and which hunting methods are available.
+
+use serde::{Deserialize, Serialize};
+
+use super::{Hunttype, Masuda, OutbreakBonus, Radar, Research};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum Game {
+    GoldSilverCrystal,
+    RubySapphireEmerald,
+    FireRedLeafGreen,
+    DiamondPearlPlatinum,
+    HeartGoldSoulSilver,
+    BlackWhite,
+    Black2White2,
+    XY,
+    OmegaRubyAlphaSapphire,
+    SunMoon,
+    UltraSunUltraMoon,
+    LetsGo,
+    SwordShield,
+    BrilliantDiamondShiningPearl,
+    LegendsArceus,
+    ScarletViolet,
+}
+
+impl Game {
+    pub const ALL: [Game; 16] = [
+        Game::GoldSilverCrystal,
+        Game::RubySapphireEmerald,
+        Game::FireRedLeafGreen,
+        Game::DiamondPearlPlatinum,
+        Game::HeartGoldSoulSilver,
+        Game::BlackWhite,
+        Game::Black2White2,
+        Game::XY,
+        Game::OmegaRubyAlphaSapphire,
+        Game::SunMoon,
+        Game::UltraSunUltraMoon,
+        Game::LetsGo,
+        Game::SwordShield,
+        Game::BrilliantDiamondShiningPearl,
+        Game::LegendsArceus,
+        Game::ScarletViolet,
+    ];
+
+    pub fn generation(&self) -> u8 {
+        match self {
+            Game::GoldSilverCrystal => 2,
+            Game::RubySapphireEmerald | Game::FireRedLeafGreen => 3,
+            Game::DiamondPearlPlatinum | Game::HeartGoldSoulSilver => 4,
+            Game::BlackWhite | Game::Black2White2 => 5,
+            Game::XY | Game::OmegaRubyAlphaSapphire => 6,
+            Game::SunMoon | Game::UltraSunUltraMoon | Game::LetsGo => 7,
+            Game::SwordShield | Game::BrilliantDiamondShiningPearl | Game::LegendsArceus => 8,
+            Game::ScarletViolet => 9,
+        }
+    }
+
+    /// The shiny charm was introduced in Black 2 and White 2
+    pub fn has_charm(&self) -> bool {
+        *self == Game::Black2White2 || self.generation() >= 6
+    }
+
+    /// Base odds of a single roll
+    pub fn odds(&self) -> f64 {
+        if self.generation() < 6 {
+            8192.0
+        } else {
+            4096.0
+        }
+    }
+
+    /// Hunting methods that exist in the game, the first one is the default
+    pub fn methods(&self) -> Vec<Hunttype> {
+        let base = if self.generation() < 6 {
+            Hunttype::OldOdds
+        } else {
+            Hunttype::NewOdds
+        };
+        let masuda = match self.generation() {
+            4 => Some(Masuda::GenIV),
+            5 => Some(Masuda::GenV),
+            // there is no breeding in these games
+            _ if matches!(self, Game::LetsGo | Game::LegendsArceus) => None,
+            g if g >= 6 => Some(Masuda::GenVI),
+            _ => None,
+        };
+
+        let mut methods = vec![base];
+        methods.extend(masuda.map(Hunttype::Masuda));
+        match self {
+            Game::DiamondPearlPlatinum => methods.push(Hunttype::Radar(Radar::GenIV)),
+            Game::XY => methods.extend([
+                Hunttype::ChainFishing,
+                Hunttype::Horde,
+                Hunttype::Radar(Radar::GenVI),
+            ]),
+            Game::OmegaRubyAlphaSapphire => {
+                methods.extend([Hunttype::ChainFishing, Hunttype::Horde])
+            }
+            Game::BrilliantDiamondShiningPearl => methods.push(Hunttype::Radar(Radar::BDSP)),
+            Game::SunMoon | Game::UltraSunUltraMoon => methods.push(Hunttype::SOS),
+            Game::LegendsArceus => methods.extend([
+                Hunttype::MassOutbreak(Research::default()),
+                Hunttype::MassiveMassOutbreak(Research::default()),
+            ]),
+            Game::ScarletViolet => methods.push(Hunttype::Outbreak(OutbreakBonus::default())),
+            _ => {}
+        }
+
+        methods
+    }
+
+    /// Custom methods are supported in every game
+    pub fn supports(&self, hunttype: Hunttype) -> bool {
+        matches!(hunttype, Hunttype::Custom(_))
+            || self.methods().iter().any(|m| m.same_method(&hunttype))
+    }
+
+    pub fn repr(&self) -> &'static str {
+        match self {
+            Game::GoldSilverCrystal => "Gold, Silver & Crystal",
+            Game::RubySapphireEmerald => "Ruby, Sapphire & Emerald",
+            Game::FireRedLeafGreen => "FireRed & LeafGreen",
+            Game::DiamondPearlPlatinum => "Diamond, Pearl & Platinum",
+            Game::HeartGoldSoulSilver => "HeartGold & SoulSilver",
+            Game::BlackWhite => "Black & White",
+            Game::Black2White2 => "Black 2 & White 2",
+            Game::XY => "X & Y",
+            Game::OmegaRubyAlphaSapphire => "Omega Ruby & Alpha Sapphire",
+            Game::SunMoon => "Sun & Moon",
+            Game::UltraSunUltraMoon => "Ultra Sun & Ultra Moon",
+            Game::LetsGo => "Let's Go Pikachu & Eevee",
+            Game::SwordShield => "Sword & Shield",
+            Game::BrilliantDiamondShiningPearl => "Brilliant Diamond & Shining Pearl",
+            Game::LegendsArceus => "Legends: Arceus",
+            Game::ScarletViolet => "Scarlet & Violet",
+        }
+    }
+}
+
+impl From<Game> for &'static str {
+    fn from(val: Game) -> Self {
+        match val {
+            Game::GoldSilverCrystal => "GoldSilverCrystal",
+            Game::RubySapphireEmerald => "RubySapphireEmerald",
+            Game::FireRedLeafGreen => "FireRedLeafGreen",
+            Game::DiamondPearlPlatinum => "DiamondPearlPlatinum",
+            Game::HeartGoldSoulSilver => "HeartGoldSoulSilver",
+            Game::BlackWhite => "BlackWhite",
+            Game::Black2White2 => "Black2White2",
+            Game::XY => "XY",
+            Game::OmegaRubyAlphaSapphire => "OmegaRubyAlphaSapphire",
+            Game::SunMoon => "SunMoon",
+            Game::UltraSunUltraMoon => "UltraSunUltraMoon",
+            Game::LetsGo => "LetsGo",
+            Game::SwordShield => "SwordShield",
+            Game::BrilliantDiamondShiningPearl => "BrilliantDiamondShiningPearl",
+            Game::LegendsArceus => "LegendsArceus",
+            Game::ScarletViolet => "ScarletViolet",
+        }
+    }
+}
+
+impl TryFrom<String> for Game {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Game::ALL
+            .into_iter()
+            .find(|game| <&'static str>::from(*game) == value)
+            .ok_or(format!("Unknown game: {value}"))
+    }
+}
+
+impl From<Game> for components::SelectOption {
+    fn from(val: Game) -> Self {
+        (val.repr(), val.into()).into()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<backend::Game> for Game {
+    fn from(value: backend::Game) -> Self {
+        match value {
+            backend::Game::GoldSilverCrystal => Self::GoldSilverCrystal,
+            backend::Game::RubySapphireEmerald => Self::RubySapphireEmerald,
+            backend::Game::FireRedLeafGreen => Self::FireRedLeafGreen,
+            backend::Game::DiamondPearlPlatinum => Self::DiamondPearlPlatinum,
+            backend::Game::HeartGoldSoulSilver => Self::HeartGoldSoulSilver,
+            backend::Game::BlackWhite => Self::BlackWhite,
+            backend::Game::Black2White2 => Self::Black2White2,
+            backend::Game::XY => Self::XY,
+            backend::Game::OmegaRubyAlphaSapphire => Self::OmegaRubyAlphaSapphire,
+            backend::Game::SunMoon => Self::SunMoon,
+            backend::Game::UltraSunUltraMoon => Self::UltraSunUltraMoon,
+            backend::Game::LetsGo => Self::LetsGo,
+            backend::Game::SwordShield => Self::SwordShield,
+            backend::Game::BrilliantDiamondShiningPearl => Self::BrilliantDiamondShiningPearl,
+            backend::Game::LegendsArceus => Self::LegendsArceus,
+            backend::Game::ScarletViolet => Self::ScarletViolet,
+        }
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<Game> for backend::Game {
+    fn from(value: Game) -> Self {
+        match value {
+            Game::GoldSilverCrystal => Self::GoldSilverCrystal,
+            Game::RubySapphireEmerald => Self::RubySapphireEmerald,
+            Game::FireRedLeafGreen => Self::FireRedLeafGreen,
+            Game::DiamondPearlPlatinum => Self::DiamondPearlPlatinum,
+            Game::HeartGoldSoulSilver => Self::HeartGoldSoulSilver,
+            Game::BlackWhite => Self::BlackWhite,
+            Game::Black2White2 => Self::Black2White2,
+            Game::XY => Self::XY,
+            Game::OmegaRubyAlphaSapphire => Self::OmegaRubyAlphaSapphire,
+            Game::SunMoon => Self::SunMoon,
+            Game::UltraSunUltraMoon => Self::UltraSunUltraMoon,
+            Game::LetsGo => Self::LetsGo,
+            Game::SwordShield => Self::SwordShield,
+            Game::BrilliantDiamondShiningPearl => Self::BrilliantDiamondShiningPearl,
+            Game::LegendsArceus => Self::LegendsArceus,
+            Game::ScarletViolet => Self::ScarletViolet,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_method(game: Game, hunttype: Hunttype) -> bool {
+        game.methods().iter().any(|m| m.same_method(&hunttype))
+    }
+
+    #[test]
+    fn gen_iv_has_no_charm_or_new_odds() {
+        for game in [Game::DiamondPearlPlatinum, Game::HeartGoldSoulSilver] {
+            assert!(!game.has_charm(), "{game:?}");
+            assert_eq!(game.odds(), 8192.0);
+            assert!(!has_method(game, Hunttype::NewOdds), "{game:?}");
+            assert_eq!(game.methods()[0], Hunttype::OldOdds);
+        }
+    }
+
+    #[test]
+    fn no_masuda_without_breeding() {
+        for game in [Game::LetsGo, Game::LegendsArceus] {
+            assert!(
+                !game
+                    .methods()
+                    .iter()
+                    .any(|m| matches!(m, Hunttype::Masuda(_))),
+                "{game:?}"
+            );
+        }
+        assert!(has_method(
+            Game::SwordShield,
+            Hunttype::Masuda(Masuda::GenVI)
+        ));
+    }
+
+    #[test]
+    fn charm_starts_with_black_2_and_white_2() {
+        assert!(!Game::BlackWhite.has_charm());
+        assert!(Game::Black2White2.has_charm());
+        // the charm came before the new odds
+        assert_eq!(Game::Black2White2.odds(), 8192.0);
+
+        for game in Game::ALL.into_iter().filter(|g| g.generation() >= 6) {
+            assert!(game.has_charm(), "{game:?}");
+            assert_eq!(game.odds(), 4096.0);
+        }
+    }
+
+    #[test]
+    fn every_game_supports_its_methods() {
+        for game in Game::ALL {
+            for method in game.methods() {
+                assert!(game.supports(method), "{game:?} {method:?}");
+            }
+        }
+        assert!(!Game::XY.supports(Hunttype::SOS));
+    }
+}
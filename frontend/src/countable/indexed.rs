@@ -1,44 +1,202 @@
 use super::*;
 use leptos::{create_effect, expect_context};
+use operation::{Operation, OperationKind};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
 
 #[cfg(not(docsrs))]
 const IDB_VERSION: &str = env!("IDB_TALLYWEB_VERSION");
 #[cfg(docsrs)]
 const IDB_VERSION: &str = "1";
 
+/// Database used before every account had its own
+const LEGACY_DATABASE: &str = "TallyWeb";
+
+thread_local! {
+    /// database of the logged in account, see [`IndexedSaveHandler::new`]
+    static DATABASE: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Name of the local database of `user`, every account keeps its data separate
+pub fn database_name(user: uuid::Uuid) -> String {
+    format!("{LEGACY_DATABASE}-{user}")
+}
+
 #[allow(dead_code)]
 #[derive(Clone)]
 pub struct IndexedSaveHandler {
     version: u32,
+    name: String,
 }
 
 impl IndexedSaveHandler {
-    pub async fn new() -> Result<Self, AppError> {
+    /**
+        `New`
+
+        Opens the local database of `user`, upgrading it when needed.
+        Countables of `user` still stored in the shared database of older versions are moved over.
+        Operations recorded for another account are dropped, they are still stored in its database.
+
+        # Errors
+          * [AppError::Environment] when `IDB_TALLYWEB_VERSION` is not a number
+          * [AppError::Indexed] when the database can not be opened or upgraded,
+            or the countables of `user` can not be moved out of the shared database.
+            They stay in the shared database then and are part of [`export`].
+    */
+    pub async fn new(user: uuid::Uuid) -> Result<Self, AppError> {
         let version = IDB_VERSION
             .parse()
             .map_err(|_| AppError::Environment("IDB_VERSION".to_string()))?;
+        let name = database_name(user);
+
+        let factory = indexed_db::Factory::<AppError>::get()?;
+        factory.open(&name, version, migrate).await?.close();
+
+        let this = Self { version, name };
+        this.adopt_legacy(user).await?;
+        select_database(&this.name);
+
+        Ok(this)
+    }
+
+    /// Removes everything stored in the local database of `user`
+    pub async fn forget(user: uuid::Uuid) -> Result<(), AppError> {
+        let name = database_name(user);
+        deselect_database(&name);
 
         let factory = indexed_db::Factory::<AppError>::get()?;
-        factory
-            .open("TallyWeb", version, |evt| async move {
-                let _ = evt.database().delete_object_store("Countable");
-                let obj_builder = evt.database().build_object_store("Countable");
-                obj_builder.create()?;
+        let db = factory.open_latest_version(&name).await?;
+        db.transaction(&["Countable", "Operation"])
+            .rw()
+            .run(|transaction| async move {
+                transaction.object_store("Countable")?.clear().await?;
+                transaction.object_store("Operation")?.clear().await?;
                 Ok(())
             })
             .await?;
+        db.close();
 
-        Ok(Self { version })
+        Ok(())
     }
 
-    #[allow(dead_code)]
-    pub async fn reset() -> Result<(), AppError> {
+    /// Countables stored in the local database of `user`
+    pub async fn load(user: uuid::Uuid) -> Result<Vec<Countable>, AppError> {
         let factory = indexed_db::Factory::<AppError>::get()?;
-        let db = factory.open_latest_version("TallyWeb").await?;
-        db.transaction(&["Countable"])
+        let db = factory.open_latest_version(&database_name(user)).await?;
+        if db.object_store_names().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let countables = db
+            .transaction(&["Countable"])
+            .run(|transaction| async move {
+                let countables = transaction
+                    .object_store("Countable")?
+                    .get_all(None)
+                    .await?
+                    .into_iter()
+                    .map(Countable::from_js)
+                    .collect::<Result<Vec<Countable>, AppError>>()?;
+                Ok(countables)
+            })
+            .await?;
+        db.close();
+
+        Ok(countables)
+    }
+
+    /// Writes the pending operations, replacing the ones stored before
+    pub async fn save_operations() -> Result<(), AppError> {
+        let Some(name) = DATABASE.with_borrow(|db| db.clone()) else {
+            return Ok(());
+        };
+
+        let factory = indexed_db::Factory::<AppError>::get()?;
+        let db = factory.open_latest_version(&name).await?;
+        db.transaction(&["Operation"])
             .rw()
             .run(|transaction| async move {
-                transaction.object_store("Countable")?.clear().await?;
+                write_operations(transaction.object_store("Operation")?).await?;
+                Ok(())
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /**
+        `Adopt Legacy`
+
+        Moves the countables of `user` and the operations on them
+        out of the database shared by all accounts before, see [`LEGACY_DATABASE`].
+        The shared database is deleted once no countables are left in it.
+    */
+    async fn adopt_legacy(&self, user: uuid::Uuid) -> Result<(), AppError> {
+        let factory = indexed_db::Factory::<AppError>::get()?;
+        // upgrades the old database so its records can be read, creates it when it does not exist
+        let legacy = factory.open(LEGACY_DATABASE, self.version, migrate).await?;
+
+        let owner = JsValue::from_str(&user.to_string());
+        let (countables, operations, remaining) = legacy
+            .transaction(&["Countable", "Operation"])
+            .rw()
+            .run(move |tr| async move {
+                let obj = tr.object_store("Countable")?;
+                let countables = obj
+                    .index("owner")?
+                    .get_all_in(owner.clone()..=owner, None)
+                    .await?
+                    .into_iter()
+                    .map(Countable::from_js)
+                    .collect::<Result<Vec<Countable>, AppError>>()?;
+                for c in countables.iter() {
+                    obj.delete(&JsValue::from_str(&c.uuid().to_string()))
+                        .await?;
+                }
+
+                let ops = tr.object_store("Operation")?;
+                let mut operations = Vec::new();
+                for op in ops.get_all(None).await? {
+                    let op = operation_from_js(op)?;
+                    if owns_operation(user, &countables, &op)? {
+                        ops.delete(&JsValue::from_str(&op.uuid.to_string())).await?;
+                        operations.push(op);
+                    }
+                }
+
+                Ok((countables, operations, obj.count().await?))
+            })
+            .await?;
+        legacy.close();
+
+        if remaining == 0 {
+            // finishes once tabs of older versions are closed, no need to wait for that
+            leptos::spawn_local(async move {
+                let _ = factory.delete_database(LEGACY_DATABASE).await;
+            });
+        }
+        if countables.is_empty() && operations.is_empty() {
+            return Ok(());
+        }
+
+        let factory = indexed_db::Factory::<AppError>::get()?;
+        let db = factory.open_latest_version(&self.name).await?;
+        db.transaction(&["Countable", "Operation"])
+            .rw()
+            .run(move |tr| async move {
+                let obj = tr.object_store("Countable")?;
+                for c in countables {
+                    let key = JsValue::from_str(&c.uuid().to_string());
+                    obj.put_kv(&key, &c.as_js()?).await?;
+                }
+                let obj = tr.object_store("Operation")?;
+                for op in operations {
+                    let key = JsValue::from_str(&op.uuid.to_string());
+                    let value =
+                        js_sys::JSON::parse(&serde_json::to_string(&op).map_err(AppError::from)?)
+                            .map_err(AppError::from)?;
+                    obj.put_kv(&key, &value).await?;
+                }
                 Ok(())
             })
             .await?;
@@ -46,35 +204,249 @@ impl IndexedSaveHandler {
         Ok(())
     }
 
+    /**
+        `Sync Store`
+
+        Sends the operations stored locally to the server and pulls the changes made since.
+        When the server can not be reached the operations are replayed on `store` instead
+        and stay pending until the next save.
+        Without any data from the server the locally stored countables are used.
+    */
     pub async fn sync_store(&self, store: &mut CountableStore) -> Result<(), AppError> {
         let factory = indexed_db::Factory::get()?;
         let owner = store.owner();
-        let db = factory.open_latest_version("TallyWeb").await?;
-        let map = db
-            .transaction(&["Countable"])
+        let db = factory.open_latest_version(&self.name).await?;
+        let (map, operations) = db
+            .transaction(&["Countable", "Operation"])
             .run(move |evt| async move {
                 let obj = evt.object_store("Countable")?;
-                let map = obj
-                    .get_all(None)
-                    .await?
+                let records = if owner.is_nil() {
+                    obj.get_all(None).await?
+                } else {
+                    let owner = JsValue::from_str(&owner.to_string());
+                    obj.index("owner")?
+                        .get_all_in(owner.clone()..=owner, None)
+                        .await?
+                };
+                let map = records
                     .into_iter()
                     .map(Countable::from_js)
                     .collect::<Result<Vec<Countable>, AppError>>()?
                     .into_iter()
                     .map(|c| (c.uuid().into(), c))
                     .collect::<std::collections::HashMap<CountableId, Countable>>();
-                Ok(map)
+                let operations = evt
+                    .object_store("Operation")?
+                    .get_all(None)
+                    .await?
+                    .into_iter()
+                    .map(operation_from_js)
+                    .collect::<Result<Vec<Operation>, AppError>>()?;
+                Ok((map, operations))
             })
             .await?;
 
-        let local_store = CountableStore::new(owner, map);
-        store.checked_mut().merge(local_store.checked())?;
+        operation::restore(operations);
+        let has_pending = operation::has_pending();
+        let sent = outbox::flush().await;
+
+        if store.is_empty() {
+            let local_store = CountableStore::new(owner, map);
+            store.checked_mut().merge(local_store.checked())?;
+        } else if sent.is_err() {
+            for op in operation::pending() {
+                op.apply(store)?
+            }
+        } else if has_pending {
+            let remote = server::changes_since(owner, store.sync_cursor()).await?;
+            store.merge_remote(remote);
+        }
         self.save(Box::new(store.clone()), Box::new(|_| ()))?;
 
         Ok(())
     }
 }
 
+/// Makes `name` the database of the logged in account,
+/// the pending operations of another account are dropped
+fn select_database(name: &str) {
+    if DATABASE.replace(Some(name.to_string())).as_deref() != Some(name) {
+        operation::clear()
+    }
+}
+
+/// Undoes [`select_database`] when `name` is the database of the logged in account
+fn deselect_database(name: &str) {
+    if DATABASE.with_borrow(|db| db.as_deref() == Some(name)) {
+        DATABASE.set(None);
+        operation::clear();
+    }
+}
+
+/// Whether `op` of the shared database belongs to `user`, who owns `countables`
+fn owns_operation(
+    user: uuid::Uuid,
+    countables: &[Countable],
+    op: &Operation,
+) -> Result<bool, AppError> {
+    Ok(match &op.kind {
+        OperationKind::Create(c) => CountableRecord::new(c)?.owner == user,
+        _ => countables.iter().any(|c| c.uuid() == op.countable.into()),
+    })
+}
+
+/**
+    `Export`
+
+    Reads everything stored in the local database as json,
+    used to keep a copy of the local data when the database can not be upgraded.
+    Data of older versions that was not moved out of the shared database yet is added as `legacy`.
+
+    # Errors
+      * [AppError::Indexed] when the database can not be opened or nothing is stored
+      * [AppError::Javascript] when a record can not be converted to json
+*/
+pub async fn export(user: uuid::Uuid) -> Result<String, AppError> {
+    let local = dump(&database_name(user)).await?;
+    let legacy = dump(LEGACY_DATABASE).await?;
+    if local.is_none() && legacy.is_none() {
+        return Err(AppError::Indexed(String::from("local database is empty")));
+    }
+
+    let mut export = local.unwrap_or_else(|| serde_json::json!({ "version": 0, "stores": {} }));
+    if let Some(legacy) = legacy {
+        export["legacy"] = legacy;
+    }
+
+    Ok(export.to_string())
+}
+
+/// Version and records of every store of the database `name`, `None` when it has no stores
+async fn dump(name: &str) -> Result<Option<serde_json::Value>, AppError> {
+    let factory = indexed_db::Factory::<AppError>::get()?;
+    let db = factory.open_latest_version(name).await?;
+    let version = db.version();
+    let names = db.object_store_names();
+    if names.is_empty() {
+        return Ok(None);
+    }
+
+    let store_names = names.clone();
+    let stores = db
+        .transaction(&names.iter().map(String::as_str).collect::<Vec<_>>())
+        .run(move |tr| async move {
+            let mut stores = serde_json::Map::new();
+            for name in store_names {
+                let mut records = Vec::new();
+                for record in tr.object_store(&name)?.get_all(None).await? {
+                    let json = js_sys::JSON::stringify(&record)
+                        .map_err(AppError::from)?
+                        .as_string()
+                        .unwrap_or_default();
+                    records.push(serde_json::from_str(&json).map_err(AppError::from)?);
+                }
+                stores.insert(name, serde_json::Value::Array(records));
+            }
+            Ok(stores)
+        })
+        .await?;
+    db.close();
+
+    Ok(Some(
+        serde_json::json!({ "version": version, "stores": stores }),
+    ))
+}
+
+/// Countable as it is stored in IndexedDB,
+/// `owner` and `parent` are copied out of the countable so they can be indexed
+#[derive(Serialize, Deserialize)]
+pub struct CountableRecord {
+    pub owner: uuid::Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<uuid::Uuid>,
+    pub countable: Countable,
+}
+
+impl CountableRecord {
+    pub fn new(countable: &Countable) -> Result<Self, AppError> {
+        let (owner, parent) = match countable {
+            Countable::Counter(c) => {
+                let c = c.lock()?;
+                (c.owner_uuid, c.parent.map(uuid::Uuid::from))
+            }
+            Countable::Phase(p) => {
+                let p = p.lock()?;
+                (p.owner_uuid, Some(p.parent.into()))
+            }
+            Countable::Chain(_) => todo!(),
+        };
+
+        Ok(Self {
+            owner,
+            parent,
+            countable: countable.clone(),
+        })
+    }
+
+    /// Turns a countable stored as json by versions before 4 into the json of its record
+    fn upgrade(countable: &str) -> Result<String, AppError> {
+        let countable: Countable = serde_json::from_str(countable)?;
+        Ok(serde_json::to_string(&Self::new(&countable)?)?)
+    }
+}
+
+/**
+    `Migrate`
+
+    Upgrades the local database one version at a time so no data is lost on upgrades.
+    When a step fails the upgrade is aborted and the database stays at its old version.
+
+    Versions:
+      1. to 3. `Countable` and `Operation` stores, these were recreated on every upgrade
+      4. countables are stored as [`CountableRecord`] with indexes by `owner` and `parent`
+*/
+async fn migrate(
+    evt: indexed_db::VersionChangeEvent<AppError>,
+) -> Result<(), indexed_db::Error<AppError>> {
+    let db = evt.database();
+    let tr = evt.transaction();
+
+    for version in evt.old_version() + 1..=evt.new_version() {
+        match version {
+            1..=3 => {
+                let names = db.object_store_names();
+                for name in ["Countable", "Operation"] {
+                    if !names.iter().any(|n| n == name) {
+                        db.build_object_store(name).create()?;
+                    }
+                }
+            }
+            4 => {
+                let obj = tr.object_store("Countable")?;
+                let mut cursor = obj.cursor().open().await?;
+                while let Some(value) = cursor.value() {
+                    let record = CountableRecord::upgrade(
+                        &js_sys::JSON::stringify(&value)
+                            .map_err(AppError::from)?
+                            .as_string()
+                            .unwrap_or_default(),
+                    )?;
+                    cursor
+                        .update(&js_sys::JSON::parse(&record).map_err(AppError::from)?)
+                        .await?;
+                    cursor.advance(1).await?;
+                }
+
+                obj.build_index("owner", "owner").create()?;
+                obj.build_index("parent", "parent").create()?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
 impl SaveHandler for IndexedSaveHandler {
     fn save(
         &self,
@@ -82,21 +454,25 @@ impl SaveHandler for IndexedSaveHandler {
         on_error: Box<dyn Fn(&dyn std::error::Error) + 'static>,
     ) -> Result<(), AppError> {
         let msg = expect_context::<components::MessageJar>();
+        let name = self.name.clone();
 
         #[allow(clippy::borrowed_box)]
         let action = leptos::create_action(move |value: &Box<dyn Savable>| {
             let value = value.clone_box();
+            let name = name.clone();
             async move {
                 let factory = indexed_db::Factory::<AppError>::get()?;
-                let db = factory.open_latest_version("TallyWeb").await?;
+                let db = factory.open_latest_version(&name).await?;
                 let store_name = value.indexed_db_name();
 
-                db.transaction(&[store_name.as_str()])
+                db.transaction(&[store_name.as_str(), "Operation"])
                     .rw()
                     .run(move |tr| {
                         let obj = tr.object_store(&store_name);
+                        let operations = tr.object_store("Operation");
                         async move {
                             value.save_indexed(obj?).await?;
+                            write_operations(operations?).await?;
                             Ok(())
                         }
                     })
@@ -124,3 +500,158 @@ impl SaveHandler for IndexedSaveHandler {
         Box::new(self.clone())
     }
 }
+
+async fn write_operations(obj: indexed_db::ObjectStore<AppError>) -> Result<(), AppError> {
+    obj.clear().await?;
+    for op in operation::pending() {
+        let key = JsValue::from_str(&op.uuid.to_string());
+        let value = js_sys::JSON::parse(&serde_json::to_string(&op)?)?;
+        obj.put_kv(&key, &value).await?;
+    }
+
+    Ok(())
+}
+
+fn operation_from_js(val: wasm_bindgen::JsValue) -> Result<Operation, AppError> {
+    Ok(serde_json::from_str(
+        &js_sys::JSON::stringify(&val)?
+            .as_string()
+            .unwrap_or_default(),
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(countable: &Countable) -> serde_json::Value {
+        serde_json::to_value(countable).unwrap()
+    }
+
+    #[test]
+    fn upgrade_keeps_countables() {
+        let owner = uuid::Uuid::new_v4();
+        let counter = Countable::new("Counter", CountableKind::Counter, owner, None);
+        let phase = Countable::new(
+            "Phase",
+            CountableKind::Phase,
+            owner,
+            Some(counter.uuid().into()),
+        );
+        if let Countable::Phase(p) = &phase {
+            let mut p = p.lock().unwrap();
+            p.count = 1234;
+            p.success = true;
+            p.hunt_type = Hunttype::Masuda(Masuda::GenV);
+        }
+
+        for countable in [&counter, &phase] {
+            let stored = serde_json::to_string(countable).unwrap();
+            let upgraded = CountableRecord::upgrade(&stored).unwrap();
+
+            let record: CountableRecord = serde_json::from_str(&upgraded).unwrap();
+            assert_eq!(record.owner, owner);
+            assert_eq!(json(&record.countable), json(countable));
+        }
+
+        let record: CountableRecord = serde_json::from_str(
+            &CountableRecord::upgrade(&serde_json::to_string(&phase).unwrap()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(record.parent, Some(counter.uuid()));
+    }
+
+    #[test]
+    fn counters_are_indexed_without_parent() {
+        let counter = Countable::new(
+            "Counter",
+            CountableKind::Counter,
+            uuid::Uuid::new_v4(),
+            None,
+        );
+        let upgraded = CountableRecord::upgrade(&serde_json::to_string(&counter).unwrap()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&upgraded).unwrap();
+        // IndexedDB leaves records without the key path out of the index
+        assert!(value.get("parent").is_none());
+    }
+
+    fn operation(countable: CountableId, kind: OperationKind) -> Operation {
+        Operation {
+            uuid: uuid::Uuid::new_v4(),
+            countable,
+            device: uuid::Uuid::new_v4(),
+            clock: 1,
+            kind,
+        }
+    }
+
+    #[test]
+    fn switching_accounts_drops_pending_operations() {
+        let (first, second) = (
+            database_name(uuid::Uuid::new_v4()),
+            database_name(uuid::Uuid::new_v4()),
+        );
+        assert_ne!(first, second);
+        let key = CountableId::from(uuid::Uuid::new_v4());
+
+        select_database(&first);
+        operation::record(key, OperationKind::AddCount(1));
+        // reopening the same account keeps them
+        select_database(&first);
+        assert!(operation::has_pending());
+
+        select_database(&second);
+        assert!(!operation::has_pending());
+        assert_eq!(DATABASE.with_borrow(|db| db.clone()), Some(second.clone()));
+    }
+
+    #[test]
+    fn forgetting_an_account() {
+        let (current, other) = (
+            database_name(uuid::Uuid::new_v4()),
+            database_name(uuid::Uuid::new_v4()),
+        );
+        select_database(&current);
+        operation::record(
+            CountableId::from(uuid::Uuid::new_v4()),
+            OperationKind::AddCount(1),
+        );
+
+        deselect_database(&other);
+        assert!(operation::has_pending());
+        assert_eq!(DATABASE.with_borrow(|db| db.clone()), Some(current.clone()));
+
+        deselect_database(&current);
+        assert!(!operation::has_pending());
+        assert_eq!(DATABASE.with_borrow(|db| db.clone()), None);
+        // without an account nothing is written
+        assert!(futures::executor::block_on(IndexedSaveHandler::save_operations()).is_ok());
+    }
+
+    #[test]
+    fn only_operations_of_the_account_are_adopted() {
+        let (user, other) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let own = Countable::new("Counter", CountableKind::Counter, user, None);
+        let foreign = Countable::new("Counter", CountableKind::Counter, other, None);
+        let countables = [own.clone()];
+
+        let created = operation(own.uuid().into(), OperationKind::Create(own.clone()));
+        let counted = operation(own.uuid().into(), OperationKind::AddCount(1));
+        let foreign_created = operation(
+            foreign.uuid().into(),
+            OperationKind::Create(foreign.clone()),
+        );
+        let foreign_counted = operation(foreign.uuid().into(), OperationKind::AddCount(1));
+
+        assert!(owns_operation(user, &countables, &created).unwrap());
+        assert!(owns_operation(user, &countables, &counted).unwrap());
+        assert!(!owns_operation(user, &countables, &foreign_created).unwrap());
+        assert!(!owns_operation(user, &countables, &foreign_counted).unwrap());
+    }
+
+    #[test]
+    fn upgrade_rejects_unknown_records() {
+        assert!(CountableRecord::upgrade("{\"owner\": 1}").is_err());
+        assert!(CountableRecord::upgrade("not json").is_err());
+    }
+}
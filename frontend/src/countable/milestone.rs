@@ -0,0 +1,261 @@
+//! Points of a hunt worth pointing out, like passing the odds.
+//!
+//! Which milestones are shown is a preference of the user,
+//! the progressbar of the infobox marks them and a message is shown once a hunt passes one.
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Custom counts a user can add
+pub const MAX_COUNTS: usize = 10;
+
+/// The milestones shown for every hunt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Milestones {
+    /// the chance to be done passes 50%
+    pub half: bool,
+    /// the rolls reach the odds
+    pub odds: bool,
+    /// the rolls reach twice the odds
+    pub double_odds: bool,
+    /// encounters chosen by the user
+    pub counts: Vec<i32>,
+    /// also show a notification of the browser
+    pub notify: bool,
+}
+
+impl Default for Milestones {
+    fn default() -> Self {
+        Self {
+            half: true,
+            odds: true,
+            double_odds: true,
+            counts: Vec::new(),
+            notify: false,
+        }
+    }
+}
+
+impl Milestones {
+    /**
+        `Parse Milestone Counts`
+
+        Reads encounters separated by commas, duplicates are dropped
+
+        # Errors
+          * [AppError::InvalidField]
+    */
+    pub fn parse_counts(input: &str) -> Result<Vec<i32>, AppError> {
+        let mut counts = input
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(|c| {
+                c.parse::<i32>().map_err(|_| {
+                    AppError::InvalidField(
+                        "milestones".into(),
+                        format!("`{c}` is not a number of encounters"),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        counts.sort_unstable();
+        counts.dedup();
+
+        Ok(counts)
+    }
+
+    /**
+        `Validate Milestones`
+
+        # Errors
+          * [AppError::InvalidField]
+    */
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.counts.len() > MAX_COUNTS {
+            Err(AppError::InvalidField(
+                "milestones".into(),
+                format!("can be at most {MAX_COUNTS} encounters"),
+            ))?
+        }
+        if self.counts.iter().any(|c| *c <= 0) {
+            Err(AppError::InvalidField(
+                "milestones".into(),
+                "have to be positive".into(),
+            ))?
+        }
+
+        Ok(())
+    }
+
+    /**
+        `Milestone Markers`
+
+        # Arguments
+          * `needed`: shinies the progress is measured against, see [CountableStore::needed]
+          * `odds`: base odds of a single roll
+          * `rolls_at`: rolls after the given amount of encounters in total
+
+        # Returns
+          * `Vec<Marker>`: The enabled milestones, empty once nothing is needed anymore
+    */
+    pub fn markers(&self, needed: i32, odds: f64, rolls_at: impl Fn(i32) -> i32) -> Vec<Marker> {
+        if needed <= 0 {
+            return Vec::new();
+        }
+
+        let odds_rolls = |times: i32| (odds * (times * needed) as f64).round() as i32;
+        let position = |rolls: i32| statistics::at_least(needed, rolls, odds);
+
+        let mut markers = Vec::new();
+        if self.half {
+            markers.push(Marker {
+                milestone: Milestone::Progress(0.5),
+                label: String::from("50% chance"),
+                position: 0.5,
+            })
+        }
+        for (enabled, times) in [(self.odds, 1), (self.double_odds, 2)] {
+            if enabled {
+                markers.push(Marker {
+                    milestone: Milestone::Rolls(odds_rolls(times)),
+                    label: format!("{times}x odds"),
+                    position: position(odds_rolls(times)),
+                })
+            }
+        }
+        for count in self.counts.iter() {
+            markers.push(Marker {
+                milestone: Milestone::Count(*count),
+                label: format!("{count} encounters"),
+                position: position(rolls_at(*count)),
+            })
+        }
+
+        markers
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Milestone {
+    /// the progress reaches this share
+    Progress(f64),
+    /// the rolls reach this amount
+    Rolls(i32),
+    /// the encounters reach this amount
+    Count(i32),
+}
+
+impl Milestone {
+    /// Whether a hunt went past this milestone from `before` to `now`
+    pub fn passed(&self, before: &Standing, now: &Standing) -> bool {
+        match *self {
+            Milestone::Progress(p) => before.progress < p && now.progress >= p,
+            Milestone::Rolls(r) => before.rolls < r && now.rolls >= r,
+            Milestone::Count(c) => before.count < c && now.count >= c,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub milestone: Milestone,
+    pub label: String,
+    /// where the milestone is on the progressbar, between 0 and 1
+    pub position: f64,
+}
+
+/// Where a hunt stands, milestones are passed between two of these
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Standing {
+    pub count: i32,
+    pub rolls: i32,
+    pub progress: f64,
+}
+
+/**
+    `Notify Milestone`
+
+    Shows a notification of the browser when the user allowed it,
+    does nothing when the browser does not support them
+*/
+pub fn notify(title: &str, body: &str) {
+    use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+    let supported = leptos::window().get("Notification").is_some();
+    if !supported || Notification::permission() != NotificationPermission::Granted {
+        return;
+    }
+
+    let options = NotificationOptions::new();
+    options.set_body(body);
+    let _ = Notification::new_with_options(title, &options);
+}
+
+/// Asks the user to allow notifications of the browser, if they did not decide yet
+pub fn request_permission() {
+    use web_sys::{Notification, NotificationPermission};
+
+    if leptos::window().get("Notification").is_some()
+        && Notification::permission() == NotificationPermission::Default
+    {
+        let _ = Notification::request_permission();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_counts_sorts_and_dedups() {
+        assert_eq!(
+            Milestones::parse_counts(" 500, 100,,500 ,1000").unwrap(),
+            vec![100, 500, 1000]
+        );
+        assert_eq!(Milestones::parse_counts("").unwrap(), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn parse_counts_rejects_other_input() {
+        for input in ["100, many", "1.5", "0x10", "99999999999"] {
+            assert!(
+                matches!(
+                    Milestones::parse_counts(input),
+                    Err(AppError::InvalidField(field, _)) if field == "milestones"
+                ),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn passed_once_when_crossing() {
+        let milestones = [
+            Milestone::Count(100),
+            Milestone::Rolls(300),
+            Milestone::Progress(0.5),
+        ];
+        let standings = (0..=200)
+            .map(|count| Standing {
+                count,
+                rolls: 3 * count,
+                progress: count as f64 / 200.0,
+            })
+            .collect::<Vec<_>>();
+
+        for milestone in milestones {
+            let passed = standings
+                .windows(2)
+                .filter(|w| milestone.passed(&w[0], &w[1]))
+                .count();
+            assert_eq!(passed, 1, "{milestone:?}");
+
+            // jumping over it counts as passing, going back does not
+            assert!(milestone.passed(&standings[0], &standings[200]));
+            assert!(!milestone.passed(&standings[200], &standings[0]));
+            assert!(!milestone.passed(&standings[150], &standings[150]));
+        }
+    }
+}
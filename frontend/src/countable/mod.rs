@@ -1,13 +1,25 @@
+mod game;
 pub(crate) mod indexed;
+pub(crate) mod milestone;
 pub(crate) mod nodes;
+pub(crate) mod operation;
+pub(crate) mod outbox;
 pub(crate) mod server;
 mod signal;
+pub(crate) mod simulation;
+pub(crate) mod statistics;
 pub(crate) mod store;
+pub(crate) mod validate;
 
-pub(crate) use super::{api, AppError, Savable, SaveHandler};
+pub(crate) use super::{api, custom_method::CustomMethod, AppError, Savable, SaveHandler};
 
 // re-export
-pub use nodes::{Countable, CountableId, CountableKind, Counter, Hunttype, Masuda};
+pub use game::Game;
+pub use nodes::{
+    Countable, CountableId, CountableKind, Counter, Hunttype, Masuda, OutbreakBonus, Radar,
+    Research,
+};
 pub(crate) use store::CountableStore as CS;
 pub type CountableStore = CS<store::Level, store::UnChecked>;
 pub use signal::ProvideStore;
+pub use statistics::{Hunt, Luck, Statistics};
@@ -149,17 +149,109 @@ impl Countable {
         self.is_archived_checked().unwrap()
     }
 
+    /// Copy that does not share its data with `self`
+    pub fn detached_checked(&self) -> Result<Self, AppError> {
+        Ok(match self {
+            Countable::Counter(c) => Countable::Counter(Arc::new(Mutex::new(c.lock()?.clone()))),
+            Countable::Phase(p) => Countable::Phase(Arc::new(Mutex::new(p.lock()?.clone()))),
+            Countable::Chain(_) => Err(AppError::Unsupported("Chain".into()))?,
+        })
+    }
+
+    pub fn set_revision_checked(&self, revision: i32) -> Result<(), AppError> {
+        match self {
+            Countable::Counter(c) => c.lock()?.revision = revision,
+            Countable::Phase(p) => p.lock()?.revision = revision,
+            Countable::Chain(_) => Err(AppError::Unsupported("Chain".into()))?,
+        }
+
+        Ok(())
+    }
+
+    /**
+        `Rebase Checked`
+
+        Resolves a conflict with `server`, the current server version of this countable.
+        The server version replaces the local data and the pending operations
+        on this countable are replayed on top of it, so no local change is lost.
+        Those operations are still sent to the server by the outbox.
+
+        # Errors
+          * [AppError::LockMutex]
+          * [AppError::Unsupported] for chains
+    */
+    pub fn rebase_checked(&self, server: &Countable) -> Result<(), AppError> {
+        let (owner, parent) = match (self, server) {
+            (Countable::Counter(local), Countable::Counter(server)) => {
+                let mut local = local.lock()?;
+                // the server does not send children
+                let children = std::mem::take(&mut local.children);
+                *local = Counter {
+                    children,
+                    ..server.lock()?.clone()
+                };
+                (local.owner_uuid, None)
+            }
+            (Countable::Phase(local), Countable::Phase(server)) => {
+                let mut local = local.lock()?;
+                *local = server.lock()?.clone();
+                (local.owner_uuid, Some(local.parent))
+            }
+            (Countable::Chain(_), _) | (_, Countable::Chain(_)) => {
+                Err(AppError::Unsupported("Chain".into()))?
+            }
+            _ => {
+                self.set_revision_checked(server.revision_checked()?)?;
+                return Ok(());
+            }
+        };
+
+        let key = CountableId::from(self.uuid());
+        let mut store = CountableStore::new(
+            owner,
+            std::collections::HashMap::from([(key, self.clone())]),
+        );
+        for mut op in operation::pending() {
+            // hunt settings of a counter are applied to all of its phases
+            if Some(op.countable) == parent
+                && matches!(
+                    op.kind,
+                    operation::OperationKind::SetHunttype(_)
+                        | operation::OperationKind::SetCharm(_)
+                        | operation::OperationKind::SetGame(_)
+                )
+            {
+                op.countable = key;
+            }
+            if op.countable == key {
+                op.apply(&mut store)?
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn revision_checked(&self) -> Result<i32, AppError> {
+        Ok(match self {
+            Countable::Counter(c) => c.lock()?.revision,
+            Countable::Phase(p) => p.lock()?.revision,
+            Countable::Chain(_) => Err(AppError::Unsupported("Chain".into()))?,
+        })
+    }
+
+    /// IndexedDB record of this countable, see [`indexed::CountableRecord`]
     pub fn as_js(&self) -> Result<wasm_bindgen::JsValue, AppError> {
-        Ok(js_sys::JSON::parse(&serde_json::to_string(&self)?)?)
+        let record = indexed::CountableRecord::new(self)?;
+        Ok(js_sys::JSON::parse(&serde_json::to_string(&record)?)?)
     }
 
     pub fn from_js(val: wasm_bindgen::JsValue) -> Result<Self, AppError> {
-        let this = serde_json::from_str(
+        let record: indexed::CountableRecord = serde_json::from_str(
             &js_sys::JSON::stringify(&val)?
                 .as_string()
                 .unwrap_or_default(),
         )?;
-        Ok(this)
+        Ok(record.countable)
     }
 }
 
@@ -190,7 +282,7 @@ impl Savable for Vec<Countable> {
         &self,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), leptos::ServerFnError>>>>
     {
-        Box::pin(api::update_countable_many(self.clone()))
+        Box::pin(save_countables(self.clone()))
     }
 
     fn message(&self) -> Option<leptos::View> {
@@ -228,7 +320,7 @@ impl Savable for Countable {
         &self,
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), leptos::ServerFnError>>>>
     {
-        Box::pin(api::update_countable_many(vec![self.clone()]))
+        Box::pin(save_countables(vec![self.clone()]))
     }
 
     fn message(&self) -> Option<leptos::View> {
@@ -244,6 +336,48 @@ impl Savable for Countable {
     }
 }
 
+/// Sends `list` to the server, on a conflict the conflicting countable is rebased
+/// on the server version and the remaining countables are sent again.
+/// Every change is recorded as an operation, so the local changes of a rebased countable
+/// reach the server through the outbox instead of overwriting the server version.
+async fn save_countables(list: Vec<Countable>) -> Result<(), leptos::ServerFnError> {
+    save_with(list, api::update_countable_many).await
+}
+
+/// [`save_countables`] sending the list with `update`
+async fn save_with<F, Fut>(mut list: Vec<Countable>, update: F) -> Result<(), leptos::ServerFnError>
+where
+    F: Fn(Vec<Countable>) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<(uuid::Uuid, i32)>, leptos::ServerFnError>>,
+{
+    loop {
+        let err = match update(list.clone()).await {
+            Ok(revisions) => {
+                for (key, revision) in revisions {
+                    if let Some(c) = list.iter().find(|c| c.uuid() == key) {
+                        c.set_revision_checked(revision)?
+                    }
+                }
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+
+        let AppError::Conflict(server) = AppError::from(err.clone()) else {
+            return Err(err);
+        };
+
+        // every conflict removes a countable from the list, so this ends
+        let Some(index) = list.iter().position(|c| c.uuid() == server.uuid()) else {
+            return Err(err);
+        };
+        list.remove(index).rebase_checked(&server)?;
+        if list.is_empty() {
+            return Ok(());
+        }
+    }
+}
+
 impl PartialEq for Countable {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -278,6 +412,8 @@ impl From<backend::DbCounter> for Countable {
             last_edit: value.last_edit,
             created_at: value.created_at,
             is_deleted: value.is_deleted,
+            revision: value.revision,
+            target: value.target,
         })))
     }
 }
@@ -285,6 +421,7 @@ impl From<backend::DbCounter> for Countable {
 #[cfg(feature = "ssr")]
 impl From<backend::DbPhase> for Countable {
     fn from(value: backend::DbPhase) -> Self {
+        let params = value.method_params();
         Self::Phase(Arc::new(Mutex::new(Phase {
             uuid: value.uuid,
             owner_uuid: value.owner_uuid,
@@ -292,13 +429,15 @@ impl From<backend::DbPhase> for Countable {
             name: value.name,
             count: value.count,
             time: chrono::Duration::milliseconds(value.time),
-            hunt_type: value.hunt_type.into(),
+            hunt_type: Hunttype::from(value.hunt_type).with_params(params),
+            game: value.game.map(Game::from),
             has_charm: value.has_charm,
             success: value.success,
             last_edit: value.last_edit,
             created_at: value.created_at,
             is_deleted: value.is_deleted,
             step_size: value.step_size,
+            revision: value.revision,
         })))
     }
 }
@@ -332,6 +471,12 @@ pub struct Counter {
     pub last_edit: chrono::NaiveDateTime,
     pub created_at: chrono::NaiveDateTime,
     pub is_deleted: bool,
+    /// server revision this counter is based on
+    #[serde(default)]
+    pub revision: i32,
+    /// amount of shinies the hunt needs, `None` when every shiny is hunted on its own
+    #[serde(default)]
+    pub target: Option<i32>,
 }
 
 impl Counter {
@@ -345,6 +490,8 @@ impl Counter {
             last_edit: chrono::Utc::now().naive_utc(),
             created_at: chrono::Utc::now().naive_utc(),
             is_deleted: false,
+            revision: 0,
+            target: None,
         }
     }
 }
@@ -359,6 +506,8 @@ impl Into<backend::DbCounter> for Counter {
             last_edit: self.last_edit,
             created_at: self.created_at,
             is_deleted: self.is_deleted,
+            revision: self.revision,
+            target: self.target,
         }
     }
 }
@@ -374,15 +523,32 @@ pub struct Phase {
     #[serde_as(as = "serde_with::DurationMilliSeconds<i64>")]
     pub time: chrono::Duration,
     pub hunt_type: Hunttype,
+    #[serde(default)]
+    pub game: Option<Game>,
     pub has_charm: bool,
     pub success: bool,
     pub last_edit: chrono::NaiveDateTime,
     pub created_at: chrono::NaiveDateTime,
     pub is_deleted: bool,
     pub step_size: i32,
+    /// server revision this phase is based on
+    #[serde(default)]
+    pub revision: i32,
 }
 
 impl Phase {
+    /// Base odds, taken from the game when it is known
+    pub fn odds(&self) -> f64 {
+        self.game
+            .map(|game| game.odds())
+            .unwrap_or(self.hunt_type.odds())
+    }
+
+    /// The charm only counts in games where it exists
+    pub fn charm_applies(&self) -> bool {
+        self.has_charm && self.game.is_none_or(|game| game.has_charm())
+    }
+
     fn new(name: String, owner_uuid: uuid::Uuid, parent: CountableId) -> Self {
         Self {
             uuid: uuid::Uuid::new_v4(),
@@ -391,6 +557,7 @@ impl Phase {
             name,
             last_edit: chrono::Utc::now().naive_utc(),
             created_at: chrono::Utc::now().naive_utc(),
+            step_size: 1,
             ..Default::default()
         }
     }
@@ -407,6 +574,11 @@ impl Into<backend::DbPhase> for Phase {
             count: self.count,
             time: self.time.num_milliseconds(),
             hunt_type: self.hunt_type.into(),
+            game: self.game.map(Game::into),
+            research_level: self.hunt_type.params().research_level,
+            outbreak_cleared: self.hunt_type.params().outbreak_cleared,
+            sparkling_power: self.hunt_type.params().sparkling_power,
+            custom_method: self.hunt_type.params().custom_method,
             has_charm: self.has_charm,
             dexnav_encounters: None,
             success: self.success,
@@ -414,6 +586,7 @@ impl Into<backend::DbPhase> for Phase {
             created_at: self.created_at,
             is_deleted: self.is_deleted,
             step_size: self.step_size,
+            revision: self.revision,
         }
     }
 }
@@ -430,6 +603,20 @@ pub enum Hunttype {
     SOS,
     // DexNav(DexNav),
     Masuda(Masuda),
+    /// Legends: Arceus
+    MassOutbreak(Research),
+    /// Legends: Arceus
+    MassiveMassOutbreak(Research),
+    /// Scarlet and Violet
+    Outbreak(OutbreakBonus),
+    /// X, Y, Omega Ruby and Alpha Sapphire, the count is the length of the chain
+    ChainFishing,
+    /// every horde counts once and has five Pokémon
+    Horde,
+    /// the count is the length of the chain
+    Radar(Radar),
+    /// `CustomMethod` of the owner with this uuid
+    Custom(uuid::Uuid),
     Mixed,
 }
 
@@ -439,20 +626,140 @@ pub enum Masuda {
     GenV,
     #[default]
     GenVI,
-    // TODO: make this a generation enum
     // TODO: gen VIII+ has 6 rerolls
 }
 
-impl Hunttype {
-    pub(crate) fn rolls(&self) -> impl Fn(i32, bool) -> i32 {
+/// Versions of the Poké Radar, the bonus of a chain differs between them
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Radar {
+    #[default]
+    GenIV,
+    GenVI,
+    BDSP,
+}
+
+impl Radar {
+    /// Longest chain that still raises the odds
+    const MAX_CHAIN: i32 = 40;
+
+    /// Rolls of a single patch when the chain has length `chain`
+    fn rolls_at(&self, chain: i32, has_charm: bool) -> f64 {
+        let chain = chain.min(Self::MAX_CHAIN);
+        match self {
+            // a patch is shiny with ⌈65535 / (8200 - 200 * chain)⌉ / 65536
+            Radar::GenIV => (65535.0 / (8200 - 200 * chain) as f64).ceil() / 8.0,
+            // same table on top of the new odds, the charm adds its rolls
+            Radar::GenVI => {
+                (65535.0 / (8200 - 200 * chain) as f64).ceil() / 8.0
+                    + if has_charm { 2.0 } else { 0.0 }
+            }
+            // every link of the chain adds a roll
+            Radar::BDSP => (1 + chain) as f64 + if has_charm { 2.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Pokédex research of the hunted species in Legends: Arceus
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Research {
+    #[default]
+    Incomplete,
+    Level10,
+    Perfect,
+}
+
+impl Research {
+    pub const ALL: [Research; 3] = [Research::Incomplete, Research::Level10, Research::Perfect];
+
+    fn bonus(&self) -> i32 {
+        match self {
+            Research::Incomplete => 0,
+            Research::Level10 => 1,
+            Research::Perfect => 3,
+        }
+    }
+
+    pub fn repr(&self) -> &'static str {
         match self {
+            Research::Incomplete => "Below level 10",
+            Research::Level10 => "Level 10",
+            Research::Perfect => "Perfect",
+        }
+    }
+}
+
+impl From<Research> for i16 {
+    fn from(value: Research) -> Self {
+        match value {
+            Research::Incomplete => 0,
+            Research::Level10 => 1,
+            Research::Perfect => 2,
+        }
+    }
+}
+
+impl From<i16> for Research {
+    fn from(value: i16) -> Self {
+        match value {
+            1 => Research::Level10,
+            2 => Research::Perfect,
+            _ => Research::Incomplete,
+        }
+    }
+}
+
+/// Bonus rolls of an outbreak in Scarlet and Violet
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OutbreakBonus {
+    /// Pokémon of the outbreak that were defeated before hunting
+    pub cleared: i32,
+    /// level of the sparkling power sandwich, 0 without one
+    pub sparkling_power: u8,
+}
+
+impl OutbreakBonus {
+    pub const MAX_SPARKLING_POWER: u8 = 3;
+
+    fn bonus(&self) -> i32 {
+        let cleared = match self.cleared {
+            c if c >= 60 => 2,
+            c if c >= 30 => 1,
+            _ => 0,
+        };
+        cleared + i32::from(self.sparkling_power.min(Self::MAX_SPARKLING_POWER))
+    }
+}
+
+impl Hunttype {
+    /// Methods that exist without a game, custom methods are added by the user
+    pub const ALL: [Hunttype; 14] = [
+        Hunttype::OldOdds,
+        Hunttype::NewOdds,
+        Hunttype::Masuda(Masuda::GenIV),
+        Hunttype::Masuda(Masuda::GenV),
+        Hunttype::Masuda(Masuda::GenVI),
+        Hunttype::SOS,
+        Hunttype::MassOutbreak(Research::Incomplete),
+        Hunttype::MassiveMassOutbreak(Research::Incomplete),
+        Hunttype::Outbreak(OutbreakBonus {
+            cleared: 0,
+            sparkling_power: 0,
+        }),
+        Hunttype::ChainFishing,
+        Hunttype::Horde,
+        Hunttype::Radar(Radar::GenIV),
+        Hunttype::Radar(Radar::GenVI),
+        Hunttype::Radar(Radar::BDSP),
+    ];
+
+    pub(crate) fn rolls(&self) -> Box<dyn Fn(i32, bool) -> i32> {
+        Box::new(match *self {
             Hunttype::OldOdds => |count, has_charm: bool| (count * if has_charm { 3 } else { 1 }),
             Hunttype::NewOdds => |count, has_charm: bool| (count * if has_charm { 3 } else { 1 }),
-            Hunttype::SOS => |count, has_charm: bool| match count {
-                c if c < 10 => count * if has_charm { 3 } else { 1 },
-                c if c < 20 => 10 + (count - 10) * if has_charm { 3 + 4 } else { 1 + 4 },
-                c if c < 30 => 60 + (count - 20) * if has_charm { 3 + 8 } else { 1 + 8 },
-                _ => 50 + (count - 30) * if has_charm { 3 + 13 } else { 1 + 12 },
+            // every ten calls of the chain add four rolls to each encounter, up to twelve
+            Hunttype::SOS => |count: i32, has_charm: bool| {
+                let bonus = (count - 10).max(0) + (count - 20).max(0) + (count - 30).max(0);
+                count * if has_charm { 3 } else { 1 } + 4 * bonus
             },
             Hunttype::Masuda(Masuda::GenIV) => {
                 |count, has_charm: bool| (count * if has_charm { 3 + 4 } else { 1 + 4 })
@@ -460,13 +767,60 @@ impl Hunttype {
             Hunttype::Masuda(_) => {
                 |count, has_charm: bool| (count * if has_charm { 3 + 5 } else { 1 + 5 })
             }
+            Hunttype::MassOutbreak(research) => {
+                return Box::new(move |count, has_charm: bool| {
+                    count * (1 + 25 + research.bonus() + if has_charm { 3 } else { 0 })
+                })
+            }
+            Hunttype::MassiveMassOutbreak(research) => {
+                return Box::new(move |count, has_charm: bool| {
+                    count * (1 + 12 + research.bonus() + if has_charm { 3 } else { 0 })
+                })
+            }
+            Hunttype::Outbreak(outbreak) => {
+                return Box::new(move |count, has_charm: bool| {
+                    count * (1 + outbreak.bonus() + if has_charm { 2 } else { 0 })
+                })
+            }
+            // every reel of the chain adds two rolls, up to a chain of 20
+            Hunttype::ChainFishing => |count: i32, has_charm: bool| {
+                let bonus = match count {
+                    c if c <= 21 => c * (c - 1) / 2,
+                    c => 210 + 20 * (c - 21),
+                };
+                count * if has_charm { 3 } else { 1 } + 2 * bonus
+            },
+            Hunttype::Horde => |count, has_charm: bool| (count * 5 * if has_charm { 3 } else { 1 }),
+            Hunttype::Radar(radar) => {
+                return Box::new(move |count, has_charm: bool| {
+                    // past the longest chain every patch has the same rolls
+                    let longest = radar.rolls_at(Radar::MAX_CHAIN, has_charm);
+                    let rolls = (0..count.min(Radar::MAX_CHAIN))
+                        .map(|chain| radar.rolls_at(chain, has_charm))
+                        .sum::<f64>()
+                        + (count - Radar::MAX_CHAIN).max(0) as f64 * longest;
+                    rolls.round() as i32
+                });
+            }
+            // the store evaluates custom methods, this is only used when the method was deleted
+            Hunttype::Custom(_) => |count, has_charm: bool| (count * if has_charm { 3 } else { 1 }),
             Hunttype::Mixed => unreachable!(),
+        })
+    }
+
+    /// Whether both are the same method, ignoring the values of the method
+    pub fn same_method(&self, other: &Hunttype) -> bool {
+        match (self, other) {
+            (Hunttype::Custom(a), Hunttype::Custom(b)) => a == b,
+            _ => <&'static str>::from(*self) == <&'static str>::from(*other),
         }
     }
 
     pub(crate) fn odds(&self) -> f64 {
         match self {
-            Hunttype::OldOdds | Hunttype::Masuda(Masuda::GenIV) => 8192.0,
+            Hunttype::OldOdds | Hunttype::Masuda(Masuda::GenIV) | Hunttype::Radar(Radar::GenIV) => {
+                8192.0
+            }
             _ => 4096.0,
         }
     }
@@ -479,6 +833,15 @@ impl Hunttype {
             Self::Masuda(Masuda::GenIV) => "Masuda (gen IV)",
             Self::Masuda(Masuda::GenV) => "Masuda (gen V)",
             Self::Masuda(Masuda::GenVI) => "Masuda (gen VI+)",
+            Self::MassOutbreak(_) => "Mass Outbreak",
+            Self::MassiveMassOutbreak(_) => "Massive Mass Outbreak",
+            Self::Outbreak(_) => "Outbreak",
+            Self::ChainFishing => "Chain Fishing",
+            Self::Horde => "Horde",
+            Self::Radar(Radar::GenIV) => "Poké Radar (gen IV)",
+            Self::Radar(Radar::GenVI) => "Poké Radar (gen VI)",
+            Self::Radar(Radar::BDSP) => "Poké Radar (BDSP)",
+            Self::Custom(_) => "Custom",
             Self::Mixed => "Mixed",
         }
     }
@@ -493,6 +856,15 @@ impl From<Hunttype> for &'static str {
             Hunttype::Masuda(Masuda::GenIV) => "MasudaGenIV",
             Hunttype::Masuda(Masuda::GenV) => "MasudaGenV",
             Hunttype::Masuda(Masuda::GenVI) => "MasudaGenVI",
+            Hunttype::MassOutbreak(_) => "MassOutbreak",
+            Hunttype::MassiveMassOutbreak(_) => "MassiveMassOutbreak",
+            Hunttype::Outbreak(_) => "Outbreak",
+            Hunttype::ChainFishing => "ChainFishing",
+            Hunttype::Horde => "Horde",
+            Hunttype::Radar(Radar::GenIV) => "RadarGenIV",
+            Hunttype::Radar(Radar::GenVI) => "RadarGenVI",
+            Hunttype::Radar(Radar::BDSP) => "RadarBDSP",
+            Hunttype::Custom(_) => "Custom",
             Hunttype::Mixed => "Mixed",
         }
     }
@@ -502,6 +874,12 @@ impl TryFrom<String> for Hunttype {
     type Error = String;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
+        if let Some(id) = value.strip_prefix("Custom:") {
+            return uuid::Uuid::parse_str(id)
+                .map(Self::Custom)
+                .map_err(|err| err.to_string());
+        }
+
         match value.as_str() {
             "OldOdds" => Ok(Self::OldOdds),
             "NewOdds" => Ok(Self::NewOdds),
@@ -509,8 +887,16 @@ impl TryFrom<String> for Hunttype {
             "MasudaGenIV" => Ok(Self::Masuda(Masuda::GenIV)),
             "MasudaGenV" => Ok(Self::Masuda(Masuda::GenV)),
             "MasudaGenVI" => Ok(Self::Masuda(Masuda::GenVI)),
+            "MassOutbreak" => Ok(Self::MassOutbreak(Research::default())),
+            "MassiveMassOutbreak" => Ok(Self::MassiveMassOutbreak(Research::default())),
+            "Outbreak" => Ok(Self::Outbreak(OutbreakBonus::default())),
+            "ChainFishing" => Ok(Self::ChainFishing),
+            "Horde" => Ok(Self::Horde),
+            "RadarGenIV" => Ok(Self::Radar(Radar::GenIV)),
+            "RadarGenVI" => Ok(Self::Radar(Radar::GenVI)),
+            "RadarBDSP" => Ok(Self::Radar(Radar::BDSP)),
             _ => Err(String::from(
-                "Hunttype should be one of the following: OldOdds, NewOdds, SOS, Masuda",
+                "Hunttype should be one of the following: OldOdds, NewOdds, SOS, Masuda, MassOutbreak, MassiveMassOutbreak, Outbreak, ChainFishing, Horde, Radar",
             )),
         }
     }
@@ -518,7 +904,10 @@ impl TryFrom<String> for Hunttype {
 
 impl From<Hunttype> for components::SelectOption {
     fn from(val: Hunttype) -> Self {
-        (val.repr(), val.into()).into()
+        match val {
+            Hunttype::Custom(id) => (val.repr().to_string(), format!("Custom:{id}")).into(),
+            _ => (val.repr(), val.into()).into(),
+        }
     }
 }
 
@@ -550,6 +939,17 @@ impl From<backend::Hunttype> for Hunttype {
             backend::Hunttype::MasudaGenIV => Self::Masuda(Masuda::GenIV),
             backend::Hunttype::MasudaGenV => Self::Masuda(Masuda::GenV),
             backend::Hunttype::MasudaGenVI => Self::Masuda(Masuda::GenVI),
+            backend::Hunttype::MassOutbreak => Self::MassOutbreak(Research::default()),
+            backend::Hunttype::MassiveMassOutbreak => {
+                Self::MassiveMassOutbreak(Research::default())
+            }
+            backend::Hunttype::Outbreak => Self::Outbreak(OutbreakBonus::default()),
+            backend::Hunttype::ChainFishing => Self::ChainFishing,
+            backend::Hunttype::Horde => Self::Horde,
+            backend::Hunttype::RadarGenIV => Self::Radar(Radar::GenIV),
+            backend::Hunttype::RadarGenVI => Self::Radar(Radar::GenVI),
+            backend::Hunttype::RadarBDSP => Self::Radar(Radar::BDSP),
+            backend::Hunttype::Custom => Self::Custom(uuid::Uuid::nil()),
         }
     }
 }
@@ -564,11 +964,59 @@ impl Into<backend::Hunttype> for Hunttype {
             Self::Masuda(Masuda::GenIV) => backend::Hunttype::MasudaGenIV,
             Self::Masuda(Masuda::GenV) => backend::Hunttype::MasudaGenV,
             Self::Masuda(Masuda::GenVI) => backend::Hunttype::MasudaGenVI,
+            Self::MassOutbreak(_) => backend::Hunttype::MassOutbreak,
+            Self::MassiveMassOutbreak(_) => backend::Hunttype::MassiveMassOutbreak,
+            Self::Outbreak(_) => backend::Hunttype::Outbreak,
+            Self::ChainFishing => backend::Hunttype::ChainFishing,
+            Self::Horde => backend::Hunttype::Horde,
+            Self::Radar(Radar::GenIV) => backend::Hunttype::RadarGenIV,
+            Self::Radar(Radar::GenVI) => backend::Hunttype::RadarGenVI,
+            Self::Radar(Radar::BDSP) => backend::Hunttype::RadarBDSP,
+            Self::Custom(_) => backend::Hunttype::Custom,
             Self::Mixed => unreachable!(),
         }
     }
 }
 
+#[cfg(feature = "ssr")]
+impl Hunttype {
+    /// Values of the method, they are stored next to the hunttype
+    pub fn params(&self) -> backend::MethodParams {
+        match *self {
+            Self::MassOutbreak(research) | Self::MassiveMassOutbreak(research) => {
+                backend::MethodParams {
+                    research_level: research.into(),
+                    ..Default::default()
+                }
+            }
+            Self::Outbreak(outbreak) => backend::MethodParams {
+                outbreak_cleared: outbreak.cleared,
+                sparkling_power: outbreak.sparkling_power.into(),
+                ..Default::default()
+            },
+            Self::Custom(id) => backend::MethodParams {
+                custom_method: Some(id),
+                ..Default::default()
+            },
+            _ => backend::MethodParams::default(),
+        }
+    }
+
+    pub fn with_params(self, params: backend::MethodParams) -> Self {
+        match self {
+            Self::MassOutbreak(_) => Self::MassOutbreak(params.research_level.into()),
+            Self::MassiveMassOutbreak(_) => Self::MassiveMassOutbreak(params.research_level.into()),
+            Self::Outbreak(_) => Self::Outbreak(OutbreakBonus {
+                cleared: params.outbreak_cleared,
+                sparkling_power: params.sparkling_power.clamp(0, 3) as u8,
+            }),
+            // the method was deleted, the phase counts like `NewOdds` from then on
+            Self::Custom(_) => Self::Custom(params.custom_method.unwrap_or_default()),
+            hunttype => hunttype,
+        }
+    }
+}
+
 impl std::ops::BitOr<Hunttype> for Hunttype {
     type Output = Hunttype;
 
@@ -580,3 +1028,196 @@ impl std::ops::BitOr<Hunttype> for Hunttype {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Chance of a single encounter as `1 / odds`, for methods with the same rolls every encounter
+    fn odds_per_encounter(hunttype: Hunttype, has_charm: bool) -> f64 {
+        hunttype.odds() / hunttype.rolls()(1, has_charm) as f64
+    }
+
+    fn hunt() -> (Countable, Countable) {
+        let owner = uuid::Uuid::new_v4();
+        let counter = Countable::new("Counter", CountableKind::Counter, owner, None);
+        let phase = Countable::new(
+            "Phase",
+            CountableKind::Phase,
+            owner,
+            Some(counter.uuid().into()),
+        );
+        counter.add_child_checked(phase.uuid().into()).unwrap();
+        (counter, phase)
+    }
+
+    /// Server version of `countable` with a newer revision
+    fn server_version(countable: &Countable, revision: i32) -> Countable {
+        let server = countable.detached_checked().unwrap();
+        server.set_revision_checked(revision).unwrap();
+        server
+    }
+
+    fn count(countable: &Countable) -> i32 {
+        match countable {
+            Countable::Phase(p) => p.lock().unwrap().count,
+            _ => panic!("not a phase"),
+        }
+    }
+
+    #[test]
+    fn rebase_keeps_pending_counts() {
+        let (counter, phase) = hunt();
+        let server = server_version(&phase, 3);
+        if let Countable::Phase(p) = &server {
+            p.lock().unwrap().count = 10;
+        }
+
+        operation::record(phase.uuid().into(), operation::OperationKind::AddCount(2));
+        operation::record(
+            counter.uuid().into(),
+            operation::OperationKind::SetCharm(true),
+        );
+        operation::record(phase.uuid().into(), operation::OperationKind::AddCount(1));
+        phase.rebase_checked(&server).unwrap();
+
+        assert_eq!(count(&phase), 13);
+        assert_eq!(phase.revision_checked().unwrap(), 3);
+        let Countable::Phase(p) = &phase else {
+            unreachable!()
+        };
+        assert!(p.lock().unwrap().has_charm);
+        // the operations are still sent by the outbox
+        assert_eq!(operation::pending().len(), 3);
+    }
+
+    #[test]
+    fn rebase_keeps_children_of_counters() {
+        let (counter, phase) = hunt();
+        let server = server_version(&counter, 2);
+        if let Countable::Counter(c) = &server {
+            let mut c = c.lock().unwrap();
+            c.children.clear();
+            c.name = String::from("Renamed");
+        }
+
+        counter.rebase_checked(&server).unwrap();
+
+        assert_eq!(counter.name_checked().unwrap(), "Renamed");
+        assert_eq!(counter.revision_checked().unwrap(), 2);
+        let Countable::Counter(c) = &counter else {
+            unreachable!()
+        };
+        assert_eq!(c.lock().unwrap().children, vec![phase.uuid().into()]);
+    }
+
+    #[test]
+    fn conflicts_do_not_drop_the_rest_of_the_batch() {
+        use std::cell::RefCell;
+
+        let (counter, phase) = hunt();
+        let (_, other) = hunt();
+        let conflict = server_version(&phase, 5);
+        operation::record(phase.uuid().into(), operation::OperationKind::AddCount(4));
+
+        let sent = RefCell::new(Vec::new());
+        let update = |list: Vec<Countable>| {
+            sent.borrow_mut()
+                .push(list.iter().map(Countable::uuid).collect::<Vec<_>>());
+            let res = if list.iter().any(|c| c.uuid() == phase.uuid()) {
+                let err = AppError::Conflict(Box::new(conflict.detached_checked().unwrap()));
+                Err(leptos::ServerFnError::ServerError(
+                    serde_json::to_string(&err).unwrap(),
+                ))
+            } else {
+                Ok(list.iter().map(|c| (c.uuid(), 7)).collect())
+            };
+            async move { res }
+        };
+
+        let list = vec![counter.clone(), phase.clone(), other.clone()];
+        futures::executor::block_on(save_with(list, update)).unwrap();
+
+        assert_eq!(
+            sent.into_inner(),
+            [
+                vec![counter.uuid(), phase.uuid(), other.uuid()],
+                vec![counter.uuid(), other.uuid()],
+            ]
+        );
+        assert_eq!(counter.revision_checked().unwrap(), 7);
+        assert_eq!(other.revision_checked().unwrap(), 7);
+        assert_eq!(phase.revision_checked().unwrap(), 5);
+        assert_eq!(count(&phase), 4);
+    }
+
+    #[test]
+    fn other_errors_are_returned() {
+        let (counter, _) = hunt();
+        let update = |_| async { Err(leptos::ServerFnError::ServerError(String::from("down"))) };
+
+        assert!(futures::executor::block_on(save_with(vec![counter.clone()], update)).is_err());
+        assert_eq!(counter.revision_checked().unwrap(), 0);
+    }
+
+    #[test]
+    fn legends_arceus_outbreaks() {
+        let mass = Hunttype::MassOutbreak(Research::Perfect);
+        assert_eq!(odds_per_encounter(mass, true).round(), 128.0);
+
+        let massive = Hunttype::MassiveMassOutbreak(Research::Perfect);
+        assert_eq!(odds_per_encounter(massive, true).round(), 216.0);
+
+        // without research or charm only the outbreak adds rolls
+        let mass = Hunttype::MassOutbreak(Research::Incomplete);
+        assert_eq!(mass.rolls()(10, false), 260);
+    }
+
+    #[test]
+    fn scarlet_violet_outbreak() {
+        let max = Hunttype::Outbreak(OutbreakBonus {
+            cleared: 60,
+            sparkling_power: OutbreakBonus::MAX_SPARKLING_POWER,
+        });
+        assert_eq!(odds_per_encounter(max, true), 512.0);
+
+        // sparkling power does not go past its highest level
+        let overpowered = Hunttype::Outbreak(OutbreakBonus {
+            cleared: 60,
+            sparkling_power: 10,
+        });
+        assert_eq!(overpowered.rolls()(100, true), max.rolls()(100, true));
+
+        let plain = Hunttype::Outbreak(OutbreakBonus::default());
+        assert_eq!(plain.rolls()(100, false), 100);
+    }
+
+    #[test]
+    fn chain_fishing_bonus_stops_at_a_chain_of_20() {
+        let rolls = Hunttype::ChainFishing.rolls();
+        assert_eq!(rolls(1, false), 1);
+        for count in 20..100 {
+            assert_eq!(rolls(count + 1, false) - rolls(count, false), 41, "{count}");
+        }
+    }
+
+    #[test]
+    fn horde_has_five_pokemon() {
+        let rolls = Hunttype::Horde.rolls();
+        for count in [1, 7, 100] {
+            assert_eq!(rolls(count, false), 5 * count);
+            assert_eq!(rolls(count, true), 15 * count);
+        }
+    }
+
+    #[test]
+    fn radar_chain_of_40() {
+        let rolls = Radar::GenIV.rolls_at(Radar::MAX_CHAIN, false);
+        assert_eq!((8192.0 / rolls).round(), 200.0);
+        // longer chains do not raise the odds any further
+        assert_eq!(Radar::GenIV.rolls_at(100, false), rolls);
+
+        let radar = Hunttype::Radar(Radar::GenIV).rolls();
+        assert_eq!(radar(60, false) - radar(50, false), 10 * rolls as i32);
+    }
+}
@@ -0,0 +1,626 @@
+//! Every change made through the store is recorded as an [`Operation`].
+//! Pending operations are sent to the server and kept in IndexedDB until the server has them,
+//! see `backend::operation` for how they are replayed.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use super::*;
+
+const DEVICE_KEY: &str = "operation_device";
+const CLOCK_KEY: &str = "operation_clock";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum OperationKind {
+    /// copy of the countable as it was created
+    Create(Countable),
+    AddCount(i32),
+    /// milliseconds
+    AddTime(i64),
+    Rename(String),
+    SetHunttype(Hunttype),
+    SetSuccess(bool),
+    SetStepSize(i32),
+    SetCharm(bool),
+    SetGame(Option<Game>),
+    SetTarget(Option<i32>),
+    Archive,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Operation {
+    pub uuid: uuid::Uuid,
+    pub countable: CountableId,
+    pub device: uuid::Uuid,
+    /// lamport clock, orders operations of the same kind from different devices
+    pub clock: i64,
+    pub kind: OperationKind,
+}
+
+/// Response of [`server::apply_operations`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Applied {
+    /// highest clock of all operations of the user
+    pub clock: i64,
+    /// operations the server will never apply and why, all other operations were applied
+    pub rejected: Vec<(uuid::Uuid, AppError)>,
+}
+
+impl Operation {
+    /**
+        `Apply Operation`
+
+        Replays the operation on `store` without recording it again.
+        Operations on a counter change its last phase.
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+    */
+    pub fn apply(&self, store: &mut CountableStore) -> Result<(), AppError> {
+        if let OperationKind::Create(countable) = &self.kind {
+            if !store.contains(&self.countable) {
+                let countable = countable.detached_checked()?;
+                if let Countable::Phase(p) = &countable {
+                    let parent = p.lock()?.parent;
+                    if let Some(parent) = store.get(&parent) {
+                        parent.add_child_checked(self.countable)?
+                    }
+                }
+                store.store.insert(self.countable, countable);
+            }
+            return Ok(());
+        }
+
+        let countable = store
+            .get(&self.countable)
+            .ok_or(AppError::CountableNotFound)?;
+        let phase = match &countable {
+            Countable::Counter(_) => store
+                .last_child(&self.countable)
+                .and_then(|child| store.get(&child)),
+            _ => Some(countable.clone()),
+        };
+
+        match (&self.kind, countable, phase) {
+            (OperationKind::AddCount(amount), _, Some(Countable::Phase(p))) => {
+                let mut phase = p.lock()?;
+                phase.count = (phase.count + amount).max(0)
+            }
+            (OperationKind::AddTime(amount), _, Some(Countable::Phase(p))) => {
+                let mut phase = p.lock()?;
+                phase.time = (phase.time + chrono::TimeDelta::milliseconds(*amount))
+                    .max(chrono::TimeDelta::zero())
+            }
+            (OperationKind::Rename(name), countable, _) => countable.set_name_checked(name)?,
+            (OperationKind::SetHunttype(hunttype), Countable::Counter(c), _) => {
+                for child in c.lock()?.children.iter() {
+                    if let Some(Countable::Phase(p)) = store.get(child) {
+                        p.lock()?.hunt_type = *hunttype
+                    }
+                }
+            }
+            (OperationKind::SetHunttype(hunttype), Countable::Phase(p), _) => {
+                p.lock()?.hunt_type = *hunttype
+            }
+            (OperationKind::SetSuccess(success), _, Some(Countable::Phase(p))) => {
+                p.lock()?.success = *success
+            }
+            (OperationKind::SetStepSize(step), _, Some(Countable::Phase(p))) => {
+                p.lock()?.step_size = *step
+            }
+            (OperationKind::SetCharm(has_charm), Countable::Counter(c), _) => {
+                for child in c.lock()?.children.iter() {
+                    if let Some(Countable::Phase(p)) = store.get(child) {
+                        p.lock()?.has_charm = *has_charm
+                    }
+                }
+            }
+            (OperationKind::SetCharm(has_charm), Countable::Phase(p), _) => {
+                p.lock()?.has_charm = *has_charm
+            }
+            (OperationKind::SetGame(game), Countable::Counter(c), _) => {
+                for child in c.lock()?.children.iter() {
+                    if let Some(Countable::Phase(p)) = store.get(child) {
+                        p.lock()?.game = *game
+                    }
+                }
+            }
+            (OperationKind::SetGame(game), Countable::Phase(p), _) => p.lock()?.game = *game,
+            (OperationKind::SetTarget(target), Countable::Counter(c), _) => {
+                c.lock()?.target = *target
+            }
+            (OperationKind::Archive, Countable::Counter(c), _) => c.lock()?.is_deleted = true,
+            (OperationKind::Archive, Countable::Phase(p), _) => p.lock()?.is_deleted = true,
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /**
+        `Validate Operation`
+
+        Checks the new values of the operation, the server rejects invalid operations
+        even when the device that recorded them did not check them.
+        `methods` holds the game and method of every phase the operation changes,
+        a new game is checked against their methods and a new method against their games.
+
+        # Errors
+          * [AppError::InvalidField]
+    */
+    pub fn validate(&self, methods: &[(Option<Game>, Hunttype)]) -> Result<(), AppError> {
+        match &self.kind {
+            OperationKind::Create(countable) => validate::countable(countable),
+            OperationKind::Rename(name) => validate::name(name),
+            OperationKind::SetStepSize(step) => validate::step_size(*step),
+            OperationKind::SetTarget(Some(target)) => validate::target(*target),
+            OperationKind::SetGame(game) => methods
+                .iter()
+                .try_for_each(|(_, hunttype)| validate::method(*game, *hunttype)),
+            OperationKind::SetHunttype(hunttype) => methods
+                .iter()
+                .try_for_each(|(game, _)| validate::method(*game, *hunttype)),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn into_backend(self) -> Result<backend::operation::Operation, AppError> {
+        use backend::operation::OperationKind as Kind;
+
+        let kind = match self.kind {
+            OperationKind::Create(Countable::Counter(c)) => {
+                Kind::CreateCounter(c.lock()?.clone().into())
+            }
+            OperationKind::Create(Countable::Phase(p)) => {
+                Kind::CreatePhase(p.lock()?.clone().into())
+            }
+            OperationKind::Create(Countable::Chain(_)) => todo!(),
+            OperationKind::AddCount(amount) => Kind::AddCount(amount),
+            OperationKind::AddTime(amount) => Kind::AddTime(amount),
+            OperationKind::Rename(name) => Kind::Rename(name),
+            OperationKind::SetHunttype(hunttype) => {
+                Kind::SetHunttype(hunttype.into(), hunttype.params())
+            }
+            OperationKind::SetSuccess(success) => Kind::SetSuccess(success),
+            OperationKind::SetStepSize(step) => Kind::SetStepSize(step),
+            OperationKind::SetCharm(has_charm) => Kind::SetCharm(has_charm),
+            OperationKind::SetGame(game) => Kind::SetGame(game.map(Game::into)),
+            OperationKind::SetTarget(target) => Kind::SetTarget(target),
+            OperationKind::Archive => Kind::Archive,
+        };
+
+        Ok(backend::operation::Operation {
+            uuid: self.uuid,
+            countable_uuid: self.countable.into(),
+            device_id: self.device,
+            clock: self.clock,
+            kind,
+        })
+    }
+}
+
+#[derive(Default)]
+struct OperationLog {
+    device: Option<uuid::Uuid>,
+    clock: i64,
+    pending: Vec<Operation>,
+    in_flight: HashSet<uuid::Uuid>,
+    /// guests only store their data locally so nothing is recorded for them
+    disabled: bool,
+}
+
+impl OperationLog {
+    fn device(&mut self) -> uuid::Uuid {
+        if let Some(device) = self.device {
+            return device;
+        }
+
+        let device = load(DEVICE_KEY).unwrap_or_else(|| {
+            let device = uuid::Uuid::new_v4();
+            persist(DEVICE_KEY, device);
+            device
+        });
+        self.clock = self.clock.max(load(CLOCK_KEY).unwrap_or_default());
+        self.device = Some(device);
+
+        device
+    }
+
+    fn observe(&mut self, clock: i64) {
+        if clock > self.clock {
+            self.clock = clock;
+            persist(CLOCK_KEY, clock);
+        }
+    }
+}
+
+/// Only the browser keeps the device and clock, elsewhere they live as long as the log
+fn load<T: serde::de::DeserializeOwned>(key: &str) -> Option<T> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use gloo_storage::{LocalStorage, Storage};
+        LocalStorage::get(key).ok()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let _ = key;
+        None
+    }
+}
+
+fn persist<T: Serialize>(key: &str, value: T) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        use gloo_storage::{LocalStorage, Storage};
+        let _ = LocalStorage::set(key, value);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = (key, value);
+}
+
+type PendingListener = Rc<dyn Fn(usize)>;
+
+thread_local! {
+    static LOG: RefCell<OperationLog> = RefCell::default();
+    static LISTENER: RefCell<Option<PendingListener>> = RefCell::default();
+}
+
+/// Adds an operation to the pending operations,
+/// counts and times are added to the last pending operation when possible
+pub fn record(countable: CountableId, kind: OperationKind) {
+    LOG.with_borrow_mut(|log| {
+        if log.disabled {
+            return;
+        }
+
+        if let Some(last) = log.pending.last_mut()
+            && last.countable == countable
+            && !log.in_flight.contains(&last.uuid)
+        {
+            match (&mut last.kind, &kind) {
+                (OperationKind::AddCount(a), OperationKind::AddCount(b)) => {
+                    *a += b;
+                    return;
+                }
+                (OperationKind::AddTime(a), OperationKind::AddTime(b)) => {
+                    *a += b;
+                    return;
+                }
+                _ => {}
+            }
+        }
+
+        let device = log.device();
+        let clock = log.clock + 1;
+        log.observe(clock);
+        log.pending.push(Operation {
+            uuid: uuid::Uuid::new_v4(),
+            countable,
+            device,
+            clock,
+            kind,
+        })
+    });
+    notify()
+}
+
+/// Turns recording on or off, see [`record`]
+pub fn set_recording(enabled: bool) {
+    LOG.with_borrow_mut(|log| log.disabled = !enabled)
+}
+
+pub fn has_pending() -> bool {
+    LOG.with_borrow(|log| !log.pending.is_empty())
+}
+
+pub fn pending() -> Vec<Operation> {
+    LOG.with_borrow(|log| log.pending.clone())
+}
+
+/// Pending operations that are not being sent yet, they are marked as sent until
+/// [`acknowledge`] or [`release`] is called.
+/// Nothing is returned while operations are being sent so the server receives them in order.
+pub fn take_unsent() -> Vec<Operation> {
+    LOG.with_borrow_mut(|log| {
+        if !log.in_flight.is_empty() {
+            return Vec::new();
+        }
+
+        let unsent: Vec<Operation> = log
+            .pending
+            .iter()
+            .filter(|op| !log.in_flight.contains(&op.uuid))
+            .cloned()
+            .collect();
+        log.in_flight.extend(unsent.iter().map(|op| op.uuid));
+        unsent
+    })
+}
+
+/// Removes operations the server has applied, `clock` is the highest clock known to the server
+pub fn acknowledge(sent: &[Operation], clock: i64) {
+    LOG.with_borrow_mut(|log| {
+        for op in sent {
+            log.in_flight.remove(&op.uuid);
+        }
+        log.pending
+            .retain(|op| !sent.iter().any(|s| s.uuid == op.uuid));
+        log.observe(clock);
+    });
+    notify()
+}
+
+/// Marks operations that could not be sent as pending again
+pub fn release(sent: &[Operation]) {
+    LOG.with_borrow_mut(|log| {
+        for op in sent {
+            log.in_flight.remove(&op.uuid);
+        }
+    })
+}
+
+/// Adds operations loaded from IndexedDB that are not pending yet
+pub fn restore(operations: Vec<Operation>) {
+    LOG.with_borrow_mut(|log| {
+        for op in operations {
+            if !log.pending.iter().any(|p| p.uuid == op.uuid) {
+                log.observe(op.clock);
+                log.pending.push(op);
+            }
+        }
+        log.pending.sort_by_key(|op| op.clock);
+    });
+    notify()
+}
+
+/// Drops all pending operations, used when another account is opened.
+/// They are still stored in the local database of their account.
+pub fn clear() {
+    LOG.with_borrow_mut(|log| {
+        log.pending.clear();
+        log.in_flight.clear();
+    });
+    notify()
+}
+
+/// Sends all unsent operations to the server,
+/// operations recorded while sending are sent right after
+///
+/// Operations stay pending when they can not be sent or the server fails to handle them.
+/// Operations the server rejects are dropped so they do not block the ones after them.
+///
+/// # Errors
+/// The error of the request, or the reason of the first rejected operation
+/// once everything else was sent
+pub async fn send() -> Result<(), leptos::ServerFnError> {
+    let mut rejected = None;
+
+    loop {
+        let sent = take_unsent();
+        if sent.is_empty() {
+            break;
+        }
+
+        match server::apply_operations(sent.clone()).await {
+            Ok(applied) => {
+                acknowledge(&sent, applied.clock);
+                let _ = indexed::IndexedSaveHandler::save_operations().await;
+                if rejected.is_none() {
+                    rejected = applied.rejected.into_iter().next();
+                }
+            }
+            Err(err) => {
+                release(&sent);
+                return Err(err);
+            }
+        }
+    }
+
+    match rejected {
+        // sent as json like the errors of server functions
+        Some((_, err)) => Err(leptos::ServerFnError::ServerError(
+            serde_json::to_string(&err).unwrap_or_else(|_| err.to_string()),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Calls `listener` with the number of pending operations whenever it changes
+pub fn on_pending_change(listener: impl Fn(usize) + 'static) {
+    LISTENER.set(Some(Rc::new(listener)))
+}
+
+fn notify() {
+    let pending = LOG.with_borrow(|log| log.pending.len());
+    if let Some(listener) = LISTENER.with_borrow(|listener| listener.clone()) {
+        listener(pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn hunt() -> (CountableStore, CountableId, CountableId) {
+        let owner = uuid::Uuid::new_v4();
+        let counter = Countable::new("Counter", CountableKind::Counter, owner, None);
+        let counter_id = counter.uuid().into();
+        let phase = Countable::new("Phase", CountableKind::Phase, owner, Some(counter_id));
+        let phase_id = phase.uuid().into();
+        counter.add_child_checked(phase_id).unwrap();
+
+        let store = CountableStore::new(
+            owner,
+            HashMap::from([(counter_id, counter), (phase_id, phase)]),
+        );
+        (store, counter_id, phase_id)
+    }
+
+    fn operation(
+        countable: CountableId,
+        device: uuid::Uuid,
+        clock: i64,
+        kind: OperationKind,
+    ) -> Operation {
+        Operation {
+            uuid: uuid::Uuid::new_v4(),
+            countable,
+            device,
+            clock,
+            kind,
+        }
+    }
+
+    fn kinds() -> Vec<String> {
+        pending()
+            .iter()
+            .map(|op| format!("{:?}", op.kind))
+            .collect()
+    }
+
+    #[test]
+    fn methods_are_checked_against_every_phase() {
+        let key = CountableId::from(uuid::Uuid::new_v4());
+        let device = uuid::Uuid::new_v4();
+        let methods = [
+            (None, Hunttype::OldOdds),
+            (Some(Game::SunMoon), Hunttype::NewOdds),
+        ];
+
+        let sos = operation(key, device, 1, OperationKind::SetHunttype(Hunttype::SOS));
+        assert!(sos.validate(&methods[..1]).is_ok());
+        assert!(sos.validate(&methods[1..]).is_ok());
+        let old_odds = operation(
+            key,
+            device,
+            2,
+            OperationKind::SetHunttype(Hunttype::OldOdds),
+        );
+        assert!(old_odds.validate(&methods).is_err());
+
+        let game = OperationKind::SetGame(Some(Game::GoldSilverCrystal));
+        assert!(operation(key, device, 3, game.clone())
+            .validate(&methods[..1])
+            .is_ok());
+        assert!(operation(key, device, 4, game).validate(&methods).is_err());
+        assert!(operation(key, device, 5, OperationKind::SetGame(None))
+            .validate(&methods)
+            .is_ok());
+    }
+
+    #[test]
+    fn concurrent_counts_add_up() {
+        let (mut store, counter, phase) = hunt();
+        let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+
+        operation(phase, a, 1, OperationKind::AddCount(3))
+            .apply(&mut store)
+            .unwrap();
+        operation(counter, b, 1, OperationKind::AddCount(4))
+            .apply(&mut store)
+            .unwrap();
+        assert_eq!(store.count(&phase), 7);
+
+        // counts never go below zero
+        operation(phase, a, 2, OperationKind::AddCount(-10))
+            .apply(&mut store)
+            .unwrap();
+        assert_eq!(store.count(&phase), 0);
+    }
+
+    #[test]
+    fn counters_change_their_last_phase() {
+        let (mut store, counter, first) = hunt();
+        let newer = Countable::new("Newer", CountableKind::Phase, store.owner(), Some(counter));
+        let create = operation(
+            newer.uuid().into(),
+            uuid::Uuid::new_v4(),
+            1,
+            OperationKind::Create(newer.clone()),
+        );
+        create.apply(&mut store).unwrap();
+        // creating twice does not add the phase again
+        create.apply(&mut store).unwrap();
+        assert_eq!(store.children(&counter), vec![first, newer.uuid().into()]);
+
+        operation(counter, uuid::Uuid::new_v4(), 2, OperationKind::AddCount(2))
+            .apply(&mut store)
+            .unwrap();
+        assert_eq!(store.count(&newer.uuid().into()), 2);
+        assert_eq!(store.count(&first), 0);
+    }
+
+    #[test]
+    fn record_merges_consecutive_counts() {
+        let (_, counter, phase) = hunt();
+
+        record(phase, OperationKind::AddCount(1));
+        record(phase, OperationKind::AddCount(2));
+        record(phase, OperationKind::AddTime(100));
+        record(phase, OperationKind::AddTime(50));
+        record(counter, OperationKind::AddCount(1));
+        record(phase, OperationKind::AddCount(1));
+        record(phase, OperationKind::SetSuccess(true));
+        record(phase, OperationKind::AddCount(1));
+        assert_eq!(
+            kinds(),
+            [
+                "AddCount(3)",
+                "AddTime(150)",
+                "AddCount(1)",
+                "AddCount(1)",
+                "SetSuccess(true)",
+                "AddCount(1)",
+            ]
+        );
+
+        // operations that are being sent do not change anymore
+        let sent = take_unsent();
+        record(phase, OperationKind::AddCount(1));
+        assert_eq!(pending().len(), sent.len() + 1);
+        assert!(take_unsent().is_empty());
+    }
+
+    #[test]
+    fn acknowledge_keeps_what_was_not_sent() {
+        let (_, _, phase) = hunt();
+
+        record(phase, OperationKind::AddCount(1));
+        let sent = take_unsent();
+        record(phase, OperationKind::AddCount(2));
+        acknowledge(&sent, 41);
+        assert_eq!(kinds(), ["AddCount(2)"]);
+
+        // new operations order after everything the server knows
+        record(phase, OperationKind::SetSuccess(true));
+        assert_eq!(pending().last().unwrap().clock, 42);
+
+        let unsent = take_unsent();
+        release(&unsent);
+        assert_eq!(take_unsent().len(), unsent.len());
+    }
+
+    #[test]
+    fn restore_skips_known_operations() {
+        let (_, _, phase) = hunt();
+        let device = uuid::Uuid::new_v4();
+        let later = operation(phase, device, 7, OperationKind::AddCount(2));
+        let earlier = operation(phase, device, 3, OperationKind::AddCount(1));
+
+        restore(vec![later.clone()]);
+        restore(vec![later, earlier]);
+        assert_eq!(
+            pending().iter().map(|op| op.clock).collect::<Vec<_>>(),
+            [3, 7]
+        );
+
+        record(phase, OperationKind::SetSuccess(true));
+        assert_eq!(pending().last().unwrap().clock, 8);
+    }
+}
@@ -0,0 +1,307 @@
+//! Keeps sending the pending operations until the server has all of them.
+//!
+//! The operations themselves are stored in IndexedDB, see [`indexed::IndexedSaveHandler`].
+//! Failed attempts are retried with an increasing delay, these retries double as heartbeat
+//! that notices when the server can be reached again.
+
+use leptos::leptos_dom::helpers::TimeoutHandle;
+use leptos::*;
+use std::cell::RefCell;
+use std::time::Duration;
+
+use super::*;
+
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(60);
+
+/// Sync state of the pending operations
+#[derive(Debug, Clone, Copy)]
+pub struct Outbox {
+    /// number of operations the server does not have yet
+    pub pending: RwSignal<usize>,
+    /// whether the server could be reached the last time
+    pub online: RwSignal<bool>,
+    /// time of the last flush that reached the server
+    pub last_saved: RwSignal<Option<chrono::NaiveDateTime>>,
+    /// whether the local database could be opened, unknown until it was tried
+    pub indexed: RwSignal<Option<bool>>,
+}
+
+type FlushResult = Result<(), ServerFnError>;
+
+#[derive(Default)]
+struct State {
+    outbox: Option<Outbox>,
+    delay: Option<Duration>,
+    retry: Option<TimeoutHandle>,
+    /// whether operations are being sent right now
+    flushing: bool,
+    /// flushes waiting for the result of the running one
+    waiting: Vec<futures::channel::oneshot::Sender<FlushResult>>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::default();
+}
+
+/// Provides [`Outbox`] and retries pending operations
+/// once the browser reports it is back online
+pub fn provide_outbox() {
+    let outbox = Outbox {
+        pending: create_rw_signal(0),
+        online: create_rw_signal(true),
+        last_saved: create_rw_signal(None),
+        indexed: create_rw_signal(None),
+    };
+    provide_context(outbox);
+
+    // effects only run in the browser
+    create_effect(move |_| {
+        STATE.with_borrow_mut(|state| state.outbox = Some(outbox));
+        outbox.pending.set(operation::pending().len());
+        outbox.online.set(window().navigator().on_line());
+
+        // operations are recorded while the store is updated,
+        // the count is updated once that is done
+        operation::on_pending_change(move |pending| {
+            queue_microtask(move || outbox.pending.set(pending))
+        });
+
+        window_event_listener(ev::online, |_| retry_now());
+        window_event_listener(ev::offline, move |_| outbox.online.set(false));
+    });
+}
+
+/**
+    `Flush Outbox`
+
+    Sends the pending operations to the server.
+    When the server can not be reached or fails to handle them another attempt is scheduled,
+    the delay doubles with every failed attempt up to a minute.
+    While another flush is running this waits for its result,
+    it also sends the operations recorded before it is done.
+
+    # Errors
+      * [ServerFnError] returned by [`operation::send`]
+*/
+pub async fn flush() -> FlushResult {
+    let (res, sent) = exclusive(operation::send).await;
+    if !sent {
+        return res;
+    }
+
+    let outcome = Outcome::of(&res, operation::has_pending());
+    if let Some(outbox) = STATE.with_borrow(|state| state.outbox) {
+        outbox.online.set(outcome.online);
+        if outcome.saved {
+            outbox.last_saved.set(Some(chrono::Utc::now().naive_utc()))
+        }
+    }
+    if outcome.retry {
+        schedule_retry()
+    } else {
+        STATE.with_borrow_mut(|state| state.delay = None)
+    }
+
+    res
+}
+
+/// Runs `send` unless a flush is running already, its result is shared instead
+///
+/// # Returns
+/// The result and whether `send` was run
+async fn exclusive<F, Fut>(send: F) -> (FlushResult, bool)
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = FlushResult>,
+{
+    let running = STATE.with_borrow_mut(|state| {
+        if !state.flushing {
+            state.flushing = true;
+            return None;
+        }
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        state.waiting.push(sender);
+        Some(receiver)
+    });
+    if let Some(receiver) = running {
+        return (receiver.await.unwrap_or(Ok(())), false);
+    }
+
+    let res = send().await;
+
+    let waiting = STATE.with_borrow_mut(|state| {
+        state.flushing = false;
+        std::mem::take(&mut state.waiting)
+    });
+    for sender in waiting {
+        let _ = sender.send(res.clone());
+    }
+
+    (res, true)
+}
+
+/// How the state of the outbox changes after a flush
+#[derive(Debug, PartialEq, Eq)]
+struct Outcome {
+    online: bool,
+    saved: bool,
+    retry: bool,
+}
+
+impl Outcome {
+    fn of(res: &FlushResult, has_pending: bool) -> Self {
+        match res {
+            Err(ServerFnError::Request(_)) => Self {
+                online: false,
+                saved: false,
+                retry: true,
+            },
+            // the server failed to handle the operations, they are still pending
+            Err(_) => Self {
+                online: true,
+                saved: false,
+                retry: has_pending,
+            },
+            Ok(()) => Self {
+                online: true,
+                saved: true,
+                retry: false,
+            },
+        }
+    }
+}
+
+/// Sends the pending operations right away instead of waiting for the next retry
+pub fn retry_now() {
+    STATE.with_borrow_mut(|state| {
+        if let Some(handle) = state.retry.take() {
+            handle.clear()
+        }
+        state.delay = None;
+    });
+
+    spawn_local(async {
+        let _ = flush().await;
+    })
+}
+
+fn schedule_retry() {
+    STATE.with_borrow_mut(|state| {
+        if state.retry.is_some() {
+            return;
+        }
+
+        let delay = next_delay(state.delay);
+        state.delay = Some(delay);
+
+        state.retry = set_timeout_with_handle(
+            move || {
+                STATE.with_borrow_mut(|state| state.retry = None);
+                spawn_local(async {
+                    let _ = flush().await;
+                })
+            },
+            delay,
+        )
+        .ok();
+    })
+}
+
+/// Delay after `delay`, doubles up to [`MAX_RETRY`]
+fn next_delay(delay: Option<Duration>) -> Duration {
+    delay
+        .map(|delay| (delay * 2).min(MAX_RETRY))
+        .unwrap_or(FIRST_RETRY)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use futures::{executor::LocalPool, task::LocalSpawnExt};
+
+    use super::*;
+
+    #[test]
+    fn delays_double_up_to_a_minute() {
+        let delays = std::iter::successors(Some(next_delay(None)), |d| Some(next_delay(Some(*d))))
+            .take(8)
+            .map(|d| d.as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+
+    #[test]
+    fn outcomes() {
+        let offline = Err(ServerFnError::Request(String::from("offline")));
+        let failed = Err(ServerFnError::ServerError(String::from("failed")));
+
+        assert_eq!(
+            Outcome::of(&offline, true),
+            Outcome {
+                online: false,
+                saved: false,
+                retry: true
+            }
+        );
+        assert!(Outcome::of(&failed, true).retry);
+        assert!(Outcome::of(&failed, true).online);
+        // nothing left to retry
+        assert!(!Outcome::of(&failed, false).retry);
+        assert_eq!(
+            Outcome::of(&Ok(()), false),
+            Outcome {
+                online: true,
+                saved: true,
+                retry: false
+            }
+        );
+    }
+
+    #[test]
+    fn running_flushes_are_joined() {
+        let mut pool = LocalPool::new();
+        let spawner = pool.spawner();
+        let sends = Rc::new(Cell::new(0));
+        let (respond, response) = futures::channel::oneshot::channel::<FlushResult>();
+
+        let first = {
+            let sends = sends.clone();
+            spawner
+                .spawn_local_with_handle(exclusive(move || {
+                    sends.set(sends.get() + 1);
+                    async move { response.await.unwrap() }
+                }))
+                .unwrap()
+        };
+        pool.run_until_stalled();
+
+        let second = {
+            let sends = sends.clone();
+            spawner
+                .spawn_local_with_handle(exclusive(move || {
+                    sends.set(sends.get() + 1);
+                    async { Ok(()) }
+                }))
+                .unwrap()
+        };
+        pool.run_until_stalled();
+        assert_eq!(sends.get(), 1);
+
+        let failed = ServerFnError::ServerError(String::from("failed"));
+        respond.send(Err(failed.clone())).unwrap();
+        assert_eq!(pool.run_until(first), (Err(failed.clone()), true));
+        assert_eq!(pool.run_until(second), (Err(failed), false));
+        assert_eq!(sends.get(), 1);
+
+        // the next flush sends again
+        let sends_after = sends.clone();
+        let third = pool.run_until(exclusive(move || {
+            sends_after.set(sends_after.get() + 1);
+            async { Ok(()) }
+        }));
+        assert_eq!(third, (Ok(()), true));
+        assert_eq!(sends.get(), 2);
+    }
+}
@@ -4,17 +4,67 @@ use super::*;
 
 #[server(GetCountableStore, "/api/session")]
 pub async fn get_countable_store(user: uuid::Uuid) -> Result<CountableStore, ServerFnError> {
-    use super::{super::api, Countable, CountableId};
-    use std::collections::{HashMap, VecDeque};
+    let session = crate::session::require_scope(backend::access_token::TokenScope::Read).await?;
+    if session.user_uuid != user {
+        Err(AppError::Unauthorized)?
+    }
+
+    let pool = api::extract_pool().await?;
+    let mut conn = pool.begin().await?;
+
+    let cursor = backend::changes::cursor(&mut conn).await?;
+    let counters = backend::counter::all_by_user(&mut conn, user).await?;
+    let phases = backend::phase::all_by_user(&mut conn, user).await?;
+
+    conn.commit().await?;
+
+    let mut store = build_store(user, counters, phases, cursor)?;
+    store.custom_methods = backend::custom_method::all_by_user(&pool, user)
+        .await?
+        .into_iter()
+        .map(crate::custom_method::CustomMethod::from)
+        .collect();
+
+    Ok(store)
+}
+
+/// Counters and phases changed at or after `cursor`, see `backend::changes::cursor`,
+/// the returned store only holds the changed phases as children of its counters
+/// and lists the countables that were removed
+#[server(ChangesSince, "/api/session")]
+pub async fn changes_since(user: uuid::Uuid, cursor: i64) -> Result<CountableStore, ServerFnError> {
+    let session = crate::session::require_scope(backend::access_token::TokenScope::Read).await?;
+    if session.user_uuid != user {
+        Err(AppError::Unauthorized)?
+    }
 
     let mut conn = api::extract_pool().await?.begin().await?;
 
+    let next_cursor = backend::changes::cursor(&mut conn).await?;
+    let counters = backend::counter::changed_since(&mut conn, user, cursor).await?;
+    let phases = backend::phase::changed_since(&mut conn, user, cursor).await?;
+    let removed = backend::changes::removed_since(&mut conn, user, cursor).await?;
+
+    conn.commit().await?;
+
+    let mut store = build_store(user, counters, phases, next_cursor)?;
+    store.removed = removed.into_iter().map(CountableId::from).collect();
+
+    Ok(store)
+}
+
+#[cfg(feature = "ssr")]
+fn build_store(
+    user: uuid::Uuid,
+    counters: Vec<backend::DbCounter>,
+    phases: Vec<backend::DbPhase>,
+    sync_cursor: i64,
+) -> Result<CountableStore, AppError> {
+    use std::collections::HashMap;
+
     let mut store: HashMap<CountableId, Countable> = HashMap::new();
-    let mut counters: VecDeque<backend::DbCounter> =
-        backend::counter::all_by_user(&mut conn, user).await?.into();
-    let phases = backend::phase::all_by_user(&mut conn, user).await?;
 
-    while let Some(c) = counters.pop_front() {
+    for c in counters {
         // TODO: allow parent field on counters
         store.insert(c.uuid.into(), c.into());
     }
@@ -27,7 +77,146 @@ pub async fn get_countable_store(user: uuid::Uuid) -> Result<CountableStore, Ser
         }
     }
 
-    conn.commit().await?;
+    let mut store = CountableStore::new(user, store);
+    store.sync_cursor = sync_cursor;
+
+    Ok(store)
+}
+
+/// Applies operations recorded while counting, operations the server already has are skipped.
+/// Operations that are invalid or can not be applied are rejected one by one,
+/// the others are still applied.
+///
+/// # Returns
+/// The highest clock of all operations of the user and the rejected operations
+#[server(ApplyOperations, "/api/session")]
+pub async fn apply_operations(
+    operations: Vec<super::operation::Operation>,
+) -> Result<super::operation::Applied, ServerFnError> {
+    use super::operation::OperationKind;
+    use backend::access_token::TokenScope;
+
+    let edits = operations.iter().any(|op| {
+        !matches!(
+            op.kind,
+            OperationKind::AddCount(_) | OperationKind::AddTime(_) | OperationKind::SetSuccess(_)
+        )
+    });
+    let scope = if edits {
+        TokenScope::Edit
+    } else {
+        TokenScope::Count
+    };
+    let session = crate::session::require_scope(scope).await?;
+
+    let mut tx = api::extract_pool().await?.begin().await?;
+    let mut rejected = Vec::new();
+    let planned = planned_methods(&operations);
+
+    for operation in operations {
+        let uuid = operation.uuid;
+        let methods = planned_phases(&mut tx, &operation, &planned).await?;
+        let backend_op = match operation
+            .validate(&methods)
+            .and_then(|_| operation.into_backend())
+        {
+            Ok(op) => op,
+            Err(err) => {
+                rejected.push((uuid, err));
+                continue;
+            }
+        };
 
-    Ok(CountableStore::new(user, store))
+        let applied =
+            backend::operation::apply_isolated(&mut tx, session.user_uuid, backend_op).await?;
+        if let Err(err) = applied {
+            rejected.push((uuid, rejection(err)));
+        }
+    }
+    let clock = backend::operation::max_clock(&mut tx, session.user_uuid).await?;
+
+    tx.commit().await?;
+
+    Ok(super::operation::Applied { clock, rejected })
+}
+
+/// Game and method of a countable after all operations of a batch,
+/// `None` when the batch does not change them
+#[cfg(feature = "ssr")]
+type PlannedMethods =
+    std::collections::HashMap<CountableId, (Option<Option<Game>>, Option<Hunttype>)>;
+
+#[cfg(feature = "ssr")]
+fn planned_methods(operations: &[super::operation::Operation]) -> PlannedMethods {
+    use super::operation::OperationKind;
+
+    let mut planned = PlannedMethods::new();
+    for op in operations {
+        match op.kind {
+            OperationKind::SetGame(game) => planned.entry(op.countable).or_default().0 = Some(game),
+            OperationKind::SetHunttype(hunttype) => {
+                planned.entry(op.countable).or_default().1 = Some(hunttype)
+            }
+            _ => {}
+        }
+    }
+
+    planned
+}
+
+/// Game and method of every phase affected by `operation` once the batch is applied,
+/// so a game and method changed together are not rejected halfway
+#[cfg(feature = "ssr")]
+async fn planned_phases(
+    tx: &mut backend::PgTx,
+    operation: &super::operation::Operation,
+    planned: &PlannedMethods,
+) -> Result<Vec<(Option<Game>, Hunttype)>, ServerFnError> {
+    use super::operation::OperationKind;
+
+    if !matches!(
+        operation.kind,
+        OperationKind::SetGame(_) | OperationKind::SetHunttype(_)
+    ) {
+        return Ok(Vec::new());
+    }
+
+    let key = uuid::Uuid::from(operation.countable);
+    let mut phases = backend::counter::get_children(tx, key).await?;
+    if phases.is_empty() {
+        match backend::phase::get(tx, key).await {
+            Ok(phase) => phases.push(phase),
+            // unknown countables are rejected when they are applied
+            Err(_) => return Ok(Vec::new()),
+        }
+    }
+
+    let (game, hunttype) = planned
+        .get(&operation.countable)
+        .copied()
+        .unwrap_or_default();
+    let mut methods = Vec::new();
+    for phase in phases {
+        let Countable::Phase(phase) = Countable::from(phase) else {
+            continue;
+        };
+        let phase = phase.lock()?;
+        methods.push((
+            game.unwrap_or(phase.game),
+            hunttype.unwrap_or(phase.hunt_type),
+        ));
+    }
+
+    Ok(methods)
+}
+
+#[cfg(feature = "ssr")]
+fn rejection(err: backend::BackendError) -> AppError {
+    match err {
+        backend::BackendError::Unauthorized => AppError::Unauthorized,
+        backend::BackendError::CounterNotFound | backend::BackendError::DataNotFound(_) => {
+            AppError::CountableNotFound
+        }
+        err => AppError::ServerError(err.to_string()),
+    }
 }
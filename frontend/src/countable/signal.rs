@@ -8,14 +8,19 @@ pub fn ProvideStore(children: ChildrenFn) -> impl IntoView {
     let user = expect_context::<RwSignal<UserSession>>();
     let msg = expect_context::<MessageJar>();
 
-    let store_resource = create_blocking_resource(user, move |user| async move {
-        server::get_countable_store(user.user_uuid).await
-    });
+    let store_resource = create_blocking_resource(user, load_store);
     provide_context(store_resource);
 
     let store = create_rw_signal(CountableStore::default());
     provide_context(store);
 
+    // effects only run in the browser
+    create_effect(move |_| {
+        if !user.get_untracked().is_guest() {
+            listen_for_changes(store)
+        }
+    });
+
     create_isomorphic_effect(move |_| match store_resource.get() {
         Some(Ok(s)) => {
             store.set(s);
@@ -27,7 +32,7 @@ pub fn ProvideStore(children: ChildrenFn) -> impl IntoView {
     });
 
     view! {
-        <Await future=move || server::get_countable_store(user.get_untracked().user_uuid) let:res>
+        <Await future=move || load_store(user.get_untracked()) let:res>
 
             {
                 if let Ok(data) = res {
@@ -39,3 +44,61 @@ pub fn ProvideStore(children: ChildrenFn) -> impl IntoView {
         </Await>
     }
 }
+
+/// Guests start with an empty store, their countables are loaded from IndexedDB
+async fn load_store(user: UserSession) -> Result<CountableStore, ServerFnError> {
+    if user.is_guest() {
+        return Ok(CountableStore::new(user.user_uuid, Default::default()));
+    }
+
+    server::get_countable_store(user.user_uuid).await
+}
+
+/// Merges counters changed by other sessions into `store` as they are pushed by the server,
+/// see `events::stream`
+fn listen_for_changes(store: RwSignal<CountableStore>) {
+    use std::cell::Cell;
+    use wasm_bindgen::{prelude::Closure, JsCast};
+    use web_sys::{EventSource, MessageEvent};
+
+    let Ok(source) = EventSource::new("/api/session/events") else {
+        return;
+    };
+
+    let on_store = Closure::<dyn Fn(MessageEvent)>::new(move |ev: MessageEvent| {
+        if let Some(data) = ev.data().as_string()
+            && let Ok(changed) = serde_json::from_str::<CountableStore>(&data)
+        {
+            store.update(|s| s.merge_remote(changed));
+        }
+    });
+
+    let resync = move || {
+        spawn_local(async move {
+            let (user, cursor) = store.with_untracked(|s| (s.owner(), s.sync_cursor()));
+            if let Ok(remote) = server::changes_since(user, cursor).await {
+                store.update(|s| s.merge_remote(remote));
+            }
+        })
+    };
+
+    // the browser reconnects on its own,
+    // changes made while disconnected are fetched once it is back
+    let connected_before = Cell::new(false);
+    let on_open = Closure::<dyn Fn()>::new(move || {
+        if connected_before.replace(true) {
+            resync()
+        }
+    });
+    let on_resync = Closure::<dyn Fn()>::new(resync);
+
+    let _ = source.add_event_listener_with_callback("store", on_store.as_ref().unchecked_ref());
+    let _ = source.add_event_listener_with_callback("resync", on_resync.as_ref().unchecked_ref());
+    source.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+
+    on_store.forget();
+    on_resync.forget();
+    on_open.forget();
+
+    on_cleanup(move || source.close());
+}
@@ -0,0 +1,222 @@
+//! Monte Carlo simulation of whole hunts.
+//!
+//! Every simulated hunt draws the chance at which it ends and looks up the encounters
+//! that chance takes with [`Statistics`], so the hunts follow the same rolls and odds as the progress bar.
+//! This gives the same distribution as rolling every single encounter
+//! while the time it takes does not grow with the odds.
+
+use leptos::{server, ServerFnError};
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+pub const DEFAULT_RUNS: u32 = 5000;
+pub const MAX_RUNS: u32 = 20000;
+
+/// The hunt that is simulated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationParams {
+    pub hunttype: Hunttype,
+    pub has_charm: bool,
+    /// base odds of a single roll
+    pub odds: f64,
+    /// shinies every hunt needs
+    pub successes: i32,
+    /// encounters of the current hunt, compared against the simulated ones
+    pub count: i32,
+    pub runs: u32,
+}
+
+impl SimulationParams {
+    /**
+        `Validate Simulation Params`
+
+        # Errors
+          * [AppError::InvalidField]
+    */
+    pub fn validate(&self) -> Result<(), AppError> {
+        fn invalid(field: &str, reason: String) -> AppError {
+            AppError::InvalidField(field.into(), reason)
+        }
+
+        let max_odds = crate::custom_method::MAX_ODDS as f64;
+        if !(1.0..=max_odds).contains(&self.odds) {
+            Err(invalid(
+                "odds",
+                format!("have to be between 1 and {max_odds}"),
+            ))?
+        }
+        if !(1..=validate::MAX_TARGET).contains(&self.successes) {
+            Err(invalid(
+                "target",
+                format!("has to be between 1 and {}", validate::MAX_TARGET),
+            ))?
+        }
+        validate::count(self.count)?;
+        if !(1..=MAX_RUNS).contains(&self.runs) {
+            Err(invalid(
+                "runs",
+                format!("have to be between 1 and {MAX_RUNS}"),
+            ))?
+        }
+
+        Ok(())
+    }
+}
+
+/// Encounters the simulated hunts took
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Simulation {
+    pub runs: u32,
+    /// encounters covered by every bar of `buckets`
+    pub width: i32,
+    /// hunts that were done within every range of `width` encounters, starting at 0
+    pub buckets: Vec<u32>,
+    /// hunts that would have taken more than `MAX_ENCOUNTERS`
+    pub unfinished: u32,
+    pub median: Option<i32>,
+    pub mean: Option<f64>,
+    /// hunts that were done within the encounters of the current hunt
+    pub done_by_count: u32,
+}
+
+impl Simulation {
+    /// Share of the simulated hunts that were done within the encounters of the current hunt
+    pub fn share_done(&self) -> f64 {
+        self.done_by_count as f64 / self.runs.max(1) as f64
+    }
+}
+
+#[cfg(any(feature = "ssr", test))]
+impl Simulation {
+    /// Bars of the histogram, the last one also holds every longer hunt
+    const BUCKETS: usize = 40;
+    /// Share of the hunts the histogram covers before its last bar
+    const COVERED: f64 = 0.99;
+
+    /**
+        `Run Simulation`
+
+        # Arguments
+          * `statistics`: describes a hunt from its first encounter on
+          * `runs`: amount of hunts
+          * `count`: encounters of the current hunt
+    */
+    pub fn run(statistics: &Statistics, runs: u32, count: i32, rng: &mut impl rand::Rng) -> Self {
+        let mut encounters = Vec::with_capacity(runs as usize);
+        let mut unfinished = 0;
+        for _ in 0..runs {
+            // a hunt can not end before its first encounter
+            let chance = rng.gen_range(f64::MIN_POSITIVE..1.0);
+            match statistics.encounters_for(chance) {
+                Some(e) => encounters.push(e),
+                None => unfinished += 1,
+            }
+        }
+        encounters.sort_unstable();
+
+        let covered = encounters
+            .get((encounters.len() as f64 * Self::COVERED) as usize)
+            .or(encounters.last())
+            .copied()
+            .unwrap_or_default();
+        let width = ((covered + Self::BUCKETS as i32 - 1) / Self::BUCKETS as i32).max(1);
+
+        let mut buckets = vec![0; Self::BUCKETS];
+        for e in encounters.iter() {
+            buckets[(e / width).min(Self::BUCKETS as i32 - 1) as usize] += 1
+        }
+
+        let mean = (!encounters.is_empty())
+            .then(|| encounters.iter().map(|e| *e as f64).sum::<f64>() / encounters.len() as f64);
+
+        Self {
+            runs,
+            width,
+            buckets,
+            unfinished,
+            median: encounters.get(encounters.len() / 2).copied(),
+            mean,
+            done_by_count: encounters.partition_point(|e| *e <= count) as u32,
+        }
+    }
+}
+
+#[server(SimulateHunt, "/api/session")]
+pub async fn simulate_hunt(params: SimulationParams) -> Result<Simulation, ServerFnError> {
+    let session = crate::session::require_scope(backend::access_token::TokenScope::Read).await?;
+
+    if let Err(err) = params.validate() {
+        return Err(ServerFnError::ServerError(serde_json::to_string(&err)?));
+    }
+
+    let custom = match params.hunttype {
+        Hunttype::Custom(id) => {
+            let pool = api::extract_pool().await?;
+            backend::custom_method::all_by_user(&pool, session.user_uuid)
+                .await?
+                .into_iter()
+                .find(|m| m.uuid == id)
+                .map(CustomMethod::from)
+        }
+        _ => None,
+    };
+
+    let statistics = Statistics::new(
+        params.odds,
+        0,
+        statistics::rolls_fn(params.hunttype, params.has_charm, custom),
+    )
+    .with_successes(params.successes);
+
+    Ok(Simulation::run(
+        &statistics,
+        params.runs,
+        params.count,
+        &mut rand::thread_rng(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn simulate(odds: f64, runs: u32, count: i32) -> Simulation {
+        let statistics = Statistics::new(
+            odds,
+            0,
+            statistics::rolls_fn(Hunttype::NewOdds, false, None),
+        );
+        Simulation::run(&statistics, runs, count, &mut StdRng::seed_from_u64(42))
+    }
+
+    #[test]
+    fn every_finished_hunt_is_in_the_histogram() {
+        for odds in [1.0, 4096.0, 1e12] {
+            let simulation = simulate(odds, 1000, 0);
+            assert_eq!(
+                simulation.buckets.iter().sum::<u32>(),
+                simulation.runs - simulation.unfinished,
+                "{odds}"
+            );
+        }
+        // a billion encounters are not enough for these odds
+        assert!(simulate(1e12, 1000, 0).unfinished > 0);
+    }
+
+    #[test]
+    fn done_by_count_grows_with_the_count() {
+        let done = [0, 100, 1000, 4096, 10_000, 100_000]
+            .map(|count| simulate(4096.0, 2000, count).done_by_count);
+        assert!(done.windows(2).all(|w| w[0] <= w[1]), "{done:?}");
+        assert_eq!(done[0], 0);
+        assert_eq!(done[done.len() - 1], 2000);
+
+        // roughly the half of the hunts is done at the median
+        let simulation = simulate(4096.0, 2000, 2839);
+        assert!((simulation.share_done() - 0.5).abs() < 0.05);
+        assert!((simulation.median.unwrap() - 2839).abs() < 300);
+    }
+}
@@ -0,0 +1,489 @@
+//! Estimates of how much longer a hunt will take and how lucky finished hunts were.
+//!
+//! All values follow from the chance of a single roll and the rolls a phase has after a
+//! number of encounters, so methods with a bonus that grows with the count are covered as well.
+
+use chrono::TimeDelta;
+
+use super::*;
+
+/// Encounters after which the chance of a method no longer changes are not simulated one by one,
+/// every method reaches its highest bonus well before this
+const EXACT_ENCOUNTERS: i32 = 1000;
+/// Searches give up past this count, it only happens with extremely low odds
+const MAX_ENCOUNTERS: i32 = 1 << 30;
+
+/// Rolls of a method as a function of the count, custom methods that are not known count like `hunttype`
+pub(crate) fn rolls_fn(
+    hunttype: Hunttype,
+    has_charm: bool,
+    custom: Option<CustomMethod>,
+) -> Box<dyn Fn(i32) -> i32> {
+    match custom {
+        Some(method) => Box::new(move |count| method.rolls(count, has_charm)),
+        None => {
+            let rolls = hunttype.rolls();
+            Box::new(move |count| rolls(count, has_charm))
+        }
+    }
+}
+
+/**
+    `At Least`
+
+    The chance to have found at least `successes` shinies in `rolls` rolls with odds `odds`
+
+    # Arguments
+      * `successes`: shinies that are needed
+      * `rolls`: rolls so far
+      * `odds`: base odds of a single roll
+*/
+pub fn at_least(successes: i32, rolls: i32, odds: f64) -> f64 {
+    if successes <= 0 {
+        return 1.0;
+    }
+
+    let prob = 1.0 / odds.max(1.0);
+    let rolls = rolls.max(0);
+    let fewer = (0..successes.min(rolls + 1))
+        .map(|k| {
+            store::n_choose_k(rolls as usize, k as usize)
+                * prob.powi(k)
+                * (1.0 - prob).powi(rolls - k)
+        })
+        .sum::<f64>();
+
+    (1.0 - fewer).clamp(0.0, 1.0)
+}
+
+pub struct Statistics {
+    /// chance of a single roll not being shiny
+    miss: f64,
+    odds: f64,
+    count: i32,
+    /// shinies that are still needed
+    successes: i32,
+    rolls: Box<dyn Fn(i32) -> i32>,
+}
+
+impl Statistics {
+    /**
+        `New Statistics`
+
+        # Arguments
+          * `odds`: base odds of a single roll
+          * `count`: encounters so far
+          * `rolls`: rolls after the given amount of encounters, has to grow with the count
+    */
+    pub fn new(odds: f64, count: i32, rolls: Box<dyn Fn(i32) -> i32>) -> Self {
+        Self {
+            miss: 1.0 - 1.0 / odds.max(1.0),
+            odds,
+            count: count.max(0),
+            successes: 1,
+            rolls,
+        }
+    }
+
+    /// Estimates for finding `successes` more shinies instead of only the next one
+    pub fn with_successes(mut self, successes: i32) -> Self {
+        self.successes = successes.max(0);
+        self
+    }
+
+    pub fn successes(&self) -> i32 {
+        self.successes
+    }
+
+    pub fn count(&self) -> i32 {
+        self.count
+    }
+
+    /// Rolls after `encounters` encounters in total
+    pub fn rolls(&self, encounters: i32) -> i32 {
+        (self.rolls)(encounters)
+    }
+
+    /// Chance to have found the shinies after `encounters` encounters in total
+    pub fn probability(&self, encounters: i32) -> f64 {
+        at_least(self.successes, (self.rolls)(encounters), self.odds)
+    }
+
+    /**
+        `Encounters For`
+
+        # Returns
+          * `Some(i32)`: The total encounters after which the shinies are found with chance `chance`
+          * `None`: This would take more than `MAX_ENCOUNTERS` encounters
+    */
+    pub fn encounters_for(&self, chance: f64) -> Option<i32> {
+        if self.probability(0) >= chance {
+            return Some(0);
+        }
+
+        // the rolls grow with the count, so look for a count that is large enough
+        // and narrow it down from there
+        let mut high = self.count.max(1);
+        while self.probability(high) < chance {
+            if high >= MAX_ENCOUNTERS {
+                return None;
+            }
+            high = high.saturating_mul(2).min(MAX_ENCOUNTERS);
+        }
+
+        let mut low = 0;
+        while high - low > 1 {
+            let mid = low + (high - low) / 2;
+            if self.probability(mid) >= chance {
+                high = mid
+            } else {
+                low = mid
+            }
+        }
+
+        Some(high)
+    }
+
+    /// Encounters still needed to reach `chance`, `Some(0)` when it is already reached
+    pub fn remaining_for(&self, chance: f64) -> Option<i32> {
+        self.encounters_for(chance)
+            .map(|total| (total - self.count).max(0))
+    }
+
+    /**
+        `Expected Remaining`
+
+        The mean of the encounters until the last of the needed shinies,
+        that is the sum of the chances to still be hunting after every further encounter.
+        An encounter finds at most one shiny, however many rolls it has.
+        Past `EXACT_ENCOUNTERS` the rolls per encounter are taken as constant
+        and every shiny still missing takes the mean of a geometric distribution.
+
+        # Returns
+          * `None`: The odds never allow a shiny
+    */
+    pub fn expected_remaining(&self) -> Option<f64> {
+        if self.successes == 0 {
+            return Some(0.0);
+        }
+        if self.miss >= 1.0 {
+            return None;
+        }
+
+        // chance to have found exactly `i` of the needed shinies so far
+        let mut found = vec![0.0; self.successes as usize];
+        found[0] = 1.0;
+        let mut expected = 0.0;
+        let mut last_rolls = (self.rolls)(self.count);
+        let mut hit = 0.0;
+
+        for encounter in 1..=EXACT_ENCOUNTERS {
+            let still_hunting = found.iter().sum::<f64>();
+            if still_hunting < 1e-12 {
+                return Some(expected);
+            }
+            expected += still_hunting;
+
+            let rolls = (self.rolls)(self.count + encounter);
+            hit = 1.0 - self.miss.powi(rolls - last_rolls);
+            last_rolls = rolls;
+            for i in (0..found.len()).rev() {
+                let moved = found[i] * hit;
+                found[i] -= moved;
+                if let Some(next) = found.get_mut(i + 1) {
+                    *next += moved
+                }
+            }
+        }
+
+        if hit <= 0.0 {
+            return None;
+        }
+
+        let rest = found
+            .iter()
+            .enumerate()
+            .map(|(i, chance)| chance * (found.len() - i) as f64 / hit)
+            .sum::<f64>();
+
+        Some(expected + rest)
+    }
+
+    /// Time until the needed shinies are expected when every encounter takes `step`
+    pub fn expected_time(&self, step: TimeDelta) -> Option<TimeDelta> {
+        let remaining = self.expected_remaining()?;
+        let millis = step.num_milliseconds() as f64 * remaining;
+        if !millis.is_finite() || millis > i64::MAX as f64 {
+            return None;
+        }
+
+        Some(TimeDelta::milliseconds(millis as i64))
+    }
+}
+
+/// A phase as it counts towards the statistics of the account
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunt {
+    pub key: CountableId,
+    /// name of the counter followed by the name of the phase
+    pub name: String,
+    pub method: String,
+    pub year: i32,
+    pub count: i32,
+    pub time: TimeDelta,
+    pub rolls: i32,
+    pub odds: f64,
+    pub success: bool,
+}
+
+impl Hunt {
+    /**
+        `Hunt Percentile`
+
+        The share of hunts with the same odds that would have found the shiny in fewer rolls,
+        low values are lucky and values around 0.5 are what is to be expected
+    */
+    pub fn percentile(&self) -> f64 {
+        1.0 - (1.0 - 1.0 / self.odds.max(1.0)).powi(self.rolls)
+    }
+}
+
+/**
+    `Hunts`
+
+    All phases of `store` that are not archived, built from `rolls`, `odds` and `is_success`.
+*/
+pub fn hunts(store: &CountableStore) -> Vec<Hunt> {
+    use chrono::Datelike;
+
+    let recursive = store.recursive_ref();
+    let mut hunts = store
+        .nodes()
+        .into_iter()
+        .filter(|c| matches!(c, Countable::Phase(_)) && !c.is_archived())
+        .map(|c| {
+            let key = c.uuid().into();
+            let name = match store.parent(&key) {
+                Some(parent) => format!("{} - {}", store.name(&parent), store.name(&key)),
+                None => store.name(&key),
+            };
+            let method = match recursive.hunttype(&key) {
+                Hunttype::Custom(id) => store
+                    .custom_methods()
+                    .iter()
+                    .find(|m| m.uuid == id)
+                    .map(|m| m.name.clone())
+                    .unwrap_or(String::from("Deleted method")),
+                hunttype => hunttype.repr().to_string(),
+            };
+
+            Hunt {
+                key,
+                name,
+                method,
+                year: store.created_at(&key).year(),
+                count: recursive.count(&key),
+                time: recursive.time(&key),
+                rolls: recursive.rolls(&key),
+                odds: recursive.odds(&key),
+                success: store.is_success(&key),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    hunts.sort_by_key(|h| (h.year, h.name.clone()));
+    hunts
+}
+
+/// Combined luck of all successful hunts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Luck {
+    /// share of all possible outcomes that would have been worse, 0.5 is average luck
+    pub score: f64,
+    /// 95% confidence interval of `score`
+    pub low: f64,
+    pub high: f64,
+    pub hunts: usize,
+}
+
+impl Luck {
+    /**
+        `Luck From Hunts`
+
+        Without luck the percentiles of finished hunts are spread evenly between 0 and 1,
+        the score is one minus their mean.
+
+        # Returns
+          * `None`: No hunt was successful yet
+    */
+    pub fn from_hunts(hunts: &[Hunt]) -> Option<Self> {
+        let percentiles = hunts
+            .iter()
+            .filter(|h| h.success)
+            .map(Hunt::percentile)
+            .collect::<Vec<_>>();
+        if percentiles.is_empty() {
+            return None;
+        }
+
+        let n = percentiles.len() as f64;
+        let mean = percentiles.iter().sum::<f64>() / n;
+        // a single hunt has no spread, use the one of evenly spread percentiles instead
+        let deviation = if percentiles.len() > 1 {
+            (percentiles.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            (1.0_f64 / 12.0).sqrt()
+        };
+        let margin = 1.96 * deviation / n.sqrt();
+
+        Some(Self {
+            score: 1.0 - mean,
+            low: (1.0 - mean - margin).max(0.0),
+            high: (1.0 - mean + margin).min(1.0),
+            hunts: percentiles.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statistics(hunttype: Hunttype, count: i32) -> Statistics {
+        Statistics::new(hunttype.odds(), count, rolls_fn(hunttype, true, None))
+    }
+
+    #[test]
+    fn half_chance_of_new_odds() {
+        let stats = Statistics::new(4096.0, 0, rolls_fn(Hunttype::NewOdds, false, None));
+        // ln 2 * 4096
+        assert_eq!(stats.encounters_for(0.5), Some(2839));
+
+        let stats = Statistics::new(4096.0, 1000, rolls_fn(Hunttype::NewOdds, false, None));
+        assert_eq!(stats.remaining_for(0.5), Some(1839));
+        assert_eq!(stats.remaining_for(0.01), Some(0));
+    }
+
+    #[test]
+    fn expected_remaining_of_constant_odds() {
+        for odds in [100.0, 4096.0, 8192.0] {
+            let stats = Statistics::new(odds, 0, Box::new(|count| count));
+            let expected = stats.expected_remaining().unwrap();
+            assert!((expected - odds).abs() < 1e-6 * odds, "{odds}: {expected}");
+
+            // the odds have no memory
+            let stats = Statistics::new(odds, 5000, Box::new(|count| count));
+            let expected = stats.expected_remaining().unwrap();
+            assert!((expected - odds).abs() < 1e-6 * odds, "{odds}: {expected}");
+
+            let stats = Statistics::new(odds, 0, Box::new(|count| count)).with_successes(3);
+            let expected = stats.expected_remaining().unwrap();
+            assert!(
+                (expected - 3.0 * odds).abs() < 1e-6 * odds,
+                "{odds}: {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn chains_only_raise_the_chance() {
+        for hunttype in [Hunttype::SOS, Hunttype::Radar(Radar::GenIV)] {
+            let stats = statistics(hunttype, 0);
+            for count in 0..200 {
+                assert!(
+                    stats.rolls(count + 1) > stats.rolls(count),
+                    "{hunttype:?} {count}"
+                );
+            }
+
+            let encounters = [0.1, 0.5, 0.9, 0.99].map(|chance| stats.encounters_for(chance));
+            assert!(encounters.windows(2).all(|w| w[0] <= w[1]), "{hunttype:?}");
+            // the chain makes it faster than the base odds
+            assert!(
+                stats.expected_remaining().unwrap() < hunttype.odds() / 3.0,
+                "{hunttype:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn more_shinies_take_longer() {
+        // the chance of a single success is the classic one
+        let single = 1.0 - (1.0 - 1.0 / 4096.0_f64).powi(4096);
+        assert!((at_least(1, 4096, 4096.0) - single).abs() < 1e-12);
+
+        let chances = (1..=5)
+            .map(|target| at_least(target, 10_000, 4096.0))
+            .collect::<Vec<_>>();
+        assert!(chances.windows(2).all(|w| w[0] > w[1]), "{chances:?}");
+        // more shinies than rolls are impossible
+        assert_eq!(at_least(3, 2, 1.0), 0.0);
+
+        let encounters = (1..=5)
+            .map(|target| statistics(Hunttype::NewOdds, 0).with_successes(target))
+            .map(|stats| stats.encounters_for(0.5).unwrap())
+            .collect::<Vec<_>>();
+        assert!(encounters.windows(2).all(|w| w[0] < w[1]), "{encounters:?}");
+    }
+
+    #[test]
+    fn nothing_left_to_find() {
+        assert_eq!(at_least(0, 0, 4096.0), 1.0);
+        assert_eq!(at_least(1, 0, 4096.0), 0.0);
+
+        let stats = statistics(Hunttype::NewOdds, 10).with_successes(0);
+        assert_eq!(stats.encounters_for(0.99), Some(0));
+        assert_eq!(stats.remaining_for(0.99), Some(0));
+        assert_eq!(stats.expected_remaining(), Some(0.0));
+    }
+
+    #[test]
+    fn gives_up_past_max_encounters() {
+        let stats = Statistics::new(1e15, 0, Box::new(|count| count));
+        assert_eq!(stats.encounters_for(0.5), None);
+        assert!(stats.expected_time(TimeDelta::days(365)).is_none());
+
+        let stats = Statistics::new(f64::INFINITY, 0, Box::new(|count| count));
+        assert_eq!(stats.expected_remaining(), None);
+    }
+
+    fn hunt(rolls: i32, odds: f64, success: bool) -> Hunt {
+        Hunt {
+            key: uuid::Uuid::new_v4().into(),
+            name: String::from("hunt"),
+            method: Hunttype::NewOdds.repr().to_string(),
+            year: 2024,
+            count: rolls,
+            time: TimeDelta::zero(),
+            rolls,
+            odds,
+            success,
+        }
+    }
+
+    #[test]
+    fn median_hunt_has_average_luck() {
+        // one roll with odds 2 is done half of the time
+        let luck = Luck::from_hunts(&[hunt(1, 2.0, true)]).unwrap();
+        assert_eq!(luck.score, 0.5);
+        assert_eq!(luck.hunts, 1);
+        assert!(luck.low < 0.5 && luck.high > 0.5);
+
+        // hunts that are still going do not count
+        let luck = Luck::from_hunts(&[hunt(1, 2.0, true), hunt(10_000, 4096.0, false)]).unwrap();
+        assert_eq!(luck.score, 0.5);
+        assert_eq!(luck.hunts, 1);
+    }
+
+    #[test]
+    fn quick_hunts_are_lucky() {
+        let lucky = Luck::from_hunts(&[hunt(100, 4096.0, true), hunt(200, 4096.0, true)]).unwrap();
+        let unlucky =
+            Luck::from_hunts(&[hunt(20_000, 4096.0, true), hunt(30_000, 4096.0, true)]).unwrap();
+        assert!(lucky.score > 0.9);
+        assert!(unlucky.score < 0.1);
+        assert!((0.0..=1.0).contains(&lucky.high) && (0.0..=1.0).contains(&unlucky.low));
+
+        assert_eq!(Luck::from_hunts(&[hunt(100, 4096.0, false)]), None);
+    }
+}
@@ -1,10 +1,11 @@
 use chrono::TimeDelta;
-use leptos::{SignalGetUntracked, SignalUpdateUntracked};
+use leptos::{SignalGetUntracked, SignalUpdateUntracked, SignalWithUntracked};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use super::*;
+use operation::OperationKind;
 
 pub trait StoreMethod: Default {}
 
@@ -32,6 +33,21 @@ pub struct CountableStore<M: StoreMethod, C: StoreCheck> {
     pub(crate) store: HashMap<CountableId, Countable>,
     pub(crate) selection: Vec<CountableId>,
     pub(crate) is_changed: RefCell<bool>,
+    /// countables changed locally since the store was last sent to the server
+    #[serde(skip)]
+    pub(crate) unsaved: RefCell<HashSet<CountableId>>,
+    /// countables changed locally since the store was last written to IndexedDB
+    #[serde(skip)]
+    pub(crate) unwritten: RefCell<HashSet<CountableId>>,
+    /// server cursor up to which all changes are included, see `server::changes_since`
+    #[serde(default)]
+    pub(crate) sync_cursor: i64,
+    /// countables removed on the server, only filled in by `server::changes_since`
+    #[serde(default)]
+    pub(crate) removed: Vec<CountableId>,
+    /// methods of the owner that phases with `Hunttype::Custom` refer to
+    #[serde(default)]
+    pub(crate) custom_methods: Vec<CustomMethod>,
     phantom_data: std::marker::PhantomData<(M, C)>,
 }
 
@@ -60,6 +76,20 @@ where
         self.store.get(countable).cloned()
     }
 
+    pub fn has_unsaved(&self, countable: &CountableId) -> bool {
+        self.unsaved.borrow().contains(countable)
+    }
+
+    fn mark_changed(&self, countable: &CountableId) {
+        self.is_changed.replace(true);
+        self.unsaved.borrow_mut().insert(*countable);
+        self.unwritten.borrow_mut().insert(*countable);
+    }
+
+    pub fn sync_cursor(&self) -> i64 {
+        self.sync_cursor
+    }
+
     pub fn len(&self) -> usize {
         self.store.len()
     }
@@ -80,10 +110,49 @@ where
             owner: self.owner,
             store,
             selection: self.selection.clone(),
+            custom_methods: self.custom_methods.clone(),
             ..Default::default()
         }
     }
 
+    pub(crate) fn custom_methods(&self) -> &[CustomMethod] {
+        &self.custom_methods
+    }
+
+    pub(crate) fn set_custom_methods(&mut self, methods: Vec<CustomMethod>) {
+        self.custom_methods = methods
+    }
+
+    fn custom_method(&self, hunttype: Hunttype) -> Option<&CustomMethod> {
+        match hunttype {
+            Hunttype::Custom(id) => self.custom_methods.iter().find(|m| m.uuid == id),
+            _ => None,
+        }
+    }
+
+    /// Rolls of `count` encounters, custom methods that were deleted count like `NewOdds`
+    fn method_rolls(&self, hunttype: Hunttype, count: i32, has_charm: bool) -> i32 {
+        self.method_rolls_fn(hunttype, has_charm)(count)
+    }
+
+    /// Rolls of a method as a function of the count
+    fn method_rolls_fn(&self, hunttype: Hunttype, has_charm: bool) -> Box<dyn Fn(i32) -> i32> {
+        statistics::rolls_fn(hunttype, has_charm, self.custom_method(hunttype).cloned())
+    }
+
+    /// Odds of `hunttype` in `game`, a custom method decides its own odds regardless of the game
+    pub(crate) fn method_odds(&self, hunttype: Hunttype, game: Option<Game>) -> f64 {
+        match self.custom_method(hunttype) {
+            Some(method) => method.odds(),
+            None => game.map(|g| g.odds()).unwrap_or(hunttype.odds()),
+        }
+    }
+
+    /// Odds of `phase`, a custom method decides its own odds regardless of the game
+    fn phase_odds(&self, phase: &nodes::Phase) -> f64 {
+        self.method_odds(phase.hunt_type, phase.game)
+    }
+
     pub fn root_nodes(&self) -> Vec<Countable> {
         let this: &CountableStore<Recursive, Checked> = unsafe { std::mem::transmute(self) };
         this.store
@@ -114,7 +183,7 @@ where
                     }
                     has
                 }
-                Countable::Phase(p) => p.lock()?.has_charm,
+                Countable::Phase(p) => p.lock()?.charm_applies(),
                 Countable::Chain(_) => todo!(),
             },
         )
@@ -124,6 +193,50 @@ where
         self.has_charm_checked(countable).unwrap()
     }
 
+    /// Game of a phase, counters report the game of their newest phase
+    pub fn game_checked(&self, countable: &CountableId) -> Result<Option<Game>, AppError> {
+        Ok(
+            match self
+                .store
+                .get(countable)
+                .ok_or(AppError::CountableNotFound)?
+            {
+                Countable::Counter(c) => match c.lock()?.children.last() {
+                    Some(child) => self.game_checked(child)?,
+                    None => None,
+                },
+                Countable::Phase(p) => p.lock()?.game,
+                Countable::Chain(_) => todo!(),
+            },
+        )
+    }
+
+    pub fn game(&self, countable: &CountableId) -> Option<Game> {
+        self.game_checked(countable).unwrap_or_default()
+    }
+
+    /// Amount of shinies a counter needs, phases report the target of their counter
+    pub fn target_checked(&self, countable: &CountableId) -> Result<Option<i32>, AppError> {
+        Ok(
+            match self
+                .store
+                .get(countable)
+                .ok_or(AppError::CountableNotFound)?
+            {
+                Countable::Counter(c) => c.lock()?.target,
+                Countable::Phase(p) => {
+                    let parent = p.lock()?.parent;
+                    self.target_checked(&parent)?
+                }
+                Countable::Chain(_) => todo!(),
+            },
+        )
+    }
+
+    pub fn target(&self, countable: &CountableId) -> Option<i32> {
+        self.target_checked(countable).unwrap_or_default()
+    }
+
     pub fn is_success_checked(&self, countable: &CountableId) -> Result<bool, AppError> {
         Ok(
             match self
@@ -157,11 +270,12 @@ where
             Countable::Phase(p) => {
                 let success = p.lock()?.success;
                 p.lock()?.success = !success;
+                operation::record(*countable, OperationKind::SetSuccess(!success))
             }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -198,6 +312,7 @@ impl Savable for CountableStore<Level, UnChecked> {
         use wasm_bindgen::JsValue;
 
         self.is_changed.replace(false);
+        self.unwritten.borrow_mut().clear();
 
         Box::pin(async move {
             obj.clear().await?;
@@ -216,10 +331,8 @@ impl Savable for CountableStore<Level, UnChecked> {
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), leptos::ServerFnError>>>>
     {
         self.is_changed.replace(false);
-        let cloned = self.clone();
-        Box::pin(api::update_countable_many(
-            cloned.store.into_values().collect(),
-        ))
+        self.unsaved.borrow_mut().clear();
+        Box::pin(outbox::flush())
     }
 
     fn message(&self) -> Option<leptos::View> {
@@ -231,7 +344,7 @@ impl Savable for CountableStore<Level, UnChecked> {
     }
 
     fn has_change(&self) -> bool {
-        *self.is_changed.borrow()
+        *self.is_changed.borrow() || operation::has_pending()
     }
 }
 
@@ -314,6 +427,59 @@ impl<M: StoreMethod> CountableStore<M, Checked> {
         Ok(())
     }
 
+    /**
+        `Merge Remote Checked`
+
+        Merges countables pushed by the server into the store.
+        Countables with local changes that were not saved yet are kept as they are,
+        children that only exist locally stay attached to their parent.
+        Countables the server removed are dropped together with their local changes.
+        Unlike [`merge`](Self::merge) this does not mark the store as changed.
+
+        # Errors
+          * [AppError::LockMutex]
+          * [AppError::CannotContainChildren]
+    */
+    pub fn merge_remote(&mut self, other: Self) -> Result<(), AppError> {
+        for (id, other_c) in other.store {
+            if self.has_unsaved(&id) {
+                continue;
+            }
+
+            if let Some(Countable::Counter(local)) = self.get(&id)
+                && let Countable::Counter(remote) = &other_c
+            {
+                let local_children = local.lock()?.children.clone();
+                let mut remote = remote.lock()?;
+                for child in local_children {
+                    if !remote.children.contains(&child) {
+                        remote.children.push(child)
+                    }
+                }
+            }
+
+            self.store.insert(id, other_c);
+        }
+
+        for id in other.removed.iter() {
+            self.store.remove(id);
+            self.selection.retain(|s| s != id);
+        }
+        if !other.removed.is_empty() {
+            for countable in self.store.values() {
+                if let Countable::Counter(c) = countable {
+                    c.lock()?
+                        .children
+                        .retain(|child| !other.removed.contains(child))
+                }
+            }
+        }
+
+        self.sync_cursor = self.sync_cursor.max(other.sync_cursor);
+
+        Ok(())
+    }
+
     pub fn new_countable(
         &mut self,
         name: &str,
@@ -321,7 +487,16 @@ impl<M: StoreMethod> CountableStore<M, Checked> {
         parent: Option<CountableId>,
     ) -> Result<CountableId, AppError> {
         let countable = Countable::new(name, kind, self.owner, parent);
+        self.insert_new(countable, parent)
+    }
+
+    fn insert_new(
+        &mut self,
+        countable: Countable,
+        parent: Option<CountableId>,
+    ) -> Result<CountableId, AppError> {
         let key = countable.clone().into();
+        operation::record(key, OperationKind::Create(countable.detached_checked()?));
         self.store.insert(key, countable);
         if let Some(parent) = parent {
             self.get(&parent)
@@ -329,11 +504,105 @@ impl<M: StoreMethod> CountableStore<M, Checked> {
                 .add_child_checked(key)?
         }
 
-        self.is_changed.replace(true);
+        self.mark_changed(&key);
 
         Ok(key)
     }
 
+    /**
+        `New Phase Checked`
+
+        Adds a phase named after its position to `counter`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+    */
+    pub fn new_phase(&mut self, counter: &CountableId) -> Result<CountableId, AppError> {
+        let name = self.phase_name(counter)?;
+        self.new_countable(&name, CountableKind::Phase, Some(*counter))
+    }
+
+    fn phase_name(&self, counter: &CountableId) -> Result<String, AppError> {
+        let phases = match self.get(counter).ok_or(AppError::CountableNotFound)? {
+            Countable::Counter(c) => c.lock()?.children.len(),
+            _ => 0,
+        };
+        Ok(format!("Phase {}", phases + 1))
+    }
+
+    /**
+        `Advance Phase Checked`
+
+        Continues the hunt of `phase` in a new phase once it is a success,
+        the new phase keeps the method, game, charm and step size of `phase`
+
+        # Arguments
+          * `phase`: &[CountableId] of the phase that found its shiny
+          * `record_found`: appends which of the shinies of the counter was found to the name of `phase`
+
+        # Returns
+          * `Ok(Some(CountableId))`: The new phase
+          * `Ok(None)`: `phase` is no success or its hunt already continues in a later phase
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+    */
+    pub fn advance(
+        &mut self,
+        phase: &CountableId,
+        record_found: bool,
+    ) -> Result<Option<CountableId>, AppError> {
+        let parent = match self.get(phase).ok_or(AppError::CountableNotFound)? {
+            Countable::Phase(p) => p.lock()?.parent,
+            _ => return Ok(None),
+        };
+        let phases = match self.get(&parent).ok_or(AppError::CountableNotFound)? {
+            Countable::Counter(c) => c.lock()?.children.clone(),
+            _ => return Ok(None),
+        };
+        if phases.last() != Some(phase) || !self.is_success_checked(phase)? {
+            return Ok(None);
+        }
+
+        if record_found {
+            let found = phases
+                .iter()
+                .map(|p| self.is_success_checked(p))
+                .collect::<Result<Vec<_>, AppError>>()?
+                .into_iter()
+                .filter(|s| *s)
+                .count();
+            let name = match self.target_checked(&parent)? {
+                Some(target) => format!("{} - Shiny {found}/{target}", self.name(phase)?),
+                None => format!("{} - Shiny {found}", self.name(phase)?),
+            };
+            // a name that grew too long is kept as it was
+            if validate::name(&name).is_ok() {
+                self.set_name(phase, &name)?
+            }
+        }
+
+        let next = Countable::new(
+            &self.phase_name(&parent)?,
+            CountableKind::Phase,
+            self.owner,
+            Some(parent),
+        );
+        if let (Countable::Phase(next), Some(Countable::Phase(previous))) = (&next, self.get(phase))
+        {
+            let previous = previous.lock()?;
+            let mut next = next.lock()?;
+            next.hunt_type = previous.hunt_type;
+            next.game = previous.game;
+            next.has_charm = previous.has_charm;
+            next.step_size = previous.step_size.max(1);
+        }
+
+        self.insert_new(next, Some(parent)).map(Some)
+    }
+
     pub fn archive(&self, countable: &CountableId) -> Result<Countable, AppError> {
         let this: &CountableStore<Recursive, Checked> = unsafe { std::mem::transmute(self) };
 
@@ -347,7 +616,8 @@ impl<M: StoreMethod> CountableStore<M, Checked> {
             Countable::Chain(_) => todo!(),
         }
 
-        self.is_changed.replace(true);
+        operation::record(*countable, OperationKind::Archive);
+        self.mark_changed(countable);
 
         this.get(countable).ok_or(AppError::CountableNotFound)
     }
@@ -480,7 +750,227 @@ impl<M: StoreMethod> CountableStore<M, Checked> {
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        operation::record(*countable, OperationKind::Rename(name.into()));
+        self.mark_changed(countable);
+
+        Ok(())
+    }
+
+    /**
+        `Set Countable Hunttype Checked`
+
+        Setting the hunttype of a `Counter` changes the hunttype of all its phases
+
+        # Arguments
+          * `countable`: &[CountableId]
+          * `hunttype`: [Hunttype]; The new hunttype for the `Countable`
+
+        # Returns
+          * `Ok(())`
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+
+        [Countable]\
+        [AppError]
+    */
+    pub fn set_hunttype(
+        &self,
+        countable: &CountableId,
+        hunttype: Hunttype,
+    ) -> Result<(), AppError> {
+        match self
+            .store
+            .get(countable)
+            .ok_or(AppError::CountableNotFound)?
+        {
+            Countable::Counter(c) => {
+                for child in c.lock()?.children.iter() {
+                    if let Some(Countable::Phase(p)) = self.store.get(child) {
+                        p.lock()?.hunt_type = hunttype
+                    }
+                }
+            }
+            Countable::Phase(p) => p.lock()?.hunt_type = hunttype,
+            Countable::Chain(_) => todo!(),
+        };
+
+        operation::record(*countable, OperationKind::SetHunttype(hunttype));
+        self.mark_changed(countable);
+
+        Ok(())
+    }
+
+    /**
+        `Set Countable Step Size Checked`
+
+        Setting the step size of a `Counter` changes the step size of its newest phase
+
+        # Arguments
+          * `countable`: &[CountableId]
+          * `step`: i32; The new step size for the `Countable`
+
+        # Returns
+          * `Ok(())`
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+
+        [Countable]\
+        [AppError]
+    */
+    pub fn set_step_size(&self, countable: &CountableId, step: i32) -> Result<(), AppError> {
+        match self
+            .store
+            .get(countable)
+            .ok_or(AppError::CountableNotFound)?
+        {
+            Countable::Counter(c) => {
+                if let Some(Countable::Phase(p)) = c
+                    .lock()?
+                    .children
+                    .last()
+                    .and_then(|child| self.store.get(child))
+                {
+                    p.lock()?.step_size = step
+                }
+            }
+            Countable::Phase(p) => p.lock()?.step_size = step,
+            Countable::Chain(_) => todo!(),
+        };
+
+        operation::record(*countable, OperationKind::SetStepSize(step));
+        self.mark_changed(countable);
+
+        Ok(())
+    }
+
+    /**
+        `Set Countable Charm Checked`
+
+        Setting the charm of a `Counter` changes the charm of all its phases
+
+        # Arguments
+          * `countable`: &[CountableId]
+          * `has_charm`: bool; Whether the `Countable` is hunted with a shiny charm
+
+        # Returns
+          * `Ok(())`
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+
+        [Countable]\
+        [AppError]
+    */
+    pub fn set_charm(&self, countable: &CountableId, has_charm: bool) -> Result<(), AppError> {
+        match self
+            .store
+            .get(countable)
+            .ok_or(AppError::CountableNotFound)?
+        {
+            Countable::Counter(c) => {
+                for child in c.lock()?.children.iter() {
+                    if let Some(Countable::Phase(p)) = self.store.get(child) {
+                        p.lock()?.has_charm = has_charm
+                    }
+                }
+            }
+            Countable::Phase(p) => p.lock()?.has_charm = has_charm,
+            Countable::Chain(_) => todo!(),
+        };
+
+        operation::record(*countable, OperationKind::SetCharm(has_charm));
+        self.mark_changed(countable);
+
+        Ok(())
+    }
+
+    /**
+        `Set Countable Game Checked`
+
+        Setting the game of a `Counter` changes the game of all its phases
+
+        # Arguments
+          * `countable`: &[CountableId]
+          * `game`: Option<[Game]>; The game the `Countable` is hunted in, `None` when unknown
+
+        # Returns
+          * `Ok(())`
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+
+        [Countable]\
+        [AppError]
+    */
+    pub fn set_game(&self, countable: &CountableId, game: Option<Game>) -> Result<(), AppError> {
+        match self
+            .store
+            .get(countable)
+            .ok_or(AppError::CountableNotFound)?
+        {
+            Countable::Counter(c) => {
+                for child in c.lock()?.children.iter() {
+                    if let Some(Countable::Phase(p)) = self.store.get(child) {
+                        p.lock()?.game = game
+                    }
+                }
+            }
+            Countable::Phase(p) => p.lock()?.game = game,
+            Countable::Chain(_) => todo!(),
+        };
+
+        operation::record(*countable, OperationKind::SetGame(game));
+        self.mark_changed(countable);
+
+        Ok(())
+    }
+
+    /**
+        `Set Countable Target Checked`
+
+        The target belongs to the `Counter`, setting it on a `Phase` sets it on its counter
+
+        # Arguments
+          * `countable`: &[CountableId]
+          * `target`: Option<i32>; Amount of shinies the hunt needs, `None` for no target
+
+        # Returns
+          * `Ok(())`
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+
+        [Countable]\
+        [AppError]
+    */
+    pub fn set_target(&self, countable: &CountableId, target: Option<i32>) -> Result<(), AppError> {
+        match self
+            .store
+            .get(countable)
+            .ok_or(AppError::CountableNotFound)?
+        {
+            Countable::Counter(c) => c.lock()?.target = target,
+            Countable::Phase(p) => {
+                let parent = p.lock()?.parent;
+                return self.set_target(&parent, target);
+            }
+            Countable::Chain(_) => todo!(),
+        };
+
+        operation::record(*countable, OperationKind::SetTarget(target));
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -491,6 +981,10 @@ impl<M: StoreMethod> CountableStore<M, UnChecked> {
         self.checked_mut().merge(other.checked()).unwrap()
     }
 
+    pub fn merge_remote(&mut self, other: Self) {
+        self.checked_mut().merge_remote(other.checked()).unwrap()
+    }
+
     pub fn new_countable(
         &mut self,
         name: &str,
@@ -502,6 +996,18 @@ impl<M: StoreMethod> CountableStore<M, UnChecked> {
             .unwrap()
     }
 
+    pub fn new_phase(&mut self, counter: &CountableId) -> CountableId {
+        self.checked_mut().new_phase(counter).unwrap()
+    }
+
+    pub fn advance(&mut self, phase: &CountableId, record_found: bool) -> Option<CountableId> {
+        match self.checked_mut().advance(phase, record_found) {
+            Ok(next) => next,
+            Err(AppError::CountableNotFound) => None,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
     /**
         `CountableStore Filter UnChecked`
 
@@ -604,7 +1110,28 @@ impl<M: StoreMethod> CountableStore<M, UnChecked> {
             Err(err) => panic!("{err}"),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
+    }
+
+    /**
+        `Set Countable Hunttype`
+
+        Setting the hunttype of a `Counter` changes the hunttype of all its phases
+
+        # Arguments
+          * `countable`: &[CountableId]
+          * `hunttype`: [Hunttype]; The new hunttype for the `Countable`
+
+        # Panics
+          * lock on a `Mutex` fails
+
+        [Countable]
+    */
+    pub fn set_hunttype(&self, countable: &CountableId, hunttype: Hunttype) {
+        match self.checked_ref().set_hunttype(countable, hunttype) {
+            Ok(_) | Err(AppError::CountableNotFound) => (),
+            Err(err) => panic!("{err}"),
+        };
     }
 }
 
@@ -709,11 +1236,15 @@ impl CountableStore<Level, Checked> {
     pub fn set_count(&self, countable: &CountableId, count: i32) -> Result<(), AppError> {
         match self.get(countable).ok_or(AppError::CountableNotFound)? {
             Countable::Counter(_) => (),
-            Countable::Phase(p) => p.lock()?.count = count,
+            Countable::Phase(p) => {
+                let diff = count - p.lock()?.count;
+                p.lock()?.count = count;
+                operation::record(*countable, OperationKind::AddCount(diff))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -742,11 +1273,14 @@ impl CountableStore<Level, Checked> {
     pub fn add_count(&self, countable: &CountableId, count: i32) -> Result<(), AppError> {
         match self.get(countable).ok_or(AppError::CountableNotFound)? {
             Countable::Counter(_) => (),
-            Countable::Phase(p) => p.lock()?.count += count,
+            Countable::Phase(p) => {
+                p.lock()?.count += count;
+                operation::record(*countable, OperationKind::AddCount(count))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -813,13 +1347,14 @@ impl CountableStore<Level, Checked> {
             Countable::Counter(_) => (),
             Countable::Phase(p) => {
                 if let Ok(mut phase) = p.lock() {
-                    phase.count += phase.step_size
+                    phase.count += phase.step_size;
+                    operation::record(*countable, OperationKind::AddCount(phase.step_size))
                 }
             }
             Countable::Chain(_) => todo!(),
         }
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -880,11 +1415,15 @@ impl CountableStore<Level, Checked> {
     pub fn set_time(&self, countable: &CountableId, time: TimeDelta) -> Result<(), AppError> {
         match self.get(countable).ok_or(AppError::CountableNotFound)? {
             Countable::Counter(_) => (),
-            Countable::Phase(p) => p.lock()?.time = time,
+            Countable::Phase(p) => {
+                let diff = time - p.lock()?.time;
+                p.lock()?.time = time;
+                operation::record(*countable, OperationKind::AddTime(diff.num_milliseconds()))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -913,11 +1452,14 @@ impl CountableStore<Level, Checked> {
     pub fn add_time(&self, countable: &CountableId, time: TimeDelta) -> Result<(), AppError> {
         match self.get(countable).ok_or(AppError::CountableNotFound)? {
             Countable::Counter(_) => (),
-            Countable::Phase(p) => p.lock()?.time += time,
+            Countable::Phase(p) => {
+                p.lock()?.time += time;
+                operation::record(*countable, OperationKind::AddTime(time.num_milliseconds()))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -949,7 +1491,8 @@ impl CountableStore<Level, Checked> {
                 .ok_or(AppError::CountableNotFound)?
             {
                 Countable::Counter(_) => 0,
-                Countable::Phase(_) => self.recursive_ref().hunttype(countable)?.rolls()(
+                Countable::Phase(_) => self.method_rolls(
+                    self.recursive_ref().hunttype(countable)?,
                     self.count(countable)?,
                     self.has_charm_checked(countable)?,
                 ),
@@ -985,7 +1528,7 @@ impl CountableStore<Level, Checked> {
                 .ok_or(AppError::CountableNotFound)?
             {
                 Countable::Counter(_) => 0.0,
-                Countable::Phase(p) => p.lock()?.hunt_type.odds(),
+                Countable::Phase(p) => self.phase_odds(&*p.lock()?),
                 Countable::Chain(_) => todo!(),
             },
         )
@@ -1283,11 +1826,14 @@ impl CountableStore<Recursive, Checked> {
                     }
                 }
             }
-            Countable::Phase(p) => p.lock()?.count += diff,
+            Countable::Phase(p) => {
+                p.lock()?.count += diff;
+                operation::record(*countable, OperationKind::AddCount(diff))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -1333,11 +1879,14 @@ impl CountableStore<Recursive, Checked> {
                     }
                 }
             }
-            Countable::Phase(p) => p.lock()?.count += diff,
+            Countable::Phase(p) => {
+                p.lock()?.count += diff;
+                operation::record(*countable, OperationKind::AddCount(diff))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -1415,13 +1964,14 @@ impl CountableStore<Recursive, Checked> {
                 .try_for_each(|child| -> Result<(), AppError> { self.increase(child) })?,
             Countable::Phase(p) => {
                 if let Ok(mut phase) = p.lock() {
-                    phase.count += phase.step_size
+                    phase.count += phase.step_size;
+                    operation::record(*countable, OperationKind::AddCount(phase.step_size))
                 }
             }
             Countable::Chain(_) => todo!(),
         }
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -1505,11 +2055,14 @@ impl CountableStore<Recursive, Checked> {
                     }
                 }
             }
-            Countable::Phase(p) => p.lock()?.time += diff,
+            Countable::Phase(p) => {
+                p.lock()?.time += diff;
+                operation::record(*countable, OperationKind::AddTime(diff.num_milliseconds()))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -1555,11 +2108,14 @@ impl CountableStore<Recursive, Checked> {
                     }
                 }
             }
-            Countable::Phase(p) => p.lock()?.time += diff,
+            Countable::Phase(p) => {
+                p.lock()?.time += diff;
+                operation::record(*countable, OperationKind::AddTime(diff.num_milliseconds()))
+            }
             Countable::Chain(_) => todo!(),
         };
 
-        self.is_changed.replace(true);
+        self.mark_changed(countable);
 
         Ok(())
     }
@@ -1636,7 +2192,8 @@ impl CountableStore<Recursive, Checked> {
                     .collect::<Result<Vec<_>, AppError>>()?
                     .into_iter()
                     .sum(),
-                Countable::Phase(_) => self.hunttype(countable)?.rolls()(
+                Countable::Phase(_) => self.method_rolls(
+                    self.hunttype(countable)?,
                     self.count(countable)?,
                     self.has_charm_checked(countable)?,
                 ),
@@ -1684,7 +2241,7 @@ impl CountableStore<Recursive, Checked> {
                         .sum::<f64>();
                     sum / (self.count(countable)? as f64).max(1.0)
                 }
-                Countable::Phase(p) => p.lock()?.hunt_type.odds(),
+                Countable::Phase(p) => self.phase_odds(&*p.lock()?),
                 Countable::Chain(_) => todo!(),
             },
         )
@@ -1713,30 +2270,47 @@ impl CountableStore<Recursive, Checked> {
         [AppError]
     */
     pub fn progress(&self, countable: &CountableId) -> Result<f64, AppError> {
-        let prob = 1.0 / self.odds(countable)?;
-        let rolls = self.rolls(countable)?;
+        Ok(statistics::at_least(
+            self.needed(countable)?,
+            self.rolls(countable)?,
+            self.odds(countable)?,
+        ))
+    }
+
+    /**
+        `Recursive Countable Needed Checked`
+
+        The shinies all rolls of `countable` are measured against in its progress
+
+        # Arguments
+          * `countable`: &[CountableId]
+
+        # Returns
+          * `Ok(i32)`: The shinies needed, `0` once the target of a counter is reached
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::LockMutex]
+    */
+    pub fn needed(&self, countable: &CountableId) -> Result<i32, AppError> {
         Ok(
             match self
                 .store
                 .get(countable)
                 .ok_or(AppError::CountableNotFound)?
             {
-                Countable::Counter(_) => {
-                    let children_len = self.children(countable)?.len();
-                    let mut chance = 0.0;
-
-                    for k in 0..=((self.completed(countable)? as usize).min(children_len)) {
-                        let combs = if rolls >= 0 {
-                            n_choose_k(rolls as usize, k)
-                        } else {
-                            0.0
-                        };
-                        chance += combs * prob.powi(k as i32) * (1.0 - prob).powi(rolls - k as i32)
+                Countable::Counter(c) => {
+                    let target = c.lock()?.target;
+                    let completed = self.completed(countable)? as i32;
+                    match target {
+                        Some(target) if completed >= target => 0,
+                        Some(target) => target,
+                        // without a target the next shiny is hunted
+                        None => completed + 1,
                     }
-
-                    1.0 - chance
                 }
-                Countable::Phase(_) => 1.0 - (1.0 - prob).powi(rolls),
+                Countable::Phase(_) => 1,
                 Countable::Chain(_) => todo!(),
             },
         )
@@ -1780,6 +2354,59 @@ impl CountableStore<Recursive, Checked> {
             },
         )
     }
+
+    /**
+        `Recursive Countable Statistics Checked`
+
+        A counter is described by its newest phase, which is the one still being hunted,
+        and needs the shinies of its target that were not found yet.
+
+        # Arguments
+          * `countable`: &[CountableId]
+
+        # Returns
+          * `Ok(Statistics)`: Estimates for the `Countable` for the given `CountableId`
+          * `Err(AppError)`
+
+        # Errors
+          * [AppError::CountableNotFound]
+          * [AppError::RequiresChild]
+          * [AppError::LockMutex]
+
+        [Countable]\
+        [Statistics]\
+        [AppError]
+    */
+    pub fn statistics(&self, countable: &CountableId) -> Result<Statistics, AppError> {
+        match self
+            .store
+            .get(countable)
+            .ok_or(AppError::CountableNotFound)?
+        {
+            Countable::Counter(c) => {
+                let (newest, target) = {
+                    let counter = c.lock()?;
+                    (counter.children.last().copied(), counter.target)
+                };
+                let statistics = self.statistics(&newest.ok_or(AppError::RequiresChild)?)?;
+                Ok(match target {
+                    Some(target) => {
+                        statistics.with_successes(target - self.completed(countable)? as i32)
+                    }
+                    None => statistics,
+                })
+            }
+            Countable::Phase(_) => Ok(Statistics::new(
+                self.odds(countable)?,
+                self.count(countable)?,
+                self.method_rolls_fn(
+                    self.hunttype(countable)?,
+                    self.has_charm_checked(countable)?,
+                ),
+            )),
+            Countable::Chain(_) => todo!(),
+        }
+    }
 }
 
 impl CountableStore<Level, UnChecked> {
@@ -2471,6 +3098,24 @@ impl CountableStore<Recursive, UnChecked> {
         }
     }
 
+    /**
+        `Recursive Countable Needed UnChecked`
+
+        # Returns
+          * `i32`: The shinies the progress of `countable` is measured against
+          * `0`: The target is reached or `countable` was not available in `CountableStore`
+
+        # Panics
+          * lock on a `Mutex` fails
+    */
+    pub fn needed(&self, countable: &CountableId) -> i32 {
+        match self.checked_ref().needed(countable) {
+            Ok(n) => n,
+            Err(AppError::CountableNotFound) => 0,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
     /**
         `Recursive Completed Countable Checked`
 
@@ -2497,6 +3142,31 @@ impl CountableStore<Recursive, UnChecked> {
             Err(err) => panic!("{err}"),
         }
     }
+
+    /**
+        `Recursive Countable Statistics UnChecked`
+
+        # Arguments
+          * `countable`: &[CountableId]
+
+        # Returns
+          * `Some(Statistics)`: Estimates for the `Countable` for the given `CountableId`
+          * `None`: The `countable` was not available in `CountableStore` or has no phases
+
+        # Panics
+          * lock on a `Mutex` fails
+
+        [Countable]\
+        [Statistics]\
+        [AppError]
+    */
+    pub fn statistics(&self, countable: &CountableId) -> Option<Statistics> {
+        match self.checked_ref().statistics(countable) {
+            Ok(s) => Some(s),
+            Err(AppError::CountableNotFound) | Err(AppError::RequiresChild) => None,
+            Err(err) => panic!("{err}"),
+        }
+    }
 }
 
 #[typetag::serde]
@@ -2511,13 +3181,19 @@ impl Savable for leptos::RwSignal<CountableStore<Level, UnChecked>> {
     ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), AppError>> + 'a>> {
         use wasm_bindgen::JsValue;
 
-        self.update_untracked(|s| {
+        // only countables changed since the last write,
+        // the whole store is written once it is synced on startup
+        let changed: Vec<Countable> = self.with_untracked(|s| {
             s.is_changed.replace(false);
+            s.unwritten
+                .take()
+                .iter()
+                .filter_map(|id| s.get(id))
+                .collect()
         });
 
         Box::pin(async move {
-            obj.clear().await?;
-            for c in self.get_untracked().store.values() {
+            for c in changed {
                 let key = JsValue::from_str(&c.uuid().to_string());
                 let value = c.as_js();
 
@@ -2533,11 +3209,10 @@ impl Savable for leptos::RwSignal<CountableStore<Level, UnChecked>> {
     {
         self.update_untracked(|s| {
             s.is_changed.replace(false);
+            s.unsaved.borrow_mut().clear();
         });
 
-        Box::pin(api::update_countable_many(
-            self.get_untracked().store.into_values().collect(),
-        ))
+        Box::pin(outbox::flush())
     }
 
     fn message(&self) -> Option<leptos::View> {
@@ -2549,11 +3224,11 @@ impl Savable for leptos::RwSignal<CountableStore<Level, UnChecked>> {
     }
 
     fn has_change(&self) -> bool {
-        *self.get_untracked().is_changed.borrow()
+        *self.get_untracked().is_changed.borrow() || operation::has_pending()
     }
 }
 
-fn n_choose_k(n: usize, k: usize) -> f64 {
+pub(super) fn n_choose_k(n: usize, k: usize) -> f64 {
     match (n, k) {
         (n, k) if k > n => 0.0,
         (_, 0) => 1.0,
@@ -2561,3 +3236,60 @@ fn n_choose_k(n: usize, k: usize) -> f64 {
         (n, k) => n as f64 / k as f64 * n_choose_k(n - 1, k - 1),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::countable::nodes::Phase;
+
+    type Store = CountableStore<Recursive, Checked>;
+
+    fn phase(store: &Store, key: &CountableId) -> Phase {
+        match store.get(key) {
+            Some(Countable::Phase(p)) => p.lock().unwrap().clone(),
+            _ => panic!("not a phase"),
+        }
+    }
+
+    fn hunt() -> (Store, CountableId, CountableId) {
+        let mut store = Store::new(uuid::Uuid::new_v4(), HashMap::new());
+        let counter = store
+            .new_countable("Counter", CountableKind::Counter, None)
+            .unwrap();
+        let first = store.new_phase(&counter).unwrap();
+        if let Some(Countable::Phase(p)) = store.get(&first) {
+            let mut p = p.lock().unwrap();
+            p.hunt_type = Hunttype::SOS;
+            p.has_charm = true;
+            p.step_size = 3;
+            p.success = true;
+        }
+        (store, counter, first)
+    }
+
+    #[test]
+    fn advancing_continues_the_hunt() {
+        let (mut store, counter, first) = hunt();
+        let next = store.advance(&first, false).unwrap().unwrap();
+
+        let next = phase(&store, &next);
+        assert_eq!(next.name, "Phase 2");
+        assert_eq!(next.parent, counter);
+        assert_eq!(next.hunt_type, Hunttype::SOS);
+        assert!(next.has_charm);
+        assert_eq!(next.step_size, 3);
+        assert!(!next.success);
+    }
+
+    #[test]
+    fn new_phases_start_with_defaults() {
+        let (mut store, counter, _) = hunt();
+        let next = store.new_phase(&counter).unwrap();
+        let next = phase(&store, &next);
+
+        assert_eq!(next.name, "Phase 2");
+        assert_eq!(next.hunt_type, Hunttype::default());
+        assert!(!next.has_charm);
+        assert_eq!(next.step_size, 1);
+    }
+}
@@ -0,0 +1,171 @@
+//! Limits for the values of a countable.
+//!
+//! The edit page checks them before changing the store,
+//! the server checks them again before it applies operations or saves countables.
+
+use super::*;
+
+pub const MAX_NAME_LENGTH: usize = 64;
+pub const MAX_STEP_SIZE: i32 = 1000;
+pub const MAX_TARGET: i32 = 100;
+
+fn invalid(field: &str, reason: &str) -> AppError {
+    AppError::InvalidField(field.into(), reason.into())
+}
+
+pub fn name(name: &str) -> Result<(), AppError> {
+    if name.trim().is_empty() {
+        Err(invalid("name", "can not be empty"))
+    } else if name.chars().count() > MAX_NAME_LENGTH {
+        Err(invalid(
+            "name",
+            &format!("can be at most {MAX_NAME_LENGTH} characters"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn count(count: i32) -> Result<(), AppError> {
+    if count < 0 {
+        Err(invalid("count", "can not be negative"))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn step_size(step: i32) -> Result<(), AppError> {
+    if !(1..=MAX_STEP_SIZE).contains(&step) {
+        Err(invalid(
+            "step size",
+            &format!("has to be between 1 and {MAX_STEP_SIZE}"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn target(target: i32) -> Result<(), AppError> {
+    if !(1..=MAX_TARGET).contains(&target) {
+        Err(invalid(
+            "target",
+            &format!("has to be between 1 and {MAX_TARGET}"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub fn time(time: chrono::TimeDelta) -> Result<(), AppError> {
+    if time < chrono::TimeDelta::zero() {
+        Err(invalid("time", "can not be negative"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Games only offer some methods, without a game every method can be used
+pub fn method(game: Option<Game>, hunttype: Hunttype) -> Result<(), AppError> {
+    match game {
+        Some(game) if !game.supports(hunttype) => Err(invalid(
+            "method",
+            &format!("{} is not available in {}", hunttype.repr(), game.repr()),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/**
+    `Validate Countable`
+
+    Checks the values a whole countable is saved with.
+    Phases that were created before step sizes were checked may still have a step size of 0,
+    so only the name, count, time, target and method are checked.
+
+    # Errors
+      * [AppError::InvalidField]
+      * [AppError::LockMutex]
+*/
+pub fn countable(countable: &Countable) -> Result<(), AppError> {
+    match countable {
+        Countable::Counter(c) => {
+            let counter = c.lock()?;
+            name(&counter.name)?;
+            counter.target.map_or(Ok(()), target)
+        }
+        Countable::Phase(p) => {
+            let phase = p.lock()?;
+            name(&phase.name)?;
+            count(phase.count)?;
+            time(phase.time)?;
+            method(phase.game, phase.hunt_type)
+        }
+        Countable::Chain(_) => todo!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rejects(res: Result<(), AppError>, expected: &str) {
+        match res {
+            Err(AppError::InvalidField(field, _)) => assert_eq!(field, expected),
+            res => panic!("expected `{expected}` to be rejected, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn name_length() {
+        assert!(name("a").is_ok());
+        assert!(name(&"a".repeat(MAX_NAME_LENGTH)).is_ok());
+        // characters are counted, not bytes
+        assert!(name(&"é".repeat(MAX_NAME_LENGTH)).is_ok());
+        rejects(name(&"a".repeat(MAX_NAME_LENGTH + 1)), "name");
+        rejects(name(""), "name");
+        rejects(name("   "), "name");
+    }
+
+    #[test]
+    fn step_size_range() {
+        assert!(step_size(1).is_ok());
+        assert!(step_size(MAX_STEP_SIZE).is_ok());
+        rejects(step_size(0), "step size");
+        rejects(step_size(-1), "step size");
+        rejects(step_size(MAX_STEP_SIZE + 1), "step size");
+    }
+
+    #[test]
+    fn target_range() {
+        assert!(target(1).is_ok());
+        assert!(target(MAX_TARGET).is_ok());
+        rejects(target(0), "target");
+        rejects(target(-1), "target");
+        rejects(target(MAX_TARGET + 1), "target");
+    }
+
+    #[test]
+    fn count_and_time_are_not_negative() {
+        assert!(count(0).is_ok());
+        rejects(count(-1), "count");
+        assert!(time(chrono::TimeDelta::zero()).is_ok());
+        rejects(time(chrono::TimeDelta::milliseconds(-1)), "time");
+    }
+
+    #[test]
+    fn methods_of_the_game() {
+        assert!(method(None, Hunttype::SOS).is_ok());
+        assert!(method(Some(Game::SunMoon), Hunttype::SOS).is_ok());
+        assert!(method(Some(Game::GoldSilverCrystal), Hunttype::OldOdds).is_ok());
+        assert!(method(
+            Some(Game::GoldSilverCrystal),
+            Hunttype::Custom(uuid::Uuid::nil())
+        )
+        .is_ok());
+        rejects(
+            method(Some(Game::GoldSilverCrystal), Hunttype::SOS),
+            "method",
+        );
+        rejects(method(Some(Game::SunMoon), Hunttype::OldOdds), "method");
+    }
+}
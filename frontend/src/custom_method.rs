@@ -0,0 +1,181 @@
+use leptos::*;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+pub const MAX_ODDS: i32 = 1 << 20;
+pub const MAX_ROLLS: i32 = 1000;
+
+/// Hunting method defined by the user, phases refer to it with `Hunttype::Custom`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomMethod {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    /// base odds of a single roll
+    pub odds: i32,
+    /// rolls per encounter before the first entry of `table`
+    pub rolls: i32,
+    /// extra rolls per encounter with the shiny charm
+    pub charm_rolls: i32,
+    /// `(count, rolls)`, from `count` on every encounter has `rolls` rolls
+    pub table: Vec<(i32, i32)>,
+}
+
+impl CustomMethod {
+    /// Rolls of all `count` encounters, summed piece by piece like `Hunttype::SOS`
+    pub fn rolls(&self, count: i32, has_charm: bool) -> i32 {
+        let charm = if has_charm { self.charm_rolls } else { 0 };
+        let steps = std::iter::once((0, self.rolls))
+            .chain(self.table.iter().copied())
+            .collect::<Vec<_>>();
+
+        steps
+            .iter()
+            .enumerate()
+            .map(|(idx, (from, rolls))| {
+                let until = steps.get(idx + 1).map(|s| s.0).unwrap_or(i32::MAX);
+                let encounters = count.min(until) - from;
+                encounters.max(0) * (rolls + charm)
+            })
+            .sum()
+    }
+
+    pub fn odds(&self) -> f64 {
+        self.odds as f64
+    }
+
+    /**
+        `Validate Custom Method`
+
+        # Errors
+          * [AppError::InvalidField]
+    */
+    pub fn validate(&self) -> Result<(), AppError> {
+        fn invalid(field: &str, reason: String) -> AppError {
+            AppError::InvalidField(field.into(), reason)
+        }
+
+        countable::validate::name(&self.name)?;
+        if !(1..=MAX_ODDS).contains(&self.odds) {
+            Err(invalid(
+                "odds",
+                format!("have to be between 1 and {MAX_ODDS}"),
+            ))?
+        }
+        if !(1..=MAX_ROLLS).contains(&self.rolls) {
+            Err(invalid(
+                "rolls",
+                format!("have to be between 1 and {MAX_ROLLS}"),
+            ))?
+        }
+        if !(0..=MAX_ROLLS).contains(&self.charm_rolls) {
+            Err(invalid(
+                "charm bonus",
+                format!("has to be between 0 and {MAX_ROLLS}"),
+            ))?
+        }
+        let mut last = 0;
+        for (count, rolls) in self.table.iter().copied() {
+            if count <= last {
+                Err(invalid(
+                    "table",
+                    String::from("counts have to be positive and ascending"),
+                ))?
+            }
+            if !(1..=MAX_ROLLS).contains(&rolls) {
+                Err(invalid(
+                    "table",
+                    format!("rolls have to be between 1 and {MAX_ROLLS}"),
+                ))?
+            }
+            last = count;
+        }
+
+        Ok(())
+    }
+
+    /// Parses a table written as `count: rolls` pairs separated by commas or new lines
+    pub fn parse_table(value: &str) -> Result<Vec<(i32, i32)>, AppError> {
+        value
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .split_once(':')
+                    .and_then(|(count, rolls)| {
+                        Some((count.trim().parse().ok()?, rolls.trim().parse().ok()?))
+                    })
+                    .ok_or(AppError::InvalidField(
+                        "table".into(),
+                        format!("`{entry}` should look like `count: rolls`"),
+                    ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(feature = "ssr")]
+impl From<backend::custom_method::DbCustomMethod> for CustomMethod {
+    fn from(value: backend::custom_method::DbCustomMethod) -> Self {
+        Self {
+            uuid: value.uuid,
+            name: value.name,
+            odds: value.odds,
+            rolls: value.rolls,
+            charm_rolls: value.charm_rolls,
+            table: value
+                .table_counts
+                .into_iter()
+                .zip(value.table_rolls)
+                .collect(),
+        }
+    }
+}
+
+#[server(GetCustomMethods, "/api/session")]
+pub async fn get_custom_methods() -> Result<Vec<CustomMethod>, ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Read).await?;
+    let pool = api::extract_pool().await?;
+
+    let methods = backend::custom_method::all_by_user(&pool, session.user_uuid)
+        .await?
+        .into_iter()
+        .map(CustomMethod::from)
+        .collect();
+
+    Ok(methods)
+}
+
+#[server(CreateCustomMethod, "/api/session")]
+pub async fn create_custom_method(method: CustomMethod) -> Result<CustomMethod, ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Edit).await?;
+    let pool = api::extract_pool().await?;
+
+    if let Err(err) = method.validate() {
+        return Err(ServerFnError::ServerError(serde_json::to_string(&err)?));
+    }
+
+    let method = backend::custom_method::create(
+        &pool,
+        session.user_uuid,
+        method.name.trim(),
+        method.odds,
+        method.rolls,
+        method.charm_rolls,
+        &method.table,
+    )
+    .await?;
+
+    Ok(method.into())
+}
+
+#[server(DeleteCustomMethod, "/api/session")]
+pub async fn delete_custom_method(method: uuid::Uuid) -> Result<(), ServerFnError> {
+    let session = session::require_scope(backend::access_token::TokenScope::Edit).await?;
+    let pool = api::extract_pool().await?;
+
+    backend::custom_method::delete(&pool, session.user_uuid, method).await?;
+
+    Ok(())
+}
@@ -86,6 +86,55 @@ pub fn AccountOverlay(
 
     let show_about = create_rw_signal(false);
 
+    let msg = expect_context::<components::MessageJar>();
+    let user = expect_context::<RwSignal<UserSession>>();
+
+    // saved accounts are read from LocalStorage so only load them in the browser
+    let other_accounts = create_rw_signal(Vec::<UserSession>::new());
+    create_effect(move |_| {
+        if show_overlay() {
+            let current = user.get().user_uuid;
+            other_accounts.set(
+                session::saved_accounts()
+                    .into_iter()
+                    .filter(|a| a.user_uuid != current)
+                    .collect(),
+            )
+        }
+    });
+
+    let switch_account = move |account: UserSession| {
+        spawn_local(async move {
+            match api::switch_account(account.clone()).await {
+                // reload so nothing of the current account stays in memory
+                Ok(()) => {
+                    let _ = window().location().set_href("/");
+                }
+                Err(err) => {
+                    let _ = session::forget_account(account.user_uuid);
+                    msg.set_err(format!(
+                        "{} has to log in again\n{}",
+                        account.username,
+                        AppError::from(err)
+                    ));
+                }
+            }
+        })
+    };
+
+    let logout = move || {
+        spawn_local(async move {
+            let current = user.get_untracked().user_uuid;
+            // local data is only removed once the server has all changes
+            if outbox::flush().await.is_ok() && !operation::has_pending() {
+                let _ = indexed::IndexedSaveHandler::forget(current).await;
+            }
+            let _ = session::forget_account(current);
+            let _ = api::logout_user().await;
+            let _ = window().location().set_href("/login");
+        })
+    };
+
     view! {
         <Show when=show_overlay fallback=|| ()>
             <div
@@ -99,18 +148,55 @@ pub fn AccountOverlay(
                     fa_icon="fa-solid fa-gear"
                     text="preferences"
                 />
+                <AccountOverlayNavigate
+                    link="/statistics"
+                    fa_icon="fa-solid fa-chart-simple"
+                    text="statistics"
+                />
                 <AccountOverlayButton
                     on_click=move || show_about.set(true)
                     fa_icon="fa-solid fa-circle-info"
                     text="about"
                 />
                 <hr />
-                <AccountOverlayNavigate
-                    link="/login"
-                    fa_icon="fa-solid fa-right-from-bracket"
-                    text="Logout"
+                <For
+                    each=other_accounts
+                    key=|account| account.user_uuid
+                    children=move |account| {
+                        let username = account.username.clone();
+                        view! {
+                            <button on:click=move |_| switch_account(account.clone())>
+                                <div class=stylance::classes!(overlay::row, overlay::interactive)>
+                                    <i class="fa-solid fa-user"></i>
+                                    <span>{username}</span>
+                                </div>
+                            </button>
+                        }
+                    }
                 />
-            // TODO: remove session cookie
+                <Show
+                    when=move || !user.get().is_guest()
+                    fallback=|| {
+                        view! {
+                            <AccountOverlayNavigate
+                                link="/create-account"
+                                fa_icon="fa-solid fa-user-plus"
+                                text="create account"
+                            />
+                        }
+                    }
+                >
+                    <AccountOverlayNavigate
+                        link="/login"
+                        fa_icon="fa-solid fa-user-plus"
+                        text="add account"
+                    />
+                    <AccountOverlayButton
+                        on_click=logout
+                        fa_icon="fa-solid fa-right-from-bracket"
+                        text="Logout"
+                    />
+                </Show>
             </div>
         </Show>
 
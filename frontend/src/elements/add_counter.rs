@@ -12,12 +12,9 @@ pub fn NewCounterButton() -> impl IntoView {
         let name = format!("Counter {}", store.get_untracked().root_nodes().len() + 1);
         store.update(|s| {
             let c_id = s.new_countable(&name, CountableKind::Counter, None);
-            let p_id = s.new_countable("Phase 1", CountableKind::Phase, Some(c_id));
-            let _ = save_handler().save(
-                Box::new([s.get(&c_id).unwrap(), s.get(&p_id).unwrap()].to_vec()),
-                Box::new(move |_| resource.refetch()),
-            );
+            s.new_countable("Phase 1", CountableKind::Phase, Some(c_id));
         });
+        let _ = save_handler().save(Box::new(store), Box::new(move |_| resource.refetch()));
     };
 
     import_style!(style, "new-counter.module.scss");
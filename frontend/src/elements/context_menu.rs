@@ -38,6 +38,7 @@ pub fn CountableContextMenu(
         move |s| s.is_success(&key().into()),
         move |s, _| s.toggle_success(&key().into()),
     );
+    let advance = store_value(use_advance());
 
     let on_click_delete = move |ev: ev::MouseEvent| {
         ev.stop_propagation();
@@ -61,6 +62,16 @@ pub fn CountableContextMenu(
                     <span>Edit</span>
                 </div>
             </A>
+            <A href=move || format!("/action-urls/{}", key()) class="remove-underline">
+                <div class=stylance::classes!(overlay::row, overlay::interactive)>
+                    <span>Action urls</span>
+                </div>
+            </A>
+            <A href=move || format!("/simulate/{}", key()) class="remove-underline">
+                <div class=stylance::classes!(overlay::row, overlay::interactive)>
+                    <span>Simulate</span>
+                </div>
+            </A>
             // TODO: look further into this actionform not working
             // <ActionForm action=delete_action>
             <Show when=move || is_phase.get()>
@@ -70,6 +81,9 @@ pub fn CountableContextMenu(
                         ev.stop_propagation();
                         ev.prevent_default();
                         toggle_success(());
+                        if is_success.get_untracked() {
+                            advance.with_value(|advance| advance(key.get_untracked()))
+                        }
                     }
                 >
 
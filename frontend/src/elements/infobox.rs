@@ -2,13 +2,15 @@
 
 use super::*;
 use chrono::Duration;
-use components::Progressbar;
+use components::{MessageJar, Progressbar};
+use countable::milestone::{self, Marker, Milestones, Standing};
 use leptos::*;
+use std::collections::HashMap;
 use web_sys::MouseEvent;
 
 stylance::import_style!(style, "infobox.module.scss");
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub struct IsActive(RwSignal<bool>);
 impl IsActive {
     fn toggle(&self) {
@@ -45,6 +47,65 @@ impl FnOnce<()> for HasChange {
     }
 }
 
+/// Phase whose timer starts once it is shown, so a running timer carries over to the next phase
+#[derive(Debug, Clone, Copy)]
+struct ResumeTimer {
+    phase: RwSignal<Option<uuid::Uuid>>,
+    /// timers of the countables the infobox shows
+    shown: StoredValue<HashMap<uuid::Uuid, IsActive>>,
+}
+
+pub fn provide_resume_timer() {
+    provide_context(ResumeTimer {
+        phase: create_rw_signal(None),
+        shown: store_value(HashMap::new()),
+    })
+}
+
+/**
+    `Use Advance`
+
+    Continues the hunt of a phase in a new phase once it is marked as success,
+    when the user enabled it in the preferences.
+    Only call it on the device where the phase was marked,
+    other devices receive the new phase with the next sync.
+    A phase that is shown switches to the new phase and its running timer carries over.
+*/
+pub fn use_advance() -> impl Fn(uuid::Uuid) + Clone {
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let preferences = expect_context::<RwSignal<Preferences>>();
+    let save_handler = expect_context::<RwSignal<SaveHandlers>>();
+    let resume = expect_context::<ResumeTimer>();
+    let navigate = leptos_router::use_navigate();
+
+    move |phase| {
+        let (auto_advance, record_found) =
+            preferences.with_untracked(|p| (p.auto_advance, p.record_found));
+        if !auto_advance {
+            return;
+        }
+        let Some(next) = store
+            .try_update(|s| s.advance(&phase.into(), record_found))
+            .flatten()
+        else {
+            return;
+        };
+
+        let _ = save_handler
+            .get_untracked()
+            .save(Box::new(store), Box::new(|_| ()));
+
+        // a counter that is shown continues in its newest phase by itself
+        let Some(is_active) = resume.shown.with_value(|s| s.get(&phase).copied()) else {
+            return;
+        };
+        if is_active.0.get_untracked() {
+            resume.phase.set(Some(next.into()))
+        }
+        navigate(&format!("/{}", uuid::Uuid::from(next)), Default::default())
+    }
+}
+
 #[component]
 pub fn InfoBox(#[prop(into)] countable_list: Signal<Vec<uuid::Uuid>>) -> impl IntoView {
     let screen = expect_context::<Screen>();
@@ -83,8 +144,14 @@ pub fn InfoBoxPart(
     let has_change = HasChange::default();
     provide_context(has_change);
 
-    let last = create_read_slice(store, move |s| {
-        s.get(&s.recursive_ref().last_child(&key().into()))
+    let resume = expect_context::<ResumeTimer>();
+    if resume.phase.get_untracked() == Some(key.get_untracked()) {
+        resume.phase.set(None);
+        is_active.set(true);
+    }
+    let shown = key.get_untracked();
+    resume.shown.update_value(|s| {
+        s.insert(shown, is_active);
     });
 
     create_effect(move |_| {
@@ -92,16 +159,21 @@ pub fn InfoBoxPart(
         is_active.0.with(|a| {
             if !a && preferences.get_untracked().save_on_pause && has_change.0.get_untracked() {
                 has_change.set(false);
-                if let Some(last) = last() {
-                    let _ = save_handler
-                        .get_untracked()
-                        .save(Box::new(last), Box::new(move |_| has_change.set(true)));
-                }
+                let _ = save_handler
+                    .get_untracked()
+                    .save(Box::new(store), Box::new(move |_| has_change.set(true)));
             }
         });
     });
 
-    on_cleanup(move || is_active.set(false));
+    on_cleanup(move || {
+        is_active.set(false);
+        resume.shown.update_value(|s| {
+            if s.get(&shown) == Some(&is_active) {
+                s.remove(&shown);
+            }
+        })
+    });
 
     view! {
         <Show when=move || store().contains(&key().into())>
@@ -115,6 +187,9 @@ pub fn InfoBoxPart(
                     <Progress expand=|| true key show_title />
                     <LastStep expand=show_multiple key show_title />
                     <AverageStep expand=show_multiple key show_title />
+                    <Percentiles expand=show_multiple key show_title />
+                    <ExpectedRemaining expand=show_multiple key show_title />
+                    <Eta expand=show_multiple key show_title />
                 </Show>
             </div>
         </Show>
@@ -154,6 +229,8 @@ where
     T: Fn() -> bool + Copy + 'static,
 {
     let store = expect_context::<RwSignal<CountableStore>>();
+    let preferences = expect_context::<RwSignal<Preferences>>();
+    let message = expect_context::<MessageJar>();
     let is_active = expect_context::<IsActive>();
     let has_change = expect_context::<HasChange>();
     let name = create_read_slice(store, move |s| s.name(&key().into()));
@@ -165,6 +242,30 @@ where
         s.recursive_ref().add_count(&key().into(), count)
     });
 
+    // only counts of this device pass milestones, not the ones that are synced from others
+    let increase = move || {
+        let key = key.get_untracked().into();
+        let before = store.with_untracked(|s| standing(s, &key));
+        inc_count(());
+        let now = store.with_untracked(|s| standing(s, &key));
+        let (Some(before), Some(now)) = (before, now) else {
+            return;
+        };
+
+        let milestones = preferences.with_untracked(|p| p.milestones.clone());
+        let markers = store.with_untracked(|s| milestone_markers(s, &milestones, &key));
+        for marker in markers {
+            if !marker.milestone.passed(&before, &now) {
+                continue;
+            }
+            let name = store.with_untracked(|s| s.name(&key));
+            message.set_msg(format!("{name} passed {}", marker.label));
+            if milestones.notify {
+                milestone::notify(&name, &format!("Passed {}", marker.label))
+            }
+        }
+    };
+
     let key_listener = window_event_listener(ev::keydown, move |ev| {
         if !document()
             .active_element()
@@ -177,7 +278,7 @@ where
             match ev.code().as_str() {
                 "Equal" => {
                     is_active.set(true);
-                    inc_count(());
+                    increase();
                 }
                 "Minus" => {
                     add_count(-1);
@@ -193,7 +294,7 @@ where
     let on_count_click = move |_| {
         is_active.set(true);
         has_change.set(true);
-        inc_count(());
+        increase();
     };
 
     let on_minus_click = move |ev: MouseEvent| {
@@ -302,6 +403,34 @@ where
     }
 }
 
+/// Where `key` stands, `None` until it is loaded into the store
+fn standing(store: &CountableStore, key: &CountableId) -> Option<Standing> {
+    let recursive = store.recursive_ref();
+    store.contains(key).then(|| Standing {
+        count: recursive.count(key),
+        rolls: recursive.rolls(key),
+        progress: recursive.progress(key),
+    })
+}
+
+/// The enabled milestones of `key`, placed on its progressbar
+fn milestone_markers(
+    store: &CountableStore,
+    milestones: &Milestones,
+    key: &CountableId,
+) -> Vec<Marker> {
+    let recursive = store.recursive_ref();
+    let Some(statistics) = recursive.statistics(key) else {
+        return Vec::new();
+    };
+    // the statistics only cover the newest phase of a counter
+    let earlier_count = recursive.count(key) - statistics.count();
+    let earlier_rolls = recursive.rolls(key) - statistics.rolls(statistics.count());
+    milestones.markers(recursive.needed(key), recursive.odds(key), |count| {
+        earlier_rolls + statistics.rolls((count - earlier_count).max(0))
+    })
+}
+
 #[component]
 fn Progress<T, E>(
     #[prop(into)] key: MaybeSignal<uuid::Uuid>,
@@ -313,6 +442,7 @@ where
     T: Fn() -> bool + Copy + 'static,
 {
     let store = expect_context::<RwSignal<CountableStore>>();
+    let preferences = expect_context::<RwSignal<Preferences>>();
 
     let progress = create_read_slice(store, move |s| {
         s.recursive_ref().progress(&key.get_untracked().into())
@@ -321,6 +451,23 @@ where
         s.recursive_ref().rolls(&key.get_untracked().into())
     });
     let odds = create_read_slice(store, move |s| s.recursive_ref().odds(&key().into()));
+    let title = create_read_slice(store, move |s| {
+        let key = key().into();
+        // phases report the target of their counter but only count towards it there
+        match s
+            .target(&key)
+            .filter(|_| s.kind(&key) == CountableKind::Counter)
+        {
+            Some(target) => format!("Progress {}/{target}", s.recursive_ref().completed(&key)),
+            None => String::from("Progress"),
+        }
+    });
+
+    let milestones = create_read_slice(preferences, |p| p.milestones.clone());
+    let markers = create_memo(move |_| {
+        let milestones = milestones();
+        store.with(|s| milestone_markers(s, &milestones, &key().into()))
+    });
 
     let color = move || match progress() {
         num if num < 0.5 => "#50fa7b",
@@ -342,9 +489,16 @@ where
                 class=style::title
                 style:display=move || if show_title() { "block" } else { "none" }
             >
-                Progress
+                {title}
             </span>
-            <Progressbar progress attr:class=style::info color>
+            <Progressbar
+                progress
+                attr:class=style::info
+                color
+                markers=Signal::derive(move || {
+                    markers().into_iter().map(|m| (m.position, m.label)).collect::<Vec<_>>()
+                })
+            >
 
                 {move || format!("{:.03}%", progress() * 100.0)}
 
@@ -482,3 +636,160 @@ where
         </div>
     }
 }
+
+/// Chances shown by `Percentiles`
+const PERCENTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+#[component]
+fn Percentiles<E, T>(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    expand: E,
+    show_title: T,
+) -> impl IntoView
+where
+    E: Fn() -> bool + Copy + 'static,
+    T: Fn() -> bool + Copy + 'static,
+{
+    let store = expect_context::<RwSignal<CountableStore>>();
+
+    let encounters = create_memo(move |_| {
+        store.with(|s| {
+            s.recursive_ref()
+                .statistics(&key().into())
+                .map(|stats| PERCENTILES.map(|chance| stats.encounters_for(chance)))
+        })
+    });
+
+    let text = move || {
+        encounters()
+            .map(|list| {
+                PERCENTILES
+                    .iter()
+                    .zip(list)
+                    .map(|(chance, count)| {
+                        let count = count.map(|c| c.to_string()).unwrap_or(String::from("---"));
+                        format!("{}%: {count}", chance * 100.0)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" · ")
+            })
+            .unwrap_or(String::from("---"))
+    };
+
+    let class = move || {
+        stylance::classes! {
+            style::rowbox,
+            if expand() { Some(style::expand) } else { None }
+        }
+    };
+
+    view! {
+        <div class=class>
+            <span
+                class=style::title
+                style:display=move || if show_title() { "block" } else { "none" }
+            >
+                Encounters For
+            </span>
+            <span class=style::info>{text}</span>
+        </div>
+    }
+}
+
+#[component]
+fn ExpectedRemaining<E, T>(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    expand: E,
+    show_title: T,
+) -> impl IntoView
+where
+    E: Fn() -> bool + Copy + 'static,
+    T: Fn() -> bool + Copy + 'static,
+{
+    let store = expect_context::<RwSignal<CountableStore>>();
+
+    let remaining = create_memo(move |_| {
+        store.with(|s| {
+            s.recursive_ref()
+                .statistics(&key().into())
+                .and_then(|stats| stats.expected_remaining())
+        })
+    });
+
+    let class = move || {
+        stylance::classes! {
+            style::rowbox,
+            if expand() { Some(style::expand) } else { None }
+        }
+    };
+
+    view! {
+        <div class=class>
+            <span
+                class=style::title
+                style:display=move || if show_title() { "block" } else { "none" }
+            >
+                Expected Left
+            </span>
+            <span class=style::info>
+                {move || {
+                    remaining()
+                        .map(|r| format!("~{}", r.round() as i64))
+                        .unwrap_or(String::from("---"))
+                }}
+            </span>
+        </div>
+    }
+}
+
+/// Time until the next shiny is expected at the average step time so far
+#[component]
+fn Eta<E, T>(#[prop(into)] key: MaybeSignal<uuid::Uuid>, expand: E, show_title: T) -> impl IntoView
+where
+    E: Fn() -> bool + Copy + 'static,
+    T: Fn() -> bool + Copy + 'static,
+{
+    let store = expect_context::<RwSignal<CountableStore>>();
+
+    let eta = create_memo(move |_| {
+        store.with(|s| {
+            let key = key().into();
+            let count = s.recursive_ref().count(&key);
+            if count == 0 {
+                return None;
+            }
+            let step = Duration::milliseconds(
+                s.recursive_ref().time(&key).num_milliseconds() / count as i64,
+            );
+            s.recursive_ref()
+                .statistics(&key)
+                .and_then(|stats| stats.expected_time(step))
+        })
+    });
+
+    let text = move || match eta() {
+        Some(d) if d.num_days() > 0 => format!("{}d {}h", d.num_days(), d.num_hours() % 24),
+        Some(d) if d.num_hours() > 0 => format!("{}h {}m", d.num_hours(), d.num_minutes() % 60),
+        Some(d) => format!("{}m {}s", d.num_minutes(), d.num_seconds() % 60),
+        None => String::from("---"),
+    };
+
+    let class = move || {
+        stylance::classes! {
+            style::rowbox,
+            if expand() { Some(style::expand) } else { None }
+        }
+    };
+
+    view! {
+        <div class=class>
+            <span
+                class=style::title
+                style:display=move || if show_title() { "block" } else { "none" }
+            >
+                ETA
+            </span>
+            <span class=stylance::classes!(style::info, style::time)>{text}</span>
+        </div>
+    }
+}
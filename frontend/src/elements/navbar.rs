@@ -1,5 +1,5 @@
 use super::*;
-use components::{CloseOverlays, ShowSidebar, ToolTip};
+use components::{CloseOverlays, ShowSidebar, StatusBar, ToolTip, WifiOffIcon};
 use leptos::*;
 use leptos_router::A;
 
@@ -42,7 +42,7 @@ pub fn Navbar(
             </A>
 
             <div style:margin-left="auto" style:display="flex" style:align-items="center">
-                <StatusBar />
+                <SyncStatus />
                 <AccountIcon username=move || user.get().username accent_color />
             </div>
         </nav>
@@ -50,43 +50,89 @@ pub fn Navbar(
 }
 
 #[component]
-pub fn StatusBar() -> impl IntoView {
-    view! {
-        <Show when=|| false>
-            <div id="status-bar">
-                <Show when=|| false>
-                    <svg
-                        style:width="24px"
-                        style:height="24px"
-                        viewBox="0 0 48 48"
-                        xmlns="http://www.w3.org/2000/svg"
-                    >
-                        <title>wifi-disable</title>
-                        <g id="Layer_2" data-name="Layer 2">
-                            <g id="invisible_box" data-name="invisible box">
-                                <rect width="48" height="48" fill="none"></rect>
-                            </g>
-                            <g id="Q3_icons" data-name="Q3 icons">
-                                <g>
-                                    <path
-                                        fill="currentColor"
-                                        d="M40.7,26.5a2,2,0,0,0-.2-2.6A23,23,0,0,0,24.3,17L29,21.7a18.6,18.6,0,0,1,8.7,5A1.9,1.9,0,0,0,40.7,26.5Z"
-                                    ></path>
-                                    <path
-                                        fill="currentColor"
-                                        d="M45.4,17.4A31.2,31.2,0,0,0,17.1,9.8l3.4,3.4L24,13a27.4,27.4,0,0,1,18.6,7.3,2,2,0,0,0,3-.2h0A2.1,2.1,0,0,0,45.4,17.4Z"
-                                    ></path>
-                                    <circle fill="currentColor" cx="24" cy="38" r="5"></circle>
-                                    <path
-                                        fill="currentColor"
-                                        d="M5.4,3.6a1.9,1.9,0,0,0-2.8,0,1.9,1.9,0,0,0,0,2.8L9,12.8H8.7L6.8,14.1l-.3.3L4.7,15.7l-.3.2L2.6,17.4a2.1,2.1,0,0,0-.2,2.7h0a2,2,0,0,0,3,.2l1.7-1.4.4-.3,1.8-1.3.3-.2,2-1.1h0l.4-.2,3,3-.5.2-.6.3-.8.4-.6.3-.8.5-.5.4-.8.5-.5.4-.9.7-.4.3a11.4,11.4,0,0,1-1.1,1.1,2,2,0,0,0-.6,1.4,2.8,2.8,0,0,0,.4,1.2,1.9,1.9,0,0,0,3,.2l1.2-1,.3-.3.9-.7.4-.3,1.1-.7h.2l1.4-.8h.4l1.1-.5.5-.2h.3l3.3,3.3a16,16,0,0,0-9.1,5.3,1.9,1.9,0,0,0-.4,1.2,2,2,0,0,0,.4,1.3,2,2,0,0,0,3.1,0A11.5,11.5,0,0,1,24,29h1.2L38.6,42.4a1.9,1.9,0,0,0,2.8,0,1.9,1.9,0,0,0,0-2.8Z"
-                                    ></path>
-                                </g>
-                            </g>
-                        </g>
-                    </svg>
+pub fn SyncStatus() -> impl IntoView {
+    let outbox = expect_context::<outbox::Outbox>();
+    let user = expect_context::<RwSignal<UserSession>>();
+
+    let is_guest = move || user.get().is_guest();
+    let pending = move || outbox.pending.get();
+    let online = move || outbox.online.get();
+    let title = Signal::derive(move || {
+        if is_guest() {
+            "Changes are only kept on this device"
+        } else if !online() {
+            "Offline, changes are kept on this device until the server can be reached"
+        } else if pending() > 0 {
+            "Sending changes to the server"
+        } else {
+            "All changes are saved"
+        }
+        .to_string()
+    });
+
+    let connection = move || if online() { "online" } else { "offline" };
+    let last_saved = move || {
+        outbox
+            .last_saved
+            .get()
+            .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| String::from("not yet"))
+    };
+    let indexed = move || match outbox.indexed.get() {
+        Some(true) => "available",
+        Some(false) => "unavailable",
+        None => "opening",
+    };
+
+    let icon = move || {
+        if is_guest() {
+            "fa-solid fa-hard-drive"
+        } else if pending() > 0 {
+            "fa-solid fa-cloud-arrow-up"
+        } else {
+            "fa-solid fa-cloud"
+        }
+    };
+
+    let details = move || {
+        view! {
+            <table>
+                <tr>
+                    <td>"Connection"</td>
+                    <td>{connection}</td>
+                </tr>
+                <tr>
+                    <td>"Pending changes"</td>
+                    <td>{pending}</td>
+                </tr>
+                <Show when=move || !is_guest()>
+                    <tr>
+                        <td>"Last saved"</td>
+                        <td>{last_saved}</td>
+                    </tr>
                 </Show>
-            </div>
-        </Show>
+                <tr>
+                    <td>"Local storage"</td>
+                    <td>{indexed}</td>
+                </tr>
+            </table>
+            <Show when=move || !is_guest()>
+                <button on:click=move |_| outbox::retry_now()>
+                    <i class="fa-solid fa-rotate"></i>
+                    <span>"Sync now"</span>
+                </button>
+            </Show>
+        }
+    };
+
+    view! {
+        <StatusBar title details>
+            <Show when=move || { pending() > 0 }>
+                <span style:margin-right="8px">{move || format!("{} pending", pending())}</span>
+            </Show>
+            <Show when=online fallback=|| view! { <WifiOffIcon /> }>
+                <i class=icon></i>
+            </Show>
+        </StatusBar>
     }
 }
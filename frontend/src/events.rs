@@ -0,0 +1,158 @@
+//! Pushes changed counters to every open session of their owner
+//!
+//! Every change made through `backend::counter` or `backend::phase` raises a notification
+//! for the affected counter, see [`backend::changes`].
+//! A single task listens for these, loads the counter together with its phases
+//! and forwards it to all `/api/session/events` streams of the owner as server sent events.
+//! Removed countables can not be loaded, their owner is asked to resync instead.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use backend::{
+    access_token::TokenScope,
+    changes::{Change, ChangeListener},
+};
+use tokio::sync::broadcast;
+
+use super::{rest, Countable, CountableId, CountableStore};
+
+const CHANNEL_CAPACITY: usize = 256;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const KEEP_ALIVE: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Clone)]
+struct StoreEvent {
+    owner: uuid::Uuid,
+    /// serialized [`CountableStore`] holding the counter and all its phases,
+    /// `None` when countables were removed and the owner has to pull the changes
+    data: Option<Arc<str>>,
+}
+
+pub struct StoreEvents(broadcast::Sender<StoreEvent>);
+
+impl StoreEvents {
+    /// Starts listening for changes, the returned data has to be added to the app
+    pub fn spawn(pool: backend::PgPool) -> web::Data<Self> {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        tokio::spawn(forward_changes(pool, sender.clone()));
+
+        web::Data::new(Self(sender))
+    }
+}
+
+async fn forward_changes(pool: backend::PgPool, sender: broadcast::Sender<StoreEvent>) {
+    loop {
+        let mut listener = match ChangeListener::connect(&pool).await {
+            Ok(listener) => listener,
+            Err(err) => {
+                eprintln!("Could not listen for countable changes: {err}");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        while let Ok(change) = listener.recv().await {
+            // without any open stream there is no need to load the counter
+            if sender.receiver_count() == 0 {
+                continue;
+            }
+
+            let key = match change {
+                Change::Counter(key) => key,
+                Change::Removed(owner) => {
+                    let _ = sender.send(StoreEvent { owner, data: None });
+                    continue;
+                }
+            };
+
+            match load_counter(&pool, key).await {
+                Ok(Some(event)) => {
+                    let _ = sender.send(event);
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("Could not load changed counter {key}: {err}"),
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn load_counter(
+    pool: &backend::PgPool,
+    key: uuid::Uuid,
+) -> Result<Option<StoreEvent>, backend::BackendError> {
+    let mut tx = pool.begin().await?;
+
+    let counter = match backend::counter::get(&mut tx, key).await {
+        Ok(counter) => counter,
+        Err(backend::BackendError::CounterNotFound) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    let phases = backend::counter::get_children(&mut tx, key).await?;
+    tx.commit().await?;
+
+    let owner = counter.owner_uuid;
+    let parent: Countable = counter.into();
+    let mut store: HashMap<CountableId, Countable> = HashMap::new();
+    for phase in phases {
+        parent.add_child(phase.uuid.into());
+        store.insert(phase.uuid.into(), phase.into());
+    }
+    store.insert(key.into(), parent);
+
+    let data = serde_json::to_string(&CountableStore::new(owner, store))
+        .map_err(|err| backend::BackendError::Internal(err.to_string()))?;
+
+    Ok(Some(StoreEvent {
+        owner,
+        data: Some(data.into()),
+    }))
+}
+
+/// Server sent event stream of all counters of the user that change,
+/// sends `store` events with a partial [`CountableStore`] as data
+/// and `resync` events when changes were missed or countables were removed
+pub async fn stream(
+    req: HttpRequest,
+    events: web::Data<StoreEvents>,
+) -> Result<HttpResponse, rest::ApiError> {
+    let session = rest::authorize(&req, TokenScope::Read)?;
+    let receiver = events.0.subscribe();
+    let keep_alive = tokio::time::interval(KEEP_ALIVE);
+
+    let stream = futures::stream::unfold(
+        (receiver, keep_alive),
+        move |(mut receiver, mut keep_alive)| async move {
+            let chunk = loop {
+                tokio::select! {
+                    event = receiver.recv() => match event {
+                        Ok(event) if event.owner == session.user_uuid => match event.data {
+                            Some(data) => break format!("event: store\ndata: {data}\n\n"),
+                            None => break String::from("event: resync\ndata:\n\n"),
+                        },
+                        Ok(_) => continue,
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            break String::from("event: resync\ndata:\n\n");
+                        }
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    },
+                    _ = keep_alive.tick() => break String::from(": keep-alive\n\n"),
+                }
+            };
+
+            Some((
+                Ok::<_, actix_web::Error>(web::Bytes::from(chunk)),
+                (receiver, keep_alive),
+            ))
+        },
+    );
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(header::CacheControl(vec![header::CacheDirective::NoCache]))
+        // compression would buffer the events
+        .insert_header(header::ContentEncoding::Identity)
+        .streaming(stream))
+}
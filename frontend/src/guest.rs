@@ -0,0 +1,242 @@
+//! Guest mode, lets people use the counter without an account.
+//!
+//! Guests get a random owner id that is kept in the `guest` cookie so pages can be rendered
+//! for them. Their countables are only stored in the local database of that owner,
+//! nothing is sent to the server until an account is created, see [`upgrade`].
+
+use super::*;
+
+pub const GUEST_COOKIE: &str = "guest";
+const UPGRADE_KEY: &str = "guest_upgrade";
+
+/// Starts guest mode, an owner id of an earlier guest session on this browser is reused
+pub fn start() -> Result<(), AppError> {
+    let owner = owner().unwrap_or_else(uuid::Uuid::new_v4);
+    set_cookie(&owner.to_string(), 365 * 24 * 60 * 60);
+    leptos::window().location().set_href("/")?;
+
+    Ok(())
+}
+
+/// Owner id of the guest session on this browser
+pub fn owner() -> Option<uuid::Uuid> {
+    let document = leptos::document();
+    let document: &web_sys::HtmlDocument = document.unchecked_ref();
+    let cookies = document.cookie().ok()?;
+
+    cookie::Cookie::split_parse_encoded(cookies)
+        .filter_map(Result::ok)
+        .find(|c| c.name() == GUEST_COOKIE)
+        .and_then(|c| uuid::Uuid::parse_str(c.value()).ok())
+}
+
+/// Moves the guest data to the next account that is opened, called when an account is created
+pub fn mark_for_upgrade() {
+    use gloo_storage::{LocalStorage, Storage};
+
+    if owner().is_some() {
+        let _ = LocalStorage::set(UPGRADE_KEY, true);
+    }
+}
+
+/// Keeps the guest data local, used while the guest session is still in use
+pub fn cancel_upgrade() {
+    use gloo_storage::{LocalStorage, Storage};
+
+    LocalStorage::delete(UPGRADE_KEY)
+}
+
+/**
+    `Upgrade`
+
+    Moves the countables of the guest session to `user` once an account was created.
+    The countables are re-owned, uploaded and removed from the local database of the guest,
+    guest mode ends afterwards.
+
+    # Returns
+    the uploaded countables, empty when there was nothing to move
+
+    # Errors
+      * [AppError::Indexed] when the guest data can not be read
+      * [AppError::ServerError] when the upload failed, the guest data is kept
+*/
+pub async fn upgrade(user: uuid::Uuid) -> Result<Vec<Countable>, AppError> {
+    use gloo_storage::{LocalStorage, Storage};
+
+    let Some(guest) = owner() else {
+        return Ok(Vec::new());
+    };
+    if !LocalStorage::get(UPGRADE_KEY).unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let countables = indexed::IndexedSaveHandler::load(guest).await?;
+    let countables = move_countables(countables, user, api::update_countable_many, || {
+        indexed::IndexedSaveHandler::forget(guest)
+    })
+    .await?;
+
+    set_cookie("", 0);
+    cancel_upgrade();
+
+    Ok(countables)
+}
+
+/**
+    `Move Countables`
+
+    Re-owns `countables` to `user`, uploads them with `upload` and only calls `forget`
+    once the upload succeeded. The server stores the whole batch in one transaction,
+    so a failed upload leaves nothing behind and the guest data can be moved again later.
+
+    # Errors
+      * [AppError::Unsupported] for chains
+      * the error of `upload`, `forget` is not called then
+      * the error of `forget`
+*/
+async fn move_countables<U, UF, F, FF>(
+    mut countables: Vec<Countable>,
+    user: uuid::Uuid,
+    upload: U,
+    forget: F,
+) -> Result<Vec<Countable>, AppError>
+where
+    U: FnOnce(Vec<Countable>) -> UF,
+    UF: std::future::Future<Output = Result<Vec<(uuid::Uuid, i32)>, leptos::ServerFnError>>,
+    F: FnOnce() -> FF,
+    FF: std::future::Future<Output = Result<(), AppError>>,
+{
+    for countable in countables.iter() {
+        match countable {
+            Countable::Counter(c) => c.lock()?.owner_uuid = user,
+            Countable::Phase(p) => p.lock()?.owner_uuid = user,
+            Countable::Chain(_) => Err(AppError::Unsupported("Chain".into()))?,
+        }
+    }
+    // phases can only be stored once their counter exists
+    countables.sort_by_key(|c| !matches!(c, Countable::Counter(_)));
+
+    if !countables.is_empty() {
+        let revisions = upload(countables.clone()).await?;
+        for (key, revision) in revisions {
+            if let Some(c) = countables.iter().find(|c| c.uuid() == key) {
+                c.set_revision_checked(revision)?
+            }
+        }
+    }
+
+    forget().await?;
+
+    Ok(countables)
+}
+
+fn set_cookie(value: &str, max_age: i64) {
+    let document = leptos::document();
+    let document: &web_sys::HtmlDocument = document.unchecked_ref();
+
+    let guest_cookie = cookie::Cookie::build((GUEST_COOKIE, value))
+        .path("/")
+        .same_site(cookie::SameSite::Strict)
+        .build();
+
+    let mut cookie_str = cookie::Cookie::encoded(&guest_cookie).to_string();
+    cookie_str.push_str(&format!("; Max-Age={max_age}"));
+
+    document.set_cookie(&cookie_str).ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn guest_data(guest: uuid::Uuid) -> Vec<Countable> {
+        let counter = Countable::new("Counter", CountableKind::Counter, guest, None);
+        let phase = Countable::new(
+            "Phase 1",
+            CountableKind::Phase,
+            guest,
+            Some(counter.uuid().into()),
+        );
+        counter.add_child(phase.uuid().into());
+        vec![phase, counter]
+    }
+
+    fn owner_of(countable: &Countable) -> uuid::Uuid {
+        match countable {
+            Countable::Counter(c) => c.lock().unwrap().owner_uuid,
+            Countable::Phase(p) => p.lock().unwrap().owner_uuid,
+            Countable::Chain(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn failed_uploads_keep_the_guest_data() {
+        let forgotten = Cell::new(false);
+        let res = block_on(move_countables(
+            guest_data(uuid::Uuid::new_v4()),
+            uuid::Uuid::new_v4(),
+            |_| async { Err(leptos::ServerFnError::Request("offline".into())) },
+            || async {
+                forgotten.set(true);
+                Ok(())
+            },
+        ));
+
+        assert_eq!(res.map(|_| ()), Err(AppError::ConnectionError));
+        assert!(!forgotten.get());
+    }
+
+    #[test]
+    fn uploads_move_the_guest_data() {
+        let user = uuid::Uuid::new_v4();
+        let forgotten = Cell::new(0);
+        let uploaded = Cell::new(Vec::new());
+
+        let moved = block_on(move_countables(
+            guest_data(uuid::Uuid::new_v4()),
+            user,
+            |list: Vec<Countable>| {
+                uploaded.set(list.iter().map(Countable::uuid).collect());
+                async move { Ok(list.iter().map(|c| (c.uuid(), 3)).collect()) }
+            },
+            || async {
+                forgotten.set(forgotten.get() + 1);
+                Ok(())
+            },
+        ))
+        .unwrap();
+
+        assert!(matches!(moved[0], Countable::Counter(_)));
+        assert_eq!(
+            uploaded.take(),
+            moved.iter().map(Countable::uuid).collect::<Vec<_>>()
+        );
+        for countable in moved.iter() {
+            assert_eq!(owner_of(countable), user);
+            assert_eq!(countable.revision_checked(), Ok(3));
+        }
+        assert_eq!(forgotten.get(), 1);
+    }
+
+    #[test]
+    fn empty_guest_data_is_not_uploaded() {
+        let forgotten = Cell::new(false);
+        let moved = block_on(move_countables(
+            Vec::new(),
+            uuid::Uuid::new_v4(),
+            |_| async { panic!("nothing to upload") },
+            || async {
+                forgotten.set(true);
+                Ok(())
+            },
+        ))
+        .unwrap();
+
+        assert!(moved.is_empty());
+        assert!(forgotten.get());
+    }
+}
@@ -8,6 +8,7 @@ use leptos::leptos_dom;
 use wasm_bindgen::{prelude::Closure, JsCast};
 
 pub mod app;
+mod guest;
 mod session;
 pub(crate) use session::SessionFormInput;
 pub use session::UserSession;
@@ -18,7 +19,10 @@ pub(crate) use preferences::{PrefResource, Preferences};
 mod tests;
 pub(crate) use tests::*;
 
+pub(crate) mod access_token;
+pub(crate) mod action_url;
 pub(crate) mod api;
+pub(crate) mod custom_method;
 pub(crate) mod elements;
 mod pages;
 pub(crate) mod saving;
@@ -30,8 +34,12 @@ pub(crate) use countable::*;
 
 use cfg_if::cfg_if;
 
+#[cfg(feature = "ssr")]
+pub mod events;
 #[cfg(feature = "ssr")]
 pub mod middleware;
+#[cfg(feature = "ssr")]
+pub mod rest;
 
 cfg_if! {
     if #[cfg(docsrs)] {
@@ -133,6 +141,12 @@ pub enum AppError {
     Environment(String),
     #[error("Countable Requires at least 1 leaf node")]
     RequiresChild,
+    #[error("Countable was changed by another device")]
+    Conflict(Box<Countable>),
+    #[error("Invalid {0}: {1}")]
+    InvalidField(String, String),
+    #[error("{0} countables are not supported yet")]
+    Unsupported(String),
 }
 
 impl From<gloo_storage::errors::StorageError> for AppError {
@@ -145,7 +159,9 @@ impl From<leptos::ServerFnError> for AppError {
     fn from(value: leptos::ServerFnError) -> Self {
         match value {
             leptos::ServerFnError::Request(_) => AppError::ConnectionError,
-            leptos::ServerFnError::ServerError(str) => AppError::ServerError(str),
+            leptos::ServerFnError::ServerError(str) => {
+                serde_json::from_str(&str).unwrap_or(AppError::ServerError(str))
+            }
             _ => serde_json::from_str(&value.to_string())
                 .unwrap_or(AppError::ServerError(value.to_string())),
         }
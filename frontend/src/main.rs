@@ -8,7 +8,7 @@ use std::thread;
 cfg_if::cfg_if! {
     if #[cfg(feature = "ssr")] {
         use actix_files::Files;
-        use tallyweb_frontend::{app, AppError, middleware as mw};
+        use tallyweb_frontend::{app, AppError, events, middleware as mw, rest};
         use actix_web::*;
         use leptos_actix::{generate_route_list, LeptosRoutes};
         use actix_web::http::StatusCode;
@@ -24,6 +24,7 @@ cfg_if::cfg_if! {
 
             let pool = backend::create_pool().await.map_err(|err| AppError::DbConnection(err.to_string()))?;
             let _ = sqlx::migrate!("../migrations").run(&pool).await.map_err(|err| println!("{err}"));
+            let store_events = events::StoreEvents::spawn(pool.clone());
 
             HttpServer::new(move || {
                 let leptos_options = &conf.leptos_options;
@@ -31,11 +32,13 @@ cfg_if::cfg_if! {
 
                 App::new()
                     .wrap(actix_web::middleware::Condition::new(conf.leptos_options.env == leptos_config::Env::PROD, middleware::Compress::default()))
+                    .configure(rest::config)
                     .service(
                         web::scope("/api")
                             .service(
                                 web::scope("/session")
                                     .wrap(mw::CheckSession)
+                                    .route("/events", web::get().to(events::stream))
                                     .route("/{tail:.*}", leptos_actix::handle_server_fns())
                             )
                             .route("/{tail:.*}", leptos_actix::handle_server_fns())
@@ -53,6 +56,7 @@ cfg_if::cfg_if! {
                     )
                     .app_data(web::Data::new(leptos_options.to_owned()))
                     .app_data(web::Data::new(pool.clone()))
+                    .app_data(store_events.clone())
             })
 
             .bind(&addr)
@@ -1,13 +1,16 @@
-use std::future::{ready, Ready};
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
 
 use actix_web::{
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
-    http::header,
-    Error, HttpResponse,
+    http::{header, StatusCode},
+    Error, HttpMessage, HttpResponse,
 };
 use futures_util::future::LocalBoxFuture;
 
-use super::UserSession;
+use super::{rest::ApiError, UserSession};
 
 // There are two steps in middleware processing.
 // 1. Middleware initialization, middleware factory gets called with
@@ -20,7 +23,7 @@ pub struct CheckSession;
 // `B` - type of response's body
 impl<S, B> Transform<S, ServiceRequest> for CheckSession
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -31,17 +34,19 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(CheckSessionMW { service }))
+        ready(Ok(CheckSessionMW {
+            service: Rc::new(service),
+        }))
     }
 }
 
 pub struct CheckSessionMW<S> {
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S, B> CheckSessionMW<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -49,7 +54,7 @@ where
 
 impl<S, B> Service<ServiceRequest> for CheckSessionMW<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -60,25 +65,54 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
+        let get_pool = |req: &ServiceRequest| {
+            req.app_data::<actix_web::web::Data<backend::PgPool>>()
+                .map(|pool| pool.clone().into_inner())
+                .ok_or(ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal",
+                    "Missing DB pool",
+                ))
+        };
+
+        if let Some(token) = bearer_token(&req) {
+            let pool = match get_pool(&req) {
+                Ok(pool) => pool,
+                Err(err) => return Box::pin(async { Err(err.into()) }),
+            };
+
+            let (req, payload) = req.into_parts();
+            let service = self.service.clone();
+
+            return Box::pin(async move {
+                let (access_token, username) = backend::access_token::authenticate(&pool, &token)
+                    .await
+                    .map_err(ApiError::unauthorized)?;
+
+                req.extensions_mut().insert(UserSession {
+                    user_uuid: access_token.user_uuid,
+                    username,
+                    token: access_token.uuid,
+                });
+                req.extensions_mut().insert(access_token);
+
+                service.call(ServiceRequest::from_parts(req, payload)).await
+            });
+        }
+
         let get_session = |req: &ServiceRequest| {
             let cookie = req
                 .cookie("session")
-                .ok_or(actix_web::error::ErrorUnauthorized(
-                    "Missing `session` cookie",
-                ))?
+                .ok_or(ApiError::unauthorized("Missing `session` cookie"))?
                 .value()
                 .to_string();
 
-            let pool = req
-                .app_data::<actix_web::web::Data<backend::PgPool>>()
-                .ok_or(actix_web::error::ErrorInternalServerError(
-                    "Missing DB pool",
-                ))?;
+            let pool = get_pool(req)?;
 
-            let session: UserSession = serde_json::from_str(&cookie)
-                .map_err(|err| actix_web::error::ErrorBadRequest(err))?;
+            let session: UserSession =
+                serde_json::from_str(&cookie).map_err(ApiError::bad_request)?;
 
-            Ok((session.clone(), pool.clone().into_inner().clone()))
+            Ok((session.clone(), pool))
         };
 
         let (session, pool) = match get_session(&req) {
@@ -86,6 +120,7 @@ where
             Err(err) => return Box::pin(async { Err(err) }),
         };
 
+        req.extensions_mut().insert(session.clone());
         let fut = self.service.call(req);
 
         Box::pin(async move {
@@ -99,8 +134,93 @@ where
                         .message_body(resp.into_body())?;
                     Ok(ServiceResponse::new(req, resp))
                 }
-                Err(err) => Err(actix_web::error::ErrorUnauthorized(err)),
+                Err(err) => Err(ApiError::unauthorized(err).into()),
             }
         })
     }
 }
+
+/// Personal access token from an `Authorization: Bearer <token>` header
+fn bearer_token(req: &ServiceRequest) -> Option<String> {
+    req.headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(|token| token.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{dev::Service, test, web, App};
+    use backend::access_token::{self, TokenScope};
+    use sqlx::PgPool;
+
+    use super::*;
+
+    async fn user(pool: &PgPool) -> uuid::Uuid {
+        sqlx::query_scalar("INSERT INTO users (username, password) VALUES ($1, '') RETURNING uuid")
+            .bind(uuid::Uuid::new_v4().to_string())
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// Status of a request to a route behind [`CheckSession`] using `token`
+    async fn status(pool: &PgPool, token: &str) -> StatusCode {
+        let app = test::init_service(
+            App::new().app_data(web::Data::new(pool.clone())).service(
+                web::scope("")
+                    .wrap(CheckSession)
+                    .route("/", web::get().to(HttpResponse::Ok)),
+            ),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/")
+            .insert_header((header::AUTHORIZATION, format!("Bearer {token}")))
+            .to_request();
+
+        match app.call(req).await {
+            Ok(res) => res.status(),
+            Err(err) => err.as_response_error().status_code(),
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn valid_tokens_pass(pool: PgPool) {
+        let user = user(&pool).await;
+        let (_, token) = access_token::create(&pool, user, "Token", &[TokenScope::Read], None)
+            .await
+            .unwrap();
+
+        assert_eq!(status(&pool, &token).await, StatusCode::OK);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn revoked_tokens_fail(pool: PgPool) {
+        let user = user(&pool).await;
+        let (db_token, token) =
+            access_token::create(&pool, user, "Token", &[TokenScope::Admin], None)
+                .await
+                .unwrap();
+        access_token::revoke(&pool, user, db_token.uuid)
+            .await
+            .unwrap();
+
+        assert_eq!(status(&pool, &token).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&pool, "tw_unknown").await, StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn expired_tokens_fail(pool: PgPool) {
+        let user = user(&pool).await;
+        let expired = chrono::Utc::now().naive_utc() - chrono::Duration::minutes(1);
+        let (_, token) =
+            access_token::create(&pool, user, "Token", &[TokenScope::Admin], Some(expired))
+                .await
+                .unwrap();
+
+        assert_eq!(status(&pool, &token).await, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,281 @@
+#![allow(unused_braces)]
+use components::{MessageJar, Slider};
+use leptos::*;
+use leptos_router::A;
+use web_sys::SubmitEvent;
+
+stylance::import_style!(
+    #[allow(dead_code)]
+    style,
+    "../../style/edit.module.scss"
+);
+
+use super::*;
+use access_token::{AccessToken, TokenScope};
+
+#[component]
+pub fn AccessTokensWindow() -> impl IntoView {
+    let message = expect_context::<MessageJar>();
+    let screen = expect_context::<Screen>();
+
+    let create_action = create_server_action::<access_token::CreateAccessToken>();
+    let revoke_action = create_server_action::<access_token::RevokeAccessToken>();
+
+    let tokens = create_resource(
+        move || (create_action.version().get(), revoke_action.version().get()),
+        |_| access_token::get_access_tokens(),
+    );
+
+    let name = create_rw_signal(String::new());
+    let scopes = create_rw_signal(vec![TokenScope::Read, TokenScope::Count]);
+    let expire_days = create_rw_signal(None::<i64>);
+    let created_token = create_rw_signal(None::<String>);
+
+    create_effect(move |_| match create_action.value().get() {
+        Some(Ok((_, plain))) => {
+            name.set(String::new());
+            created_token.set(Some(plain));
+        }
+        Some(Err(err)) => message.set_err(AppError::from(err)),
+        None => {}
+    });
+
+    create_effect(move |_| match revoke_action.value().get() {
+        Some(Ok(_)) => message.set_msg("Access token revoked"),
+        Some(Err(err)) => message.set_err(AppError::from(err)),
+        None => {}
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+
+        if name.get_untracked().trim().is_empty() {
+            message.set_err("Access token requires a name");
+            return;
+        }
+        if scopes.get_untracked().is_empty() {
+            message.set_err("Select at least one scope");
+            return;
+        }
+
+        create_action.dispatch(access_token::CreateAccessToken {
+            name: name.get_untracked(),
+            scopes: scopes.get_untracked(),
+            expire_days: expire_days.get_untracked(),
+        });
+    };
+
+    let on_revoke =
+        move |token: uuid::Uuid| revoke_action.dispatch(access_token::RevokeAccessToken { token });
+
+    let form_style = move || {
+        stylance::classes!(
+            style::form,
+            match (screen.style)() {
+                ScreenStyle::Portrait => Some(style::portrait),
+                ScreenStyle::Small => Some(style::small),
+                ScreenStyle::Big => Some(style::big),
+            }
+        )
+    };
+
+    view! {
+        <elements::Navbar has_sidebar=false></elements::Navbar>
+        <h1 style:color="white" style:padding="12px 48px">
+            Access Tokens
+        </h1>
+        <div style:display="flex" style:height="100%" style:justify-content="center">
+            <edit-form class=form_style>
+                <table class=style::content>
+                    <Transition fallback=move || {
+                        view! {
+                            <tr>
+                                <td>Loading...</td>
+                            </tr>
+                        }
+                    }>
+                        {move || {
+                            tokens
+                                .get()
+                                .map(|res| match res {
+                                    Ok(list) if list.is_empty() => {
+                                        view! {
+                                            <tr class=style::row>
+                                                <td colspan="2">No access tokens yet</td>
+                                            </tr>
+                                        }
+                                            .into_view()
+                                    }
+                                    Ok(list) => {
+                                        list.into_iter()
+                                            .map(|token| view! { <TokenRow token on_revoke /> })
+                                            .collect_view()
+                                    }
+                                    Err(err) => {
+                                        message.set_err(AppError::from(err));
+                                        ().into_view()
+                                    }
+                                })
+                        }}
+                    </Transition>
+
+                    <tr>
+                        <td colspan="2">
+                            <hr />
+                        </td>
+                    </tr>
+
+                    <Show when=move || created_token().is_some()>
+                        <tr class=style::text_row>
+                            <td colspan="2">
+                                <span class="title">
+                                    "Copy this token now, it will not be shown again"
+                                </span>
+                                <div class=style::boxed>
+                                    <input
+                                        type="text"
+                                        readonly
+                                        style:width="100%"
+                                        prop:value=move || created_token().unwrap_or_default()
+                                    />
+                                </div>
+                            </td>
+                        </tr>
+                    </Show>
+                </table>
+
+                <form on:submit=on_submit>
+                    <table class=style::content>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="token-name" class="title">
+                                    Name
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="text"
+                                    id="token-name"
+                                    placeholder="Stream deck"
+                                    prop:value=name
+                                    on:input=move |ev| name.set(event_target_value(&ev))
+                                />
+                            </td>
+                        </tr>
+
+                        {TokenScope::ALL
+                            .into_iter()
+                            .map(|scope| {
+                                let id = format!("scope-{scope}");
+                                let on_change = move |_| {
+                                    scopes
+                                        .update(|s| {
+                                            if let Some(idx) = s.iter().position(|v| *v == scope) {
+                                                s.remove(idx);
+                                            } else {
+                                                s.push(scope);
+                                            }
+                                        })
+                                };
+                                view! {
+                                    <tr class=style::row>
+                                        <td>
+                                            <label for=id.clone() class="title">
+                                                {scope.description()}
+                                            </label>
+                                        </td>
+                                        <td>
+                                            <Slider
+                                                checked=scopes.get_untracked().contains(&scope)
+                                                attr:id=id
+                                                on:change=on_change
+                                            />
+                                        </td>
+                                    </tr>
+                                }
+                            })
+                            .collect_view()}
+
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="token-expire" class="title">
+                                    Expires after (days)
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="number"
+                                    id="token-expire"
+                                    min="1"
+                                    placeholder="Never"
+                                    on:input=move |ev| {
+                                        expire_days.set(event_target_value(&ev).parse().ok())
+                                    }
+                                />
+                            </td>
+                        </tr>
+                    </table>
+
+                    <action-buttons class=style::action_buttons>
+                        <action-start></action-start>
+                        <action-end>
+                            <A href="/preferences">
+                                <button type="button">
+                                    <span>Back</span>
+                                </button>
+                            </A>
+                            <button type="submit" class=style::confirm>
+                                <span>Create</span>
+                            </button>
+                        </action-end>
+                    </action-buttons>
+                </form>
+            </edit-form>
+        </div>
+    }
+}
+
+#[component]
+fn TokenRow<F>(token: AccessToken, on_revoke: F) -> impl IntoView
+where
+    F: Fn(uuid::Uuid) + Copy + 'static,
+{
+    let scopes = token
+        .scopes
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let last_used = token
+        .last_used
+        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or(String::from("never"));
+
+    let expires = token
+        .expire_on
+        .map(|d| format!(", expires {}", d.format("%Y-%m-%d")))
+        .unwrap_or_default();
+
+    let uuid = token.uuid;
+
+    view! {
+        <tr class=style::row>
+            <td>
+                <span class="title">{token.name}</span>
+                <br />
+                <small>{format!("{scopes} - last used {last_used}{expires}")}</small>
+            </td>
+            <td>
+                <button
+                    type="button"
+                    class=style::edit
+                    title="Revoke"
+                    on:click=move |_| on_revoke(uuid)
+                >
+                    <i class="fa-solid fa-trash"></i>
+                </button>
+            </td>
+        </tr>
+    }
+}
@@ -0,0 +1,240 @@
+#![allow(unused_braces)]
+use components::MessageJar;
+use leptos::*;
+use leptos_router::{use_params, Params, A};
+
+stylance::import_style!(
+    #[allow(dead_code)]
+    style,
+    "../../style/edit.module.scss"
+);
+
+use super::*;
+use action_url::{ActionKind, ActionUrl};
+
+#[derive(Debug, Clone, Params, PartialEq, Eq, Default)]
+struct Key {
+    key: String,
+}
+
+#[component]
+pub fn ActionUrlsWindow() -> impl IntoView {
+    let message = expect_context::<MessageJar>();
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let screen = expect_context::<Screen>();
+
+    let key = create_memo(move |_| {
+        use_params::<Key>()()
+            .ok()
+            .and_then(|p| uuid::Uuid::parse_str(&p.key).ok())
+            .unwrap_or_default()
+    });
+    let name = create_read_slice(store, move |s| s.name(&key().into()));
+
+    let create_action = create_server_action::<action_url::CreateActionUrl>();
+    let revoke_action = create_server_action::<action_url::RevokeActionUrl>();
+
+    let action_urls = create_resource(
+        move || {
+            (
+                key(),
+                create_action.version().get(),
+                revoke_action.version().get(),
+            )
+        },
+        |(countable, _, _)| action_url::get_action_urls(countable),
+    );
+
+    let created_url = create_rw_signal(None::<String>);
+
+    create_effect(move |_| match create_action.value().get() {
+        Some(Ok((_, secret))) => {
+            let origin = window().location().origin().unwrap_or_default();
+            created_url.set(Some(format!(
+                "{origin}{}",
+                action_url::action_path(&secret)
+            )));
+        }
+        Some(Err(err)) => message.set_err(AppError::from(err)),
+        None => {}
+    });
+
+    create_effect(move |_| match revoke_action.value().get() {
+        Some(Ok(_)) => message.set_msg("Action url revoked"),
+        Some(Err(err)) => message.set_err(AppError::from(err)),
+        None => {}
+    });
+
+    let on_revoke = move |action_url: uuid::Uuid| {
+        revoke_action.dispatch(action_url::RevokeActionUrl { action_url })
+    };
+
+    let form_style = move || {
+        stylance::classes!(
+            style::form,
+            match (screen.style)() {
+                ScreenStyle::Portrait => Some(style::portrait),
+                ScreenStyle::Small => Some(style::small),
+                ScreenStyle::Big => Some(style::big),
+            }
+        )
+    };
+
+    view! {
+        <elements::Navbar has_sidebar=false></elements::Navbar>
+        <h1 style:color="white" style:padding="12px 48px">
+            {move || format!("Action urls for {}", name())}
+        </h1>
+        <div style:display="flex" style:height="100%" style:justify-content="center">
+            <edit-form class=form_style>
+                <table class=style::content>
+                    <Transition fallback=move || {
+                        view! {
+                            <tr>
+                                <td>Loading...</td>
+                            </tr>
+                        }
+                    }>
+                        {move || {
+                            action_urls
+                                .get()
+                                .map(|res| match res {
+                                    Ok(list) if list.is_empty() => {
+                                        view! {
+                                            <tr class=style::row>
+                                                <td colspan="2">No action urls yet</td>
+                                            </tr>
+                                        }
+                                            .into_view()
+                                    }
+                                    Ok(list) => {
+                                        list.into_iter()
+                                            .map(|action_url| {
+                                                view! { <ActionUrlRow action_url on_revoke /> }
+                                            })
+                                            .collect_view()
+                                    }
+                                    Err(err) => {
+                                        message.set_err(AppError::from(err));
+                                        ().into_view()
+                                    }
+                                })
+                        }}
+                    </Transition>
+
+                    <tr>
+                        <td colspan="2">
+                            <hr />
+                        </td>
+                    </tr>
+
+                    <Show when=move || created_url().is_some()>
+                        <tr class=style::text_row>
+                            <td colspan="2">
+                                <span class="title">
+                                    "Copy this url now, it will not be shown again"
+                                </span>
+                                <div class=style::boxed>
+                                    <input
+                                        type="text"
+                                        readonly
+                                        style:width="100%"
+                                        prop:value=move || created_url().unwrap_or_default()
+                                    />
+                                </div>
+                                <small>
+                                    "Send a POST request to apply the action, opening the url asks to confirm first. Buttons that can only open a url add ?confirm=false to apply it right away"
+                                </small>
+                            </td>
+                        </tr>
+                    </Show>
+
+                    {ActionKind::ALL
+                        .into_iter()
+                        .map(|action| {
+                            let on_click = move |_| {
+                                create_action
+                                    .dispatch(action_url::CreateActionUrl {
+                                        countable: key.get_untracked(),
+                                        action,
+                                    })
+                            };
+                            view! {
+                                <tr class=style::row>
+                                    <td>
+                                        <span class="title">{action.description()}</span>
+                                    </td>
+                                    <td>
+                                        <button
+                                            type="button"
+                                            class=style::edit
+                                            title="Create url"
+                                            on:click=on_click
+                                        >
+                                            <i class="fa-solid fa-plus"></i>
+                                        </button>
+                                    </td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </table>
+
+                <action-buttons class=style::action_buttons>
+                    <action-start></action-start>
+                    <action-end>
+                        <A href=move || format!("/{}", key())>
+                            <button type="button">
+                                <span>Back</span>
+                            </button>
+                        </A>
+                    </action-end>
+                </action-buttons>
+            </edit-form>
+        </div>
+    }
+}
+
+#[component]
+fn ActionUrlRow<F>(action_url: ActionUrl, on_revoke: F) -> impl IntoView
+where
+    F: Fn(uuid::Uuid) + Copy + 'static,
+{
+    let last_used = action_url
+        .last_used
+        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or(String::from("never"));
+
+    let timer = if action_url.timer_running {
+        ", timer running"
+    } else {
+        ""
+    };
+
+    let uuid = action_url.uuid;
+
+    view! {
+        <tr class=style::row>
+            <td>
+                <span class="title">{action_url.action.description()}</span>
+                <br />
+                <small>
+                    {format!(
+                        "created {} - last used {last_used}{timer}",
+                        action_url.created_at.format("%Y-%m-%d"),
+                    )}
+                </small>
+            </td>
+            <td>
+                <button
+                    type="button"
+                    class=style::edit
+                    title="Revoke"
+                    on:click=move |_| on_revoke(uuid)
+                >
+                    <i class="fa-solid fa-trash"></i>
+                </button>
+            </td>
+        </tr>
+    }
+}
@@ -25,6 +25,9 @@ pub fn CreateAccount() -> impl IntoView {
             // message.set(Some(String::from("passwords do not match")));
             ev.prevent_default();
         }
+        if !ev.default_prevented() {
+            guest::mark_for_upgrade()
+        }
         create_effect(move |_| match action.value().get() {
             Some(Ok(_)) => leptos_router::use_navigate()("/", Default::default()),
             Some(Err(err)) => message_jar.set_err(err.to_string()),
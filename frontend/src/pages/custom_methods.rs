@@ -0,0 +1,289 @@
+#![allow(unused_braces)]
+use components::MessageJar;
+use leptos::*;
+use leptos_router::A;
+use web_sys::SubmitEvent;
+
+stylance::import_style!(
+    #[allow(dead_code)]
+    style,
+    "../../style/edit.module.scss"
+);
+
+use super::*;
+use custom_method::CustomMethod;
+
+#[component]
+pub fn CustomMethodsWindow() -> impl IntoView {
+    let message = expect_context::<MessageJar>();
+    let screen = expect_context::<Screen>();
+    let store = expect_context::<RwSignal<CountableStore>>();
+
+    let create_action = create_server_action::<custom_method::CreateCustomMethod>();
+    let delete_action = create_server_action::<custom_method::DeleteCustomMethod>();
+
+    let methods = create_resource(
+        move || (create_action.version().get(), delete_action.version().get()),
+        |_| custom_method::get_custom_methods(),
+    );
+
+    // the store evaluates the progress of phases with these methods
+    create_effect(move |_| {
+        if let Some(Ok(list)) = methods.get() {
+            store.update(|s| s.set_custom_methods(list))
+        }
+    });
+
+    let name = create_rw_signal(String::new());
+    let odds = create_rw_signal(String::from("4096"));
+    let rolls = create_rw_signal(String::from("1"));
+    let charm_rolls = create_rw_signal(String::from("2"));
+    let table = create_rw_signal(String::new());
+
+    create_effect(move |_| match create_action.value().get() {
+        Some(Ok(method)) => {
+            name.set(String::new());
+            table.set(String::new());
+            message.set_msg(format!("Added {}", method.name))
+        }
+        Some(Err(err)) => message.set_err(AppError::from(err)),
+        None => {}
+    });
+
+    create_effect(move |_| match delete_action.value().get() {
+        Some(Ok(_)) => message.set_msg("Custom method deleted"),
+        Some(Err(err)) => message.set_err(AppError::from(err)),
+        None => {}
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+
+        let number = |field: &str, value: String| {
+            value.trim().parse::<i32>().map_err(|_| {
+                AppError::InvalidField(field.into(), "has to be a whole number".into())
+            })
+        };
+
+        let method = (|| {
+            let method = CustomMethod {
+                uuid: uuid::Uuid::nil(),
+                name: name.get_untracked().trim().to_string(),
+                odds: number("odds", odds.get_untracked())?,
+                rolls: number("rolls", rolls.get_untracked())?,
+                charm_rolls: number("charm bonus", charm_rolls.get_untracked())?,
+                table: CustomMethod::parse_table(&table.get_untracked())?,
+            };
+            method.validate()?;
+            Ok::<_, AppError>(method)
+        })();
+
+        match method {
+            Ok(method) => create_action.dispatch(custom_method::CreateCustomMethod { method }),
+            Err(err) => message.set_err(err),
+        }
+    };
+
+    let on_delete = move |method: uuid::Uuid| {
+        delete_action.dispatch(custom_method::DeleteCustomMethod { method })
+    };
+
+    let form_style = move || {
+        stylance::classes!(
+            style::form,
+            match (screen.style)() {
+                ScreenStyle::Portrait => Some(style::portrait),
+                ScreenStyle::Small => Some(style::small),
+                ScreenStyle::Big => Some(style::big),
+            }
+        )
+    };
+
+    view! {
+        <elements::Navbar has_sidebar=false></elements::Navbar>
+        <h1 style:color="white" style:padding="12px 48px">
+            Custom Methods
+        </h1>
+        <div style:display="flex" style:height="100%" style:justify-content="center">
+            <edit-form class=form_style>
+                <table class=style::content>
+                    <Transition fallback=move || {
+                        view! {
+                            <tr>
+                                <td>Loading...</td>
+                            </tr>
+                        }
+                    }>
+                        {move || {
+                            methods
+                                .get()
+                                .map(|res| match res {
+                                    Ok(list) if list.is_empty() => {
+                                        view! {
+                                            <tr class=style::row>
+                                                <td colspan="2">No custom methods yet</td>
+                                            </tr>
+                                        }
+                                            .into_view()
+                                    }
+                                    Ok(list) => {
+                                        list.into_iter()
+                                            .map(|method| view! { <MethodRow method on_delete /> })
+                                            .collect_view()
+                                    }
+                                    Err(err) => {
+                                        message.set_err(AppError::from(err));
+                                        ().into_view()
+                                    }
+                                })
+                        }}
+                    </Transition>
+
+                    <tr>
+                        <td colspan="2">
+                            <hr />
+                        </td>
+                    </tr>
+                </table>
+
+                <form on:submit=on_submit>
+                    <table class=style::content>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="method-name" class="title">
+                                    Name
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="text"
+                                    id="method-name"
+                                    placeholder="Event odds"
+                                    maxlength=countable::validate::MAX_NAME_LENGTH
+                                    prop:value=name
+                                    on:input=move |ev| name.set(event_target_value(&ev))
+                                />
+                            </td>
+                        </tr>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="method-odds" class="title">
+                                    Odds (1 in)
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="number"
+                                    id="method-odds"
+                                    min="1"
+                                    prop:value=odds
+                                    on:input=move |ev| odds.set(event_target_value(&ev))
+                                />
+                            </td>
+                        </tr>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="method-rolls" class="title">
+                                    Rolls per encounter
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="number"
+                                    id="method-rolls"
+                                    min="1"
+                                    prop:value=rolls
+                                    on:input=move |ev| rolls.set(event_target_value(&ev))
+                                />
+                            </td>
+                        </tr>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="method-charm" class="title">
+                                    Extra rolls with charm
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="number"
+                                    id="method-charm"
+                                    min="0"
+                                    prop:value=charm_rolls
+                                    on:input=move |ev| charm_rolls.set(event_target_value(&ev))
+                                />
+                            </td>
+                        </tr>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="method-table" class="title">
+                                    Rolls from count on
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <textarea
+                                    id="method-table"
+                                    placeholder="10: 5, 20: 9, 30: 13"
+                                    prop:value=table
+                                    on:input=move |ev| table.set(event_target_value(&ev))
+                                ></textarea>
+                            </td>
+                        </tr>
+                    </table>
+
+                    <action-buttons class=style::action_buttons>
+                        <action-start></action-start>
+                        <action-end>
+                            <A href="/preferences">
+                                <button type="button">
+                                    <span>Back</span>
+                                </button>
+                            </A>
+                            <button type="submit" class=style::confirm>
+                                <span>Create</span>
+                            </button>
+                        </action-end>
+                    </action-buttons>
+                </form>
+            </edit-form>
+        </div>
+    }
+}
+
+#[component]
+fn MethodRow<F>(method: CustomMethod, on_delete: F) -> impl IntoView
+where
+    F: Fn(uuid::Uuid) + Copy + 'static,
+{
+    let table = method
+        .table
+        .iter()
+        .map(|(count, rolls)| format!("{rolls} from {count}"))
+        .fold(String::new(), |acc, entry| acc + ", " + &entry);
+
+    let details = format!(
+        "1/{} - {} rolls, +{} with charm{table}",
+        method.odds, method.rolls, method.charm_rolls
+    );
+
+    let uuid = method.uuid;
+
+    view! {
+        <tr class=style::row>
+            <td>
+                <span class="title">{method.name}</span>
+                <br />
+                <small>{details}</small>
+            </td>
+            <td>
+                <button
+                    type="button"
+                    class=style::edit
+                    title="Delete"
+                    on:click=move |_| on_delete(uuid)
+                >
+                    <i class="fa-solid fa-trash"></i>
+                </button>
+            </td>
+        </tr>
+    }
+}
@@ -4,7 +4,8 @@ use components::{
 };
 use elements::{Navbar, SortMethod, SortSearch};
 use leptos::*;
-use leptos_router::{use_params, ActionForm, Outlet, Params, A};
+use leptos_router::{use_params, FromFormData, Outlet, Params, A};
+use std::collections::HashMap;
 
 use super::*;
 
@@ -177,73 +178,353 @@ struct Key {
     key: String,
 }
 
+/// Values of the edit form, numbers are kept as text so they can be checked field by field
+#[derive(Debug, Clone, serde::Deserialize)]
+struct EditForm {
+    countable_name: String,
+    countable_count: String,
+    countable_step: String,
+    countable_hours: String,
+    countable_mins: String,
+    countable_secs: String,
+    countable_millis: String,
+    countable_game: String,
+    countable_hunttype: String,
+    countable_research: Option<String>,
+    countable_cleared: Option<String>,
+    countable_sparkling: Option<String>,
+    countable_charm: Option<String>,
+    countable_target: Option<String>,
+}
+
+struct Edit {
+    name: String,
+    count: i32,
+    step: i32,
+    time: chrono::TimeDelta,
+    game: Option<Game>,
+    /// `None` when the phases of a counter use different methods and that was not changed
+    hunttype: Option<Hunttype>,
+    has_charm: bool,
+    /// `None` for phases, they have no target of their own
+    target: Option<Option<i32>>,
+}
+
+type FieldErrors = HashMap<String, String>;
+
+impl EditForm {
+    fn parse(self) -> Result<Edit, FieldErrors> {
+        fn number(field: &str, value: &str) -> Result<i64, AppError> {
+            value.trim().parse().map_err(|_| {
+                AppError::InvalidField(field.into(), "has to be a whole number".into())
+            })
+        }
+
+        let mut errors = FieldErrors::new();
+        let mut check = |res: Result<(), AppError>| {
+            if let Err(AppError::InvalidField(field, reason)) = res {
+                errors.entry(field).or_insert(reason);
+            }
+        };
+
+        let name = self.countable_name.trim().to_string();
+        check(countable::validate::name(&name));
+
+        let count = number("count", &self.countable_count).and_then(|c| {
+            i32::try_from(c)
+                .map_err(|_| AppError::InvalidField("count".into(), "is too large".into()))
+        });
+        let count = count.and_then(|c| countable::validate::count(c).map(|_| c));
+        let step = number("step size", &self.countable_step).and_then(|s| {
+            let s = i32::try_from(s).unwrap_or(i32::MAX);
+            countable::validate::step_size(s).map(|_| s)
+        });
+
+        let time = (|| {
+            let hours = number("time", &self.countable_hours)?;
+            let mins = number("time", &self.countable_mins)?;
+            let secs = number("time", &self.countable_secs)?;
+            let millis = number("time", &self.countable_millis)?;
+            if hours < 0 || mins < 0 || secs < 0 || millis < 0 {
+                Err(AppError::InvalidField(
+                    "time".into(),
+                    "can not be negative".into(),
+                ))?
+            }
+            if mins >= 60 || secs >= 60 {
+                Err(AppError::InvalidField(
+                    "time".into(),
+                    "minutes and seconds have to be below 60".into(),
+                ))?
+            }
+            if millis >= 1000 {
+                Err(AppError::InvalidField(
+                    "time".into(),
+                    "milliseconds have to be below 1000".into(),
+                ))?
+            }
+            let time = chrono::TimeDelta::try_hours(hours)
+                .and_then(|t| t.checked_add(&chrono::TimeDelta::minutes(mins)))
+                .and_then(|t| t.checked_add(&chrono::TimeDelta::seconds(secs)))
+                .and_then(|t| t.checked_add(&chrono::TimeDelta::milliseconds(millis)))
+                .ok_or(AppError::InvalidField("time".into(), "is too large".into()))?;
+            countable::validate::time(time)?;
+            Ok(time)
+        })();
+
+        let (count, step, time) = match (count, step, time) {
+            (Ok(count), Ok(step), Ok(time)) => (count, step, time),
+            (count, step, time) => {
+                check(count.map(|_| ()));
+                check(step.map(|_| ()));
+                check(time.map(|_| ()));
+                return Err(errors);
+            }
+        };
+
+        let game = match self.countable_game.as_str() {
+            "" => None,
+            _ => Game::try_from(self.countable_game).ok(),
+        };
+        let hunttype = Hunttype::try_from(self.countable_hunttype)
+            .ok()
+            .map(|hunttype| match hunttype {
+                Hunttype::MassOutbreak(_) | Hunttype::MassiveMassOutbreak(_) => {
+                    let research = self
+                        .countable_research
+                        .as_deref()
+                        .and_then(|r| r.parse::<i16>().ok())
+                        .map(Research::from)
+                        .unwrap_or_default();
+                    match hunttype {
+                        Hunttype::MassOutbreak(_) => Hunttype::MassOutbreak(research),
+                        _ => Hunttype::MassiveMassOutbreak(research),
+                    }
+                }
+                Hunttype::Outbreak(_) => {
+                    let cleared =
+                        number("cleared", self.countable_cleared.as_deref().unwrap_or("0"))
+                            .and_then(|c| match i32::try_from(c) {
+                                Ok(c) if c >= 0 => Ok(c),
+                                _ => Err(AppError::InvalidField(
+                                    "cleared".into(),
+                                    "can not be negative".into(),
+                                )),
+                            });
+                    let sparkling_power = self
+                        .countable_sparkling
+                        .as_deref()
+                        .unwrap_or("0")
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|s| *s <= OutbreakBonus::MAX_SPARKLING_POWER)
+                        .ok_or(AppError::InvalidField(
+                            "sparkling power".into(),
+                            format!(
+                                "has to be between 0 and {}",
+                                OutbreakBonus::MAX_SPARKLING_POWER
+                            ),
+                        ));
+                    let cleared = cleared.unwrap_or_else(|err| {
+                        check(Err(err));
+                        0
+                    });
+                    let sparkling_power = sparkling_power.unwrap_or_else(|err| {
+                        check(Err(err));
+                        0
+                    });
+                    Hunttype::Outbreak(OutbreakBonus {
+                        cleared,
+                        sparkling_power,
+                    })
+                }
+                hunttype => hunttype,
+            });
+        let target = self.countable_target.map(|target| match target.trim() {
+            "" => None,
+            target => number("target", target)
+                .and_then(|t| {
+                    let t = i32::try_from(t).unwrap_or(i32::MAX);
+                    countable::validate::target(t).map(|_| t)
+                })
+                .map_err(|err| check(Err(err)))
+                .ok(),
+        });
+
+        if let Some(hunttype) = hunttype {
+            check(countable::validate::method(game, hunttype));
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Edit {
+            name,
+            count,
+            step,
+            time,
+            game,
+            hunttype,
+            // the charm can not be picked in games without it
+            has_charm: self.countable_charm.is_some() && game.is_none_or(|g| g.has_charm()),
+            target,
+        })
+    }
+}
+
+impl Edit {
+    /// Changes the values that differ from `store`, every change is recorded as an operation
+    fn apply(&self, store: &CountableStore, key: &CountableId) -> Result<(), AppError> {
+        let store = store.checked_ref();
+        let recursive = store.recursive_ref();
+
+        if store.name(key)? != self.name {
+            store.set_name(key, &self.name)?
+        }
+        if recursive.count(key)? != self.count {
+            recursive.set_count(key, self.count)?
+        }
+        if recursive.step_size(key)? != self.step {
+            store.set_step_size(key, self.step)?
+        }
+        if recursive.time(key)? != self.time {
+            recursive.set_time(key, self.time)?
+        }
+        if store.game_checked(key)? != self.game {
+            store.set_game(key, self.game)?
+        }
+        if let Some(hunttype) = self.hunttype
+            && recursive.hunttype(key)? != hunttype
+        {
+            store.set_hunttype(key, hunttype)?
+        }
+        if store.has_charm_checked(key)? != self.has_charm {
+            store.set_charm(key, self.has_charm)?
+        }
+        if let Some(target) = self.target
+            && store.target_checked(key)? != target
+        {
+            store.set_target(key, target)?
+        }
+
+        Ok(())
+    }
+}
+
 #[component]
 fn EditCounterBox(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
-    let rs = expect_context::<StateResource>();
-    let session = expect_context::<RwSignal<UserSession>>();
     let store = expect_context::<RwSignal<CountableStore>>();
+    let save_handlers = expect_context::<RwSignal<SaveHandlers>>();
     let msg = expect_context::<MessageJar>();
-    let action = create_server_action::<api::EditCountableForm>();
     let screen = expect_context::<Screen>();
 
-    let kind = create_read_slice(store, move |s| s.kind(&key().into()));
+    let errors = create_rw_signal(FieldErrors::new());
+    let error =
+        move |field: &'static str| Signal::derive(move || errors.with(|e| e.get(field).cloned()));
+
+    let form_ref = create_node_ref::<html::Form>();
+
+    // the selected game restricts the methods and the charm,
+    // it starts out as the saved game of the countable
+    let saved_game = move || store.with_untracked(|s| s.game(&key.get().into()));
+    let game = create_rw_signal(saved_game());
+    create_effect(move |_| game.set(saved_game()));
+    // the selected method decides which of its values can be changed
+    let method = create_rw_signal(None);
+    let is_counter = create_read_slice(store, move |s| {
+        matches!(
+            s.checked_ref().kind(&key().into()),
+            Ok(CountableKind::Counter)
+        )
+    });
 
-    create_effect(move |_| match action.value()() {
-        Some(Ok(_)) => {
-            leptos_router::use_navigate()(format!("/{}", key()).as_str(), Default::default())
+    let on_submit = move |ev: ev::SubmitEvent| {
+        ev.prevent_default();
+
+        let form = match EditForm::from_event(&ev) {
+            Ok(form) => form,
+            Err(err) => return msg.set_err(err),
+        };
+        let edit = match form.parse() {
+            Ok(edit) => edit,
+            Err(field_errors) => return errors.set(field_errors),
+        };
+        errors.set(FieldErrors::new());
+
+        let key = key.get_untracked();
+        let mut res = Ok(());
+        store.update(|s| res = edit.apply(s, &key.into()));
+        if let Err(err) = res {
+            return msg.set_err(err);
         }
-        Some(Err(err)) => {
-            match err {
-                ServerFnError::WrappedServerError(err) => msg.set_err(err),
-                ServerFnError::Registration(err) => msg.set_err(err),
-                ServerFnError::Request(_) => msg.set_err("Could not reach server"),
-                ServerFnError::Response(err) => msg.set_err(err),
-                ServerFnError::ServerError(err) => msg.set_err(err),
-                ServerFnError::Deserialization(err) => msg.set_err(err),
-                ServerFnError::Serialization(err) => msg.set_err(err),
-                ServerFnError::Args(err) => msg.set_err(err),
-                ServerFnError::MissingArg(err) => msg.set_err(err),
-            };
-        }
-        None => {}
-    });
 
-    create_effect(move |_| {
-        if let Some(Ok(_)) = action.value()() {
-            rs.refetch();
-            leptos_router::use_navigate()(format!("/{}", key()).as_str(), Default::default())
+        if let Err(err) = save_handlers
+            .get_untracked()
+            .save(Box::new(store), Box::new(|_| ()))
+        {
+            return msg.set_err(err);
         }
-    });
+
+        // stay on the page until the server has the changes, so rejected values can be fixed
+        let navigate = leptos_router::use_navigate();
+        spawn_local(async move {
+            match countable::outbox::flush().await {
+                // offline changes are sent once the server can be reached again
+                Ok(()) | Err(ServerFnError::Request(_)) => {
+                    navigate(format!("/{}", key).as_str(), Default::default())
+                }
+                // the save handler already shows other errors
+                Err(err) => {
+                    if let AppError::InvalidField(field, reason) = AppError::from(err) {
+                        errors.update(|e| {
+                            e.insert(field, reason);
+                        })
+                    }
+                }
+            }
+        });
+    };
 
     let undo = move |_| {
-        rs.refetch();
+        errors.set(FieldErrors::new());
+        game.set(saved_game());
+        if let Some(form) = form_ref() {
+            form.reset()
+        }
     };
 
     view! {
-        <ActionForm action>
-            <SessionFormInput session />
-            <input type="hidden" name="countable_key" value=move || key().to_string() />
-            <input type="hidden" name="countable_kind" value=move || kind().to_string() />
+        <form on:submit=on_submit node_ref=form_ref novalidate>
             <table style:display="flex" style:flex-flow="column" class=style::content>
                 <tbody>
                     <tr class=stylance::classes!(style::row, style::text_row)>
-                        <EditName key />
+                        <EditName key error=error("name") />
                     </tr>
                     <tr class=stylance::classes!(style::row, style::text_row)>
-                        <EditCount key />
+                        <EditCount key error=error("count") />
                     </tr>
                     <tr class=stylance::classes!(style::row, style::text_row)>
-                        <EditStepSize key />
+                        <EditStepSize key error=error("step size") />
                     </tr>
                     <tr class=stylance::classes!(style::row, style::text_row)>
-                        <EditTime key />
+                        <EditTime key error=error("time") />
                     </tr>
                     <tr class=stylance::classes!(style::row, style::text_row)>
-                        <EditHunttype key />
+                        <EditGame key game error=error("game") />
                     </tr>
+                    <tr class=stylance::classes!(style::row, style::text_row)>
+                        <EditHunttype key game method error=error("method") />
+                    </tr>
+                    <EditMethodParams key method error />
                     <tr class=style::row>
-                        <EditCharm key />
+                        <EditCharm key game />
                     </tr>
+                    <Show when=is_counter>
+                        <tr class=stylance::classes!(style::row, style::text_row)>
+                            <EditTarget key error=error("target") />
+                        </tr>
+                    </Show>
                 </tbody>
             </table>
             <action-buttons class=move || {
@@ -263,20 +544,26 @@ fn EditCounterBox(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
                     </button>
                 </action-end>
             </action-buttons>
-        </ActionForm>
+        </form>
     }
 }
 
 #[component]
-fn EditName(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
-    let store = expect_context::<RwSignal<CountableStore>>();
-    let (name, set_name) = create_slice(
-        store,
-        move |s| s.name(&key().into()),
-        move |s, name: String| s.set_name(&key().into(), &name),
-    );
+fn FieldError(error: Signal<Option<String>>) -> impl IntoView {
+    view! {
+        <Show when=move || error.with(Option::is_some)>
+            <span class=style::field_error>{move || error.get()}</span>
+        </Show>
+    }
+}
 
-    let on_input = move |ev| set_name(event_target_value(&ev));
+#[component]
+fn EditName(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let name = create_read_slice(store, move |s| s.name(&key().into()));
 
     view! {
         <td>
@@ -287,19 +574,22 @@ fn EditName(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
                 <input
                     type="text"
                     value=name
-                    prop:value=name
                     id="change-name"
                     name="countable_name"
-                    on:input=on_input
+                    maxlength=countable::validate::MAX_NAME_LENGTH
                     style:text-align="end"
                 />
             </div>
+            <FieldError error />
         </td>
     }
 }
 
 #[component]
-fn EditCount(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
+fn EditCount(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
     let store = expect_context::<RwSignal<CountableStore>>();
     let count = create_read_slice(store, move |s| s.recursive_ref().count(&key().into()));
 
@@ -318,12 +608,16 @@ fn EditCount(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
                     style:text-align="end"
                 />
             </div>
+            <FieldError error />
         </td>
     }
 }
 
 #[component]
-fn EditStepSize(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
+fn EditStepSize(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
     let store = expect_context::<RwSignal<CountableStore>>();
     let step = create_read_slice(store, move |s| s.recursive_ref().step_size(&key().into()));
 
@@ -342,12 +636,16 @@ fn EditStepSize(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
                     style:text-align="end"
                 />
             </div>
+            <FieldError error />
         </td>
     }
 }
 
 #[component]
-fn EditTime(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
+fn EditTime(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
     let store = expect_context::<RwSignal<CountableStore>>();
     let time = create_read_slice(store, move |s| s.recursive_ref().time(&key().into()));
 
@@ -448,50 +746,229 @@ fn EditTime(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
                     />
                 </label>
             </div>
+            <FieldError error />
         </td>
     }
 }
 
 #[component]
-fn EditHunttype(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
+fn EditGame(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    game: RwSignal<Option<Game>>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
     let store = expect_context::<RwSignal<CountableStore>>();
-    let hunt_type = move || store().recursive_ref().hunttype(&key().into());
-    let selected = create_memo(move |_| hunt_type().into());
+    let selected = create_memo(move |_| match store().game(&key().into()) {
+        Some(game) => game.into(),
+        None => ("Unknown", "").into(),
+    });
 
-    let hunt_option = |ht: Hunttype| -> (&'static str, &'static str) { (ht.repr(), ht.into()) };
+    let options = std::iter::once(("Unknown", "").into())
+        .chain(Game::ALL.into_iter().map(Game::into))
+        .collect::<Vec<_>>();
 
-    let options = vec![
-        hunt_option(Hunttype::OldOdds).into(),
-        hunt_option(Hunttype::NewOdds).into(),
-        hunt_option(Hunttype::Masuda(Masuda::GenIV)).into(),
-        hunt_option(Hunttype::Masuda(Masuda::GenV)).into(),
-        hunt_option(Hunttype::Masuda(Masuda::GenVI)).into(),
-        hunt_option(Hunttype::SOS).into(),
-        // hunt_option(Hunttype::DexNav).into(),
-    ];
+    let on_change = move |value: String| game.set(Game::try_from(value).ok());
 
     view! {
         <td>
-            <label for="change-hunttype">Method</label>
+            <label for="change-game">Game</label>
         </td>
         <td style:text-align="start">
             <div class=style::boxed>
                 <Select
-                    attr:id="change-hunttype"
-                    attr:name="countable_hunttype"
-                    attr:value=hunt_type
+                    attr:id="change-game"
+                    attr:name="countable_game"
                     selected
                     options
+                    on_change
                 />
             </div>
+            <FieldError error />
         </td>
     }
 }
 
 #[component]
-fn EditCharm(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
+fn EditHunttype(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    game: RwSignal<Option<Game>>,
+    method: RwSignal<Option<Hunttype>>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let hunt_type = move || store().recursive_ref().hunttype(&key().into());
+
+    // only the methods of the selected game can be picked, custom methods fit every game
+    let custom = create_read_slice(store, |s| {
+        s.custom_methods()
+            .iter()
+            .map(|m| (m.uuid, m.name.clone()))
+            .collect::<Vec<_>>()
+    });
+    let methods = create_memo(move |_| match game() {
+        Some(game) => game.methods(),
+        None => Hunttype::ALL.to_vec(),
+    });
+
+    let select = move || {
+        let methods = methods();
+        let current = untrack(hunt_type);
+        let selected = if methods.iter().any(|m| m.same_method(&current))
+            || matches!(current, Hunttype::Custom(_))
+            || game.get_untracked().is_none()
+        {
+            current
+        } else {
+            methods[0]
+        };
+        let custom = custom.get();
+        let option = |hunttype: Hunttype| -> components::SelectOption {
+            match hunttype {
+                Hunttype::Custom(id) => custom
+                    .iter()
+                    .find(|(uuid, _)| *uuid == id)
+                    .map(|(_, name)| (name.clone(), format!("Custom:{id}")).into())
+                    .unwrap_or(hunttype.into()),
+                _ => hunttype.into(),
+            }
+        };
+        let options = methods
+            .iter()
+            .copied()
+            .chain(custom.iter().map(|(id, _)| Hunttype::Custom(*id)))
+            .map(option)
+            .collect::<Vec<_>>();
+
+        view! {
+            <Select
+                attr:id="change-hunttype"
+                attr:name="countable_hunttype"
+                selected=option(selected)
+                options
+                on_change=move |value: String| method.set(Hunttype::try_from(value).ok())
+            />
+        }
+    };
+
+    view! {
+        <td>
+            <label for="change-hunttype">Method</label>
+        </td>
+        <td style:text-align="start">
+            <div class=style::boxed>{select}</div>
+            <FieldError error />
+        </td>
+    }
+}
+
+/// Values of the selected method, only shown for methods that have them
+#[component]
+fn EditMethodParams(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    method: RwSignal<Option<Hunttype>>,
+    error: impl Fn(&'static str) -> Signal<Option<String>> + 'static,
+) -> impl IntoView {
+    let store = expect_context::<RwSignal<CountableStore>>();
+    // the stored values are kept when the method stays the same
+    let current = move || {
+        let stored = untrack(|| store().recursive_ref().hunttype(&key().into()));
+        method()
+            .map(|m| if m.same_method(&stored) { stored } else { m })
+            .unwrap_or(stored)
+    };
+    let cleared_error = error("cleared");
+    let sparkling_error = error("sparkling power");
+
+    move || match current() {
+        Hunttype::MassOutbreak(research) | Hunttype::MassiveMassOutbreak(research) => {
+            let options = Research::ALL
+                .into_iter()
+                .map(|r| (r.repr(), i16::from(r).to_string().as_str()).into())
+                .collect::<Vec<components::SelectOption>>();
+            let selected: components::SelectOption =
+                (research.repr(), i16::from(research).to_string().as_str()).into();
+            view! {
+                <tr class=stylance::classes!(style::row, style::text_row)>
+                    <td>
+                        <label for="change-research">Research</label>
+                    </td>
+                    <td style:text-align="start">
+                        <div class=style::boxed>
+                            <Select
+                                attr:id="change-research"
+                                attr:name="countable_research"
+                                selected
+                                options
+                            />
+                        </div>
+                    </td>
+                </tr>
+            }
+            .into_view()
+        }
+        Hunttype::Outbreak(outbreak) => {
+            let options = (0..=OutbreakBonus::MAX_SPARKLING_POWER)
+                .map(|level| {
+                    let value = level.to_string();
+                    let name = if level == 0 {
+                        "None".to_string()
+                    } else {
+                        value.clone()
+                    };
+                    (name, value).into()
+                })
+                .collect::<Vec<components::SelectOption>>();
+            let selected = options[outbreak.sparkling_power as usize].clone();
+            view! {
+                <tr class=stylance::classes!(style::row, style::text_row)>
+                    <td>
+                        <label for="change-cleared">Outbreak cleared</label>
+                    </td>
+                    <td>
+                        <div class=style::boxed>
+                            <input
+                                type="number"
+                                min="0"
+                                value=outbreak.cleared
+                                id="change-cleared"
+                                name="countable_cleared"
+                                style:text-align="end"
+                            />
+                        </div>
+                        <FieldError error=cleared_error />
+                    </td>
+                </tr>
+                <tr class=stylance::classes!(style::row, style::text_row)>
+                    <td>
+                        <label for="change-sparkling">Sparkling power</label>
+                    </td>
+                    <td style:text-align="start">
+                        <div class=style::boxed>
+                            <Select
+                                attr:id="change-sparkling"
+                                attr:name="countable_sparkling"
+                                selected
+                                options
+                            />
+                        </div>
+                        <FieldError error=sparkling_error />
+                    </td>
+                </tr>
+            }
+            .into_view()
+        }
+        _ => ().into_view(),
+    }
+}
+
+#[component]
+fn EditCharm(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    game: RwSignal<Option<Game>>,
+) -> impl IntoView {
     let store = expect_context::<RwSignal<CountableStore>>();
     let checked = create_read_slice(store, move |s| s.has_charm(&key().into()));
+    let no_charm = move || game().is_some_and(|game| !game.has_charm());
 
     view! {
         <td>
@@ -501,8 +978,45 @@ fn EditCharm(#[prop(into)] key: MaybeSignal<uuid::Uuid>) -> impl IntoView {
             <components::Slider
                 attr:id="has-charm"
                 attr:name="countable_charm"
+                attr:disabled=no_charm
+                attr:title=move || no_charm().then_some("There is no shiny charm in this game")
                 checked
             ></components::Slider>
         </td>
     }
 }
+
+#[component]
+fn EditTarget(
+    #[prop(into)] key: MaybeSignal<uuid::Uuid>,
+    error: Signal<Option<String>>,
+) -> impl IntoView {
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let target = create_read_slice(store, move |s| {
+        s.target(&key().into())
+            .map(|t| t.to_string())
+            .unwrap_or_default()
+    });
+
+    view! {
+        <td>
+            <label for="change-target">Shinies needed</label>
+        </td>
+        <td>
+            <div class=style::boxed>
+                <input
+                    type="number"
+                    value=target
+                    prop:value=target
+                    id="change-target"
+                    name="countable_target"
+                    min="1"
+                    max=countable::validate::MAX_TARGET
+                    placeholder="Any"
+                    style:text-align="end"
+                />
+            </div>
+            <FieldError error />
+        </td>
+    }
+}
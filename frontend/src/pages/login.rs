@@ -21,13 +21,6 @@ pub fn LoginPage() -> impl IntoView {
 
     create_effect(move |_| server_resp.track());
 
-    #[cfg(not(feature = "ssr"))]
-    spawn_local(async move {
-        if let Err(err) = indexed::IndexedSaveHandler::reset().await {
-            message_jar.set_err(err)
-        }
-    });
-
     view! {
         <ActionForm action=login_action>
             <div class=style::login_form>
@@ -56,6 +49,18 @@ pub fn LoginPage() -> impl IntoView {
                         <input type="checkbox" name="remember" id="remember" />
                         <label for="remember">Remember Me</label>
                     </div>
+                    <button
+                        type="button"
+                        title="Continue as guest"
+                        aria-label="button-guest"
+                        on:click=move |_| {
+                            if let Err(err) = guest::start() {
+                                message_jar.set_err(err)
+                            }
+                        }
+                    >
+                        <i class="fa-solid fa-user-secret"></i>
+                    </button>
                     <A href="/create-account">
                         <i class="fa-solid fa-user-plus"></i>
                     </A>
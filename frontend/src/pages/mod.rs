@@ -1,15 +1,25 @@
+mod access_tokens;
+mod action_urls;
 mod change_password;
 mod change_username;
 mod create_acc;
+mod custom_methods;
 mod edit;
 mod login;
 mod preferences;
+mod simulate;
+mod statistics;
 
+pub use access_tokens::AccessTokensWindow;
+pub use action_urls::ActionUrlsWindow;
 pub use change_password::ChangePassword;
 pub use change_username::ChangeAccountInfo;
 pub use create_acc::*;
+pub use custom_methods::CustomMethodsWindow;
 pub use edit::*;
 pub use login::*;
 pub use preferences::*;
+pub use simulate::SimulateWindow;
+pub use statistics::AccountStatisticsWindow;
 
 pub(crate) use super::*;
@@ -11,6 +11,7 @@ stylance::import_style!(
 );
 
 use super::*;
+use countable::milestone::{self, Milestones};
 
 #[component]
 pub fn PreferencesWindow() -> impl IntoView {
@@ -135,6 +136,19 @@ pub fn PreferencesWindow() -> impl IntoView {
                         </tr>
 
                         <SaveOnPause />
+                        <PreferenceSlider
+                            label="Start a new phase on success"
+                            id="auto-advance"
+                            get=|p| p.auto_advance
+                            set=|p, c| p.auto_advance = c
+                        />
+                        <PreferenceSlider
+                            label="Name found phases after their shiny"
+                            id="record-found"
+                            get=|p| p.record_found
+                            set=|p, c| p.record_found = c
+                        />
+                        <MilestonePreferences />
 
                         <tr>
                             <td colspan="2">
@@ -203,6 +217,28 @@ pub fn PreferencesWindow() -> impl IntoView {
                                 </A>
                             </td>
                         </tr>
+
+                        <tr class=style::row>
+                            <td>
+                                <span class="title">Access Tokens</span>
+                            </td>
+                            <td>
+                                <A class=style::edit href="/access-tokens">
+                                    <i class="fa-solid fa-arrow-right"></i>
+                                </A>
+                            </td>
+                        </tr>
+
+                        <tr class=style::row>
+                            <td>
+                                <span class="title">Custom Methods</span>
+                            </td>
+                            <td>
+                                <A class=style::edit href="/custom-methods">
+                                    <i class="fa-solid fa-arrow-right"></i>
+                                </A>
+                            </td>
+                        </tr>
                     </table>
 
                     <action-buttons
@@ -256,3 +292,124 @@ fn SaveOnPause() -> impl IntoView {
         </tr>
     }
 }
+
+#[component]
+fn MilestonePreferences() -> impl IntoView {
+    let preferences = expect_context::<RwSignal<Preferences>>();
+    let message = expect_context::<MessageJar>();
+
+    let (notify, set_notify) = create_slice(
+        preferences,
+        |p| p.milestones.notify,
+        |p, n| p.milestones.notify = n,
+    );
+    let on_notify = move |_| {
+        // the browser only asks once, so ask when the user turns it on
+        if !notify() {
+            milestone::request_permission()
+        }
+        set_notify(!notify())
+    };
+
+    let counts = move || {
+        preferences.with(|p| {
+            p.milestones
+                .counts
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+    };
+    let on_counts = move |ev: Event| {
+        let counts = Milestones::parse_counts(&event_target_value(&ev)).and_then(|counts| {
+            let milestones = Milestones {
+                counts,
+                ..preferences.with_untracked(|p| p.milestones.clone())
+            };
+            milestones.validate()?;
+            Ok(milestones.counts)
+        });
+        match counts {
+            Ok(counts) => preferences.update(|p| p.milestones.counts = counts),
+            Err(err) => message.set_err(err),
+        }
+    };
+
+    view! {
+        <tr>
+            <td colspan="2">
+                <hr />
+                <span class="title">Milestones</span>
+            </td>
+        </tr>
+        <PreferenceSlider
+            label="50% chance"
+            id="milestone-half"
+            get=|p| p.milestones.half
+            set=|p, c| p.milestones.half = c
+        />
+        <PreferenceSlider
+            label="1x odds"
+            id="milestone-odds"
+            get=|p| p.milestones.odds
+            set=|p, c| p.milestones.odds = c
+        />
+        <PreferenceSlider
+            label="2x odds"
+            id="milestone-double-odds"
+            get=|p| p.milestones.double_odds
+            set=|p, c| p.milestones.double_odds = c
+        />
+        <tr class=style::text_row>
+            <td>
+                <label for="milestone-counts">Encounters</label>
+            </td>
+            <td class=style::boxed>
+                <input
+                    type="text"
+                    id="milestone-counts"
+                    placeholder="1000, 5000"
+                    prop:value=counts
+                    on:change=on_counts
+                />
+            </td>
+        </tr>
+        <tr class=style::row>
+            <td>
+                <label for="milestone-notify">Browser notifications</label>
+            </td>
+            <td>
+                <Slider
+                    checked=notify
+                    attr:name="preferences[milestones][notify]"
+                    attr:id="milestone-notify"
+                    on:change=on_notify
+                />
+            </td>
+        </tr>
+    }
+}
+
+#[component]
+fn PreferenceSlider(
+    label: &'static str,
+    id: &'static str,
+    get: fn(&Preferences) -> bool,
+    set: fn(&mut Preferences, bool),
+) -> impl IntoView {
+    let preferences = expect_context::<RwSignal<Preferences>>();
+    let (checked, set_checked) = create_slice(preferences, get, set);
+    let on_change = move |_| set_checked(!checked());
+
+    view! {
+        <tr class=style::row>
+            <td>
+                <label for=id>{label}</label>
+            </td>
+            <td>
+                <Slider checked attr:id=id on:change=on_change />
+            </td>
+        </tr>
+    }
+}
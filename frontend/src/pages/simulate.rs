@@ -0,0 +1,411 @@
+#![allow(unused_braces)]
+use components::{MessageJar, Select, SelectOption};
+use leptos::*;
+use leptos_router::{use_params, Params, A};
+use web_sys::SubmitEvent;
+
+stylance::import_style!(
+    #[allow(dead_code)]
+    style,
+    "../../style/edit.module.scss"
+);
+
+use super::*;
+use countable::simulation::{self, Simulation, SimulationParams};
+
+#[derive(Debug, Clone, Params, PartialEq, Eq, Default)]
+struct Key {
+    key: String,
+}
+
+#[component]
+pub fn SimulateWindow() -> impl IntoView {
+    let message = expect_context::<MessageJar>();
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let screen = expect_context::<Screen>();
+
+    let key = create_memo(move |_| {
+        use_params::<Key>()()
+            .ok()
+            .and_then(|p| uuid::Uuid::parse_str(&p.key).ok())
+            .unwrap_or_default()
+    });
+    let name = create_read_slice(store, move |s| s.name(&key().into()));
+    let game = create_read_slice(store, move |s| s.game(&key().into()));
+    let current = create_read_slice(store, move |s| s.recursive_ref().hunttype(&key().into()));
+
+    // the hunt as it is now, the simulated hunts are compared against it
+    let progress = create_read_slice(store, move |s| {
+        s.recursive_ref()
+            .statistics(&key().into())
+            .map(|stats| (stats.count(), stats.successes().max(1)))
+            .unwrap_or((0, 1))
+    });
+    let average_step = create_read_slice(store, move |s| {
+        let key = key().into();
+        let count = s.recursive_ref().count(&key);
+        match count {
+            0 => 0.0,
+            count => s.recursive_ref().time(&key).num_milliseconds() as f64 / count as f64,
+        }
+    });
+
+    let method = create_rw_signal(None::<Hunttype>);
+    let has_charm = create_rw_signal(None::<bool>);
+    let step = create_rw_signal(None::<String>);
+    let runs = create_rw_signal(simulation::DEFAULT_RUNS.to_string());
+
+    let method_value = move || method().unwrap_or(current());
+    let charm_value = move || has_charm().unwrap_or(store().has_charm(&key().into()));
+    let step_value = move || step().unwrap_or(format!("{:.1}", average_step() / 1000.0));
+
+    let simulate_action = create_server_action::<simulation::SimulateHunt>();
+    // milliseconds per encounter the last simulation was run with
+    let simulated_step = create_rw_signal(0.0);
+
+    create_effect(move |_| {
+        if let Some(Err(err)) = simulate_action.value().get() {
+            message.set_err(AppError::from(err))
+        }
+    });
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+
+        let params = (|| {
+            let step = step_value()
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|s| s.is_finite() && *s >= 0.0)
+                .ok_or(AppError::InvalidField(
+                    "time per encounter".into(),
+                    "has to be a positive number of seconds".into(),
+                ))?;
+            let runs = runs.get_untracked().trim().parse::<u32>().map_err(|_| {
+                AppError::InvalidField("runs".into(), "has to be a whole number".into())
+            })?;
+            let hunttype = method_value();
+            let (count, successes) = progress();
+
+            let params = SimulationParams {
+                hunttype,
+                has_charm: charm_value(),
+                odds: store.with_untracked(|s| s.method_odds(hunttype, game())),
+                successes,
+                count,
+                runs,
+            };
+            params.validate()?;
+            Ok::<_, AppError>((params, step * 1000.0))
+        })();
+
+        match params {
+            Ok((params, step)) => {
+                simulated_step.set(step);
+                simulate_action.dispatch(simulation::SimulateHunt { params })
+            }
+            Err(err) => message.set_err(err),
+        }
+    };
+
+    // only the methods of the game can be picked, custom methods fit every game
+    let method_select = move || {
+        let methods = match game() {
+            Some(game) => game.methods(),
+            None => Hunttype::ALL.to_vec(),
+        };
+        let custom = store.with(|s| {
+            s.custom_methods()
+                .iter()
+                .map(|m| (m.uuid, m.name.clone()))
+                .collect::<Vec<_>>()
+        });
+        let option = |hunttype: Hunttype| -> SelectOption {
+            match hunttype {
+                Hunttype::Custom(id) => custom
+                    .iter()
+                    .find(|(uuid, _)| *uuid == id)
+                    .map(|(_, name)| (name.clone(), format!("Custom:{id}")).into())
+                    .unwrap_or(hunttype.into()),
+                _ => hunttype.into(),
+            }
+        };
+        // the current method keeps its values, like the research level of an outbreak
+        let current = untrack(current);
+        let options = methods
+            .iter()
+            .map(|m| if m.same_method(&current) { current } else { *m })
+            .chain(custom.iter().map(|(id, _)| Hunttype::Custom(*id)))
+            .map(&option)
+            .collect::<Vec<_>>();
+
+        view! {
+            <Select
+                attr:id="simulate-method"
+                selected=option(untrack(method_value))
+                options
+                on_change=move |value: String| {
+                    let hunttype = Hunttype::try_from(value).ok().map(|m| {
+                        if m.same_method(&current) { current } else { m }
+                    });
+                    method.set(hunttype)
+                }
+            />
+        }
+    };
+
+    let form_style = move || {
+        stylance::classes!(
+            style::form,
+            match (screen.style)() {
+                ScreenStyle::Portrait => Some(style::portrait),
+                ScreenStyle::Small => Some(style::small),
+                ScreenStyle::Big => Some(style::big),
+            }
+        )
+    };
+
+    view! {
+        <elements::Navbar has_sidebar=false></elements::Navbar>
+        <h1 style:color="white" style:padding="12px 48px">
+            {move || format!("Simulate {}", name())}
+        </h1>
+        <div style:display="flex" style:height="100%" style:justify-content="center">
+            <edit-form class=form_style>
+                <form on:submit=on_submit>
+                    <table class=style::content>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="simulate-method" class="title">
+                                    Method
+                                </label>
+                            </td>
+                            <td class=style::boxed>{method_select}</td>
+                        </tr>
+                        <tr class=style::row>
+                            <td>
+                                <label for="simulate-charm" class="title">
+                                    Has Charm
+                                </label>
+                            </td>
+                            // change events of the slider bubble up from its checkbox
+                            <td on:change=move |ev| {
+                                has_charm.set(Some(event_target_checked(&ev)))
+                            }>
+                                <components::Slider
+                                    attr:id="simulate-charm"
+                                    checked=Signal::derive(charm_value)
+                                ></components::Slider>
+                            </td>
+                        </tr>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="simulate-step" class="title">
+                                    Seconds per encounter
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="number"
+                                    id="simulate-step"
+                                    min="0"
+                                    step="0.1"
+                                    prop:value=step_value
+                                    on:input=move |ev| step.set(Some(event_target_value(&ev)))
+                                />
+                            </td>
+                        </tr>
+                        <tr class=style::text_row>
+                            <td>
+                                <label for="simulate-runs" class="title">
+                                    Simulated hunts
+                                </label>
+                            </td>
+                            <td class=style::boxed>
+                                <input
+                                    type="number"
+                                    id="simulate-runs"
+                                    min="1"
+                                    max=simulation::MAX_RUNS
+                                    prop:value=runs
+                                    on:input=move |ev| runs.set(event_target_value(&ev))
+                                />
+                            </td>
+                        </tr>
+                    </table>
+
+                    {move || {
+                        simulate_action
+                            .value()
+                            .get()
+                            .and_then(Result::ok)
+                            .map(|simulation| {
+                                view! {
+                                    <SimulationResult
+                                        simulation
+                                        count=progress().0
+                                        step=simulated_step.get_untracked()
+                                    />
+                                }
+                            })
+                    }}
+
+                    <action-buttons class=style::action_buttons>
+                        <action-start></action-start>
+                        <action-end>
+                            <A href=move || format!("/{}", key())>
+                                <button type="button">
+                                    <span>Back</span>
+                                </button>
+                            </A>
+                            <button
+                                type="submit"
+                                class=style::confirm
+                                disabled=simulate_action.pending()
+                            >
+                                <span>Simulate</span>
+                            </button>
+                        </action-end>
+                    </action-buttons>
+                </form>
+            </edit-form>
+        </div>
+    }
+}
+
+fn format_time(millis: f64) -> String {
+    let d = chrono::TimeDelta::milliseconds(millis as i64);
+    match d {
+        d if d.num_days() > 0 => format!("{}d {}h", d.num_days(), d.num_hours() % 24),
+        d if d.num_hours() > 0 => format!("{}h {}m", d.num_hours(), d.num_minutes() % 60),
+        d => format!("{}m {}s", d.num_minutes(), d.num_seconds() % 60),
+    }
+}
+
+#[component]
+fn SimulationResult(simulation: Simulation, count: i32, step: f64) -> impl IntoView {
+    let summary = [
+        (
+            "Median",
+            simulation
+                .median
+                .map(|m| format!("{m} encounters, {}", format_time(m as f64 * step))),
+        ),
+        (
+            "Mean",
+            simulation
+                .mean
+                .map(|m| format!("{m:.0} encounters, {}", format_time(m * step))),
+        ),
+        (
+            "Done by now",
+            Some(format!(
+                "{:.1}% of the hunts were done within {count} encounters",
+                simulation.share_done() * 100.0
+            )),
+        ),
+    ];
+    let unfinished = (simulation.unfinished > 0).then(|| {
+        format!(
+            "{} of {} hunts did not finish",
+            simulation.unfinished, simulation.runs
+        )
+    });
+
+    view! {
+        <table class=style::content>
+            {summary
+                .into_iter()
+                .map(|(title, value)| {
+                    view! {
+                        <tr class=style::row>
+                            <td>
+                                <span class="title">{title}</span>
+                            </td>
+                            <td>{value.unwrap_or(String::from("---"))}</td>
+                        </tr>
+                    }
+                })
+                .collect_view()}
+            <tr>
+                <td colspan="2">
+                    <small>{unfinished}</small>
+                </td>
+            </tr>
+        </table>
+        <Histogram
+            title="Encounters"
+            simulation=simulation.clone()
+            marker=count
+            label=|encounters| encounters.to_string()
+        />
+        <Histogram
+            title="Time"
+            simulation
+            marker=count
+            label=move |encounters| format_time(encounters as f64 * step)
+        />
+    }
+}
+
+/// Bars of the simulated hunts with a line where the current hunt is
+#[component]
+fn Histogram<F>(title: &'static str, simulation: Simulation, marker: i32, label: F) -> impl IntoView
+where
+    F: Fn(i32) -> String + 'static,
+{
+    const WIDTH: f64 = 400.0;
+    const HEIGHT: f64 = 120.0;
+
+    let bar = WIDTH / simulation.buckets.len().max(1) as f64;
+    let highest = simulation
+        .buckets
+        .iter()
+        .copied()
+        .max()
+        .unwrap_or_default()
+        .max(1) as f64;
+    let end = simulation.width * simulation.buckets.len() as i32;
+
+    let bars = simulation
+        .buckets
+        .iter()
+        .enumerate()
+        .map(|(idx, amount)| {
+            let height = HEIGHT * *amount as f64 / highest;
+            view! {
+                <rect
+                    x=idx as f64 * bar
+                    y=HEIGHT - height
+                    width=bar * 0.9
+                    height=height
+                    fill="var(--accent, #3584E4)"
+                ></rect>
+            }
+        })
+        .collect_view();
+    let marker_x = (marker as f64 / simulation.width as f64 * bar).min(WIDTH);
+
+    view! {
+        <div style:padding="12px 0px">
+            <span class="title">{title}</span>
+            <svg viewBox=format!("0 0 {WIDTH} {HEIGHT}") width="100%">
+                {bars}
+                <line
+                    x1=marker_x
+                    x2=marker_x
+                    y1="0"
+                    y2=HEIGHT
+                    stroke="#ff9580"
+                    stroke-width="2"
+                ></line>
+            </svg>
+            <div style:display="flex" style:justify-content="space-between">
+                <small>{label(0)}</small>
+                <small>{label(end / 2)}</small>
+                <small>{format!("{}+", label(end))}</small>
+            </div>
+        </div>
+    }
+}
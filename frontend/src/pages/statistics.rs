@@ -0,0 +1,214 @@
+#![allow(unused_braces)]
+use leptos::*;
+use leptos_router::A;
+use std::collections::BTreeMap;
+
+stylance::import_style!(
+    #[allow(dead_code)]
+    style,
+    "../../style/edit.module.scss"
+);
+
+use super::*;
+use countable::{statistics, Hunt, Luck};
+
+/// Hunts shown in the luckiest and unluckiest lists
+const LIST_LENGTH: usize = 5;
+
+/// Hunts, shinies and encounters of a group of phases
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Totals {
+    hunts: usize,
+    shinies: usize,
+    encounters: i64,
+}
+
+impl Totals {
+    fn add(mut self, hunt: &Hunt) -> Self {
+        self.hunts += 1;
+        self.shinies += usize::from(hunt.success);
+        self.encounters += hunt.count as i64;
+        self
+    }
+}
+
+fn totals_by<K: Ord>(hunts: &[Hunt], key: impl Fn(&Hunt) -> K) -> BTreeMap<K, Totals> {
+    hunts.iter().fold(BTreeMap::new(), |mut map, hunt| {
+        let totals = map.entry(key(hunt)).or_default();
+        *totals = totals.add(hunt);
+        map
+    })
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.1}%", value * 100.0)
+}
+
+#[component]
+pub fn AccountStatisticsWindow() -> impl IntoView {
+    let store = expect_context::<RwSignal<CountableStore>>();
+    let screen = expect_context::<Screen>();
+
+    let hunts = create_memo(move |_| store.with(statistics::hunts));
+    let luck = create_memo(move |_| hunts.with(|h| Luck::from_hunts(h)));
+
+    // successful hunts ordered from lucky to unlucky
+    let ranked = create_memo(move |_| {
+        let mut ranked =
+            hunts.with(|h| h.iter().filter(|h| h.success).cloned().collect::<Vec<_>>());
+        ranked.sort_by(|a, b| a.percentile().total_cmp(&b.percentile()));
+        ranked
+    });
+    let luckiest = move || ranked.with(|r| r.iter().take(LIST_LENGTH).cloned().collect::<Vec<_>>());
+    let unluckiest = move || {
+        ranked.with(|r| {
+            r.iter()
+                .rev()
+                .take(LIST_LENGTH)
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let by_method = move || hunts.with(|h| totals_by(h, |hunt| hunt.method.clone()));
+    let by_year = move || hunts.with(|h| totals_by(h, |hunt| hunt.year));
+
+    let form_style = move || {
+        stylance::classes!(
+            style::form,
+            match (screen.style)() {
+                ScreenStyle::Portrait => Some(style::portrait),
+                ScreenStyle::Small => Some(style::small),
+                ScreenStyle::Big => Some(style::big),
+            }
+        )
+    };
+
+    view! {
+        <elements::Navbar has_sidebar=false></elements::Navbar>
+        <h1 style:color="white" style:padding="12px 48px">
+            Statistics
+        </h1>
+        <div style:display="flex" style:height="100%" style:justify-content="center">
+            <edit-form class=form_style>
+                <table class=style::content>
+                    <tr class=style::row>
+                        <td>
+                            <span class="title">Luck score</span>
+                            <br />
+                            <small>"Share of outcomes that would have taken longer"</small>
+                        </td>
+                        <td>
+                            {move || match luck() {
+                                Some(luck) => {
+                                    format!(
+                                        "{} ({} - {}, {} hunts)",
+                                        percent(luck.score),
+                                        percent(luck.low),
+                                        percent(luck.high),
+                                        luck.hunts,
+                                    )
+                                }
+                                None => String::from("No successful hunts yet"),
+                            }}
+                        </td>
+                    </tr>
+
+                    <HuntList title="Luckiest hunts" hunts=Signal::derive(luckiest) />
+                    <HuntList title="Unluckiest hunts" hunts=Signal::derive(unluckiest) />
+
+                    <TotalsList
+                        title="By method"
+                        totals=Signal::derive(move || by_method().into_iter().collect())
+                    />
+                    <TotalsList
+                        title="By year"
+                        totals=Signal::derive(move || {
+                            by_year().into_iter().map(|(year, t)| (year.to_string(), t)).collect()
+                        })
+                    />
+                </table>
+
+                <action-buttons class=style::action_buttons>
+                    <action-start></action-start>
+                    <action-end>
+                        <A href="/">
+                            <button type="button">
+                                <span>Back</span>
+                            </button>
+                        </A>
+                    </action-end>
+                </action-buttons>
+            </edit-form>
+        </div>
+    }
+}
+
+#[component]
+fn HuntList(title: &'static str, hunts: Signal<Vec<Hunt>>) -> impl IntoView {
+    view! {
+        <tr>
+            <td colspan="2">
+                <hr />
+                <span class="title">{title}</span>
+            </td>
+        </tr>
+        {move || {
+            hunts()
+                .into_iter()
+                .map(|hunt| {
+                    let details = format!(
+                        "{} - {} encounters at 1/{:.0}",
+                        hunt.method,
+                        hunt.count,
+                        hunt.odds,
+                    );
+                    let link = format!("/{}", uuid::Uuid::from(hunt.key));
+                    let percentile = percent(hunt.percentile());
+                    view! {
+                        <tr class=style::row>
+                            <td>
+                                <A href=link>{hunt.name}</A>
+                                <br />
+                                <small>{details}</small>
+                            </td>
+                            <td>{percentile}</td>
+                        </tr>
+                    }
+                })
+                .collect_view()
+        }}
+    }
+}
+
+#[component]
+fn TotalsList(title: &'static str, totals: Signal<Vec<(String, Totals)>>) -> impl IntoView {
+    view! {
+        <tr>
+            <td colspan="2">
+                <hr />
+                <span class="title">{title}</span>
+            </td>
+        </tr>
+        {move || {
+            totals()
+                .into_iter()
+                .map(|(name, totals)| {
+                    view! {
+                        <tr class=style::row>
+                            <td>{name}</td>
+                            <td>
+                                {format!(
+                                    "{} hunts, {} shinies, {} encounters",
+                                    totals.hunts,
+                                    totals.shinies,
+                                    totals.encounters,
+                                )}
+                            </td>
+                        </tr>
+                    }
+                })
+                .collect_view()
+        }}
+    }
+}
@@ -2,6 +2,7 @@ use leptos::*;
 use serde::{Deserialize, Serialize};
 
 use super::*;
+use countable::milestone::Milestones;
 
 pub type PrefResource = Resource<UserSession, Result<Preferences, ServerFnError>>;
 
@@ -40,6 +41,11 @@ pub struct Preferences {
     pub show_separator: bool,
     pub multi_select: bool,
     pub save_on_pause: bool,
+    pub milestones: Milestones,
+    /// a phase that is marked as success is followed by a new one
+    pub auto_advance: bool,
+    /// the found phase is named after the shiny it found when advancing
+    pub record_found: bool,
 }
 
 impl Preferences {
@@ -51,6 +57,9 @@ impl Preferences {
             show_separator: false,
             multi_select: false,
             save_on_pause: true,
+            milestones: Milestones::default(),
+            auto_advance: false,
+            record_found: false,
         }
     }
 }
@@ -67,6 +76,15 @@ impl Preferences {
             show_separator: value.show_separator,
             multi_select: value.multi_select,
             save_on_pause: value.save_on_pause,
+            milestones: Milestones {
+                half: value.milestone_half,
+                odds: value.milestone_odds,
+                double_odds: value.milestone_double_odds,
+                counts: value.milestone_counts,
+                notify: value.milestone_notify,
+            },
+            auto_advance: value.auto_advance,
+            record_found: value.record_found,
         }
     }
 }
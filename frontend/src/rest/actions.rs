@@ -0,0 +1,79 @@
+//! Action urls are public, the secret in the path is the only credential
+
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/actions/{secret}", web::get().to(open))
+        .route("/actions/{secret}", web::post().to(trigger));
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionResult {
+    pub action: String,
+    /// phase the action was applied to,
+    /// for a counter this is its newest phase
+    pub phase: phases::ApiPhase,
+    pub timer_running: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenQuery {
+    /// `false` applies the action right away
+    #[serde(default = "confirm_by_default")]
+    pub confirm: bool,
+}
+
+fn confirm_by_default() -> bool {
+    true
+}
+
+/// Link previews and prefetching only ever `GET` a url,
+/// so opening it shows a button that sends the `POST` instead of applying the action.
+/// Hardware buttons and stream decks can often only open a url,
+/// they add `?confirm=false` to apply the action with the `GET` itself.
+async fn open(
+    pool: Pool,
+    secret: web::Path<String>,
+    query: web::Query<OpenQuery>,
+) -> ApiResult<HttpResponse> {
+    if !query.confirm {
+        return trigger(pool, secret).await;
+    }
+
+    // secrets are alphanumeric, anything else can not be one and is not put into the page
+    if !secret.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(ApiError::not_found("Unknown action url"));
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html>
+<head><meta name="viewport" content="width=device-width, initial-scale=1"><title>TallyWeb action</title></head>
+<body>
+<form method="post" action="/api/v1/actions/{secret}">
+<button type="submit">Apply action</button>
+</form>
+</body>
+</html>"#
+        )))
+}
+
+async fn trigger(pool: Pool, secret: web::Path<String>) -> ApiResult<HttpResponse> {
+    let outcome = backend::action_url::trigger(&pool, &secret)
+        .await
+        .map_err(|err| match err {
+            backend::BackendError::InvalidToken => ApiError::not_found("Unknown action url"),
+            err => err.into(),
+        })?;
+
+    Ok(HttpResponse::Ok().json(ActionResult {
+        action: outcome.action.to_string(),
+        phase: outcome.phase.into(),
+        timer_running: outcome.timer_running,
+    }))
+}
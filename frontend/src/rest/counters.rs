@@ -0,0 +1,338 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/counters")
+            .route("", web::get().to(list))
+            .route("", web::post().to(create))
+            .route("/{id}", web::get().to(get))
+            .route("/{id}", web::patch().to(update))
+            .route("/{id}/increment", web::post().to(increment))
+            .route("/{id}/time", web::post().to(add_time))
+            .route("/{id}/archive", web::post().to(archive))
+            .route("/{id}/phases", web::get().to(list_phases)),
+    );
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiCounter {
+    pub uuid: uuid::Uuid,
+    pub name: String,
+    /// sum of the count of all phases
+    pub count: i32,
+    /// sum of the time of all phases in milliseconds
+    pub time: i64,
+    pub phases: Vec<uuid::Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_edit: chrono::NaiveDateTime,
+    pub is_deleted: bool,
+    /// amount of shinies the hunt needs
+    pub target: Option<i32>,
+}
+
+impl ApiCounter {
+    async fn load(tx: &mut backend::PgTx, counter: backend::DbCounter) -> ApiResult<Self> {
+        let phases = backend::counter::get_children(tx, counter.uuid).await?;
+
+        Ok(Self {
+            uuid: counter.uuid,
+            name: counter.name,
+            count: phases.iter().map(|p| p.count).sum(),
+            time: phases.iter().map(|p| p.time).sum(),
+            phases: phases.into_iter().map(|p| p.uuid).collect(),
+            created_at: counter.created_at,
+            last_edit: counter.last_edit,
+            is_deleted: counter.is_deleted,
+            target: counter.target,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListQuery {
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewCounter {
+    pub name: String,
+    pub hunt_type: Option<String>,
+    pub has_charm: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CounterPatch {
+    pub name: Option<String>,
+    pub count: Option<i32>,
+    pub time: Option<i64>,
+    pub hunt_type: Option<String>,
+    pub has_charm: Option<bool>,
+    pub step_size: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Increment {
+    /// defaults to the step size of the newest phase, can be negative
+    pub amount: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddTime {
+    /// milliseconds, can be negative
+    pub millis: i64,
+}
+
+/// Fetches counter `id` and checks that it belongs to `session`
+async fn owned(
+    tx: &mut backend::PgTx,
+    session: &UserSession,
+    id: uuid::Uuid,
+) -> ApiResult<backend::DbCounter> {
+    let counter = backend::counter::get(tx, id).await?;
+    check_owner(session, counter.owner_uuid)?;
+    Ok(counter)
+}
+
+async fn respond(mut tx: backend::PgTx, id: uuid::Uuid) -> ApiResult<HttpResponse> {
+    let counter = backend::counter::get(&mut tx, id).await?;
+    let counter = ApiCounter::load(&mut tx, counter).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(counter))
+}
+
+async fn list(
+    req: HttpRequest,
+    pool: Pool,
+    query: web::Query<ListQuery>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Read)?;
+    let mut tx = pool.begin().await?;
+
+    let mut counters = Vec::new();
+    for counter in backend::counter::all_by_user(&mut tx, session.user_uuid).await? {
+        if counter.is_deleted && !query.archived {
+            continue;
+        }
+        counters.push(ApiCounter::load(&mut tx, counter).await?);
+    }
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(counters))
+}
+
+async fn get(req: HttpRequest, pool: Pool, id: web::Path<uuid::Uuid>) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Read)?;
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+
+    respond(tx, *id).await
+}
+
+/// Creates a counter together with its first phase
+async fn create(
+    req: HttpRequest,
+    pool: Pool,
+    body: web::Json<NewCounter>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Edit)?;
+    let body = body.into_inner();
+
+    validate::name(body.name.trim())?;
+    let hunt_type = body
+        .hunt_type
+        .map(phases::parse_hunttype)
+        .transpose()?
+        .unwrap_or(backend::Hunttype::NewOdds);
+
+    let now = chrono::Utc::now().naive_utc();
+    let counter = backend::DbCounter {
+        uuid: uuid::Uuid::new_v4(),
+        owner_uuid: session.user_uuid,
+        name: body.name.trim().to_string(),
+        created_at: now,
+        last_edit: now,
+        is_deleted: false,
+        revision: 0,
+        target: None,
+    };
+    let phase = backend::DbPhase {
+        uuid: uuid::Uuid::new_v4(),
+        owner_uuid: session.user_uuid,
+        parent_uuid: counter.uuid,
+        name: String::from("Phase 1"),
+        count: 0,
+        time: 0,
+        hunt_type,
+        game: None,
+        research_level: 0,
+        outbreak_cleared: 0,
+        sparkling_power: 0,
+        custom_method: None,
+        has_charm: body.has_charm.unwrap_or_default(),
+        dexnav_encounters: None,
+        success: false,
+        created_at: now,
+        last_edit: now,
+        is_deleted: false,
+        step_size: 1,
+        revision: 0,
+    };
+    validate_phase(&phase)?;
+
+    let id = counter.uuid;
+    let mut tx = pool.begin().await?;
+    backend::counter::update(&mut tx, counter).await?;
+    backend::phase::update(&mut tx, phase).await?;
+
+    let counter = backend::counter::get(&mut tx, id).await?;
+    let counter = ApiCounter::load(&mut tx, counter).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(counter))
+}
+
+async fn update(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+    body: web::Json<CounterPatch>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Edit)?;
+    let body = body.into_inner();
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+
+    if let Some(name) = body.name {
+        validate::name(name.trim())?;
+        backend::counter::set_name(&mut tx, *id, name.trim()).await?;
+    }
+    if let Some(count) = body.count {
+        validate::count(count)?;
+        backend::counter::set_count(&mut tx, *id, count).await?;
+    }
+    if let Some(time) = body.time {
+        validate::time(chrono::TimeDelta::milliseconds(time))?;
+        backend::counter::set_time(&mut tx, *id, time).await?;
+    }
+    if let Some(hunt_type) = body.hunt_type {
+        let hunt_type = phases::parse_hunttype(hunt_type)?;
+        // every phase has to keep a method its game offers
+        for mut phase in backend::counter::get_children(&mut tx, *id).await? {
+            phase.hunt_type = hunt_type.clone();
+            validate_phase(&phase)?;
+        }
+        backend::counter::set_hunttype(&mut tx, *id, hunt_type).await?;
+    }
+    if let Some(has_charm) = body.has_charm {
+        backend::counter::set_charm(&mut tx, *id, has_charm).await?;
+    }
+    if let Some(step_size) = body.step_size {
+        validate::step_size(step_size)?;
+        backend::counter::set_step(&mut tx, *id, step_size).await?;
+    }
+    backend::counter::edited(&mut tx, *id).await?;
+
+    respond(tx, *id).await
+}
+
+/// Adds to the newest phase of the counter
+async fn increment(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+    body: Option<web::Json<Increment>>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Count)?;
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+
+    let children = backend::counter::get_children(&mut tx, *id).await?;
+    let Some(phase) = children.last() else {
+        return Err(ApiError::bad_request("Counter has no phases"));
+    };
+
+    let amount = body.amount.unwrap_or(phase.step_size);
+    backend::operation::record(
+        &mut tx,
+        session.user_uuid,
+        *id,
+        backend::operation::OperationKind::AddCount(amount),
+    )
+    .await?;
+    backend::counter::edited(&mut tx, *id).await?;
+
+    respond(tx, *id).await
+}
+
+/// Adds to the time of the newest phase of the counter
+async fn add_time(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+    body: web::Json<AddTime>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Count)?;
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+
+    if backend::counter::get_children(&mut tx, *id)
+        .await?
+        .is_empty()
+    {
+        return Err(ApiError::bad_request("Counter has no phases"));
+    }
+
+    backend::operation::record(
+        &mut tx,
+        session.user_uuid,
+        *id,
+        backend::operation::OperationKind::AddTime(body.millis),
+    )
+    .await?;
+    backend::counter::edited(&mut tx, *id).await?;
+
+    respond(tx, *id).await
+}
+
+async fn archive(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Edit)?;
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+    backend::counter::archive(&mut tx, *id).await?;
+
+    respond(tx, *id).await
+}
+
+async fn list_phases(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Read)?;
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+    let phases = backend::counter::get_children(&mut tx, *id)
+        .await?
+        .into_iter()
+        .map(phases::ApiPhase::from)
+        .collect::<Vec<_>>();
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(phases))
+}
@@ -0,0 +1,90 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+
+/// Error returned by every `/api/v1` endpoint,
+/// serialized as `{ "error": "<code>", "message": "<description>" }`
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    #[serde(skip)]
+    pub status: StatusCode,
+    pub error: &'static str,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, error: &'static str, message: impl ToString) -> Self {
+        Self {
+            status,
+            error,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn bad_request(message: impl ToString) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn unauthorized(message: impl ToString) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: impl ToString) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    pub fn not_found(message: impl ToString) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.error, self.message)
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status).json(self)
+    }
+}
+
+impl From<backend::BackendError> for ApiError {
+    fn from(value: backend::BackendError) -> Self {
+        use backend::BackendError as BE;
+
+        match value {
+            BE::CounterNotFound | BE::UserNotFound | BE::DataNotFound(_) => Self::not_found(value),
+            BE::DatabaseError(sqlx::Error::RowNotFound) => Self::not_found(value),
+            BE::Unauthorized => Self::forbidden(value),
+            BE::InvalidToken
+            | BE::InvalidSecrets
+            | BE::InvalidPassword
+            | BE::InvalidUsername
+            | BE::Login(_) => Self::unauthorized(value),
+            BE::UserExists | BE::Conflict(_) => Self::new(StatusCode::CONFLICT, "conflict", value),
+            BE::DatabaseError(_) | BE::Internal(_) => {
+                Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", value)
+            }
+        }
+    }
+}
+
+impl From<crate::AppError> for ApiError {
+    fn from(value: crate::AppError) -> Self {
+        match value {
+            crate::AppError::InvalidField(..) => Self::bad_request(value),
+            _ => Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal", value),
+        }
+    }
+}
+
+impl From<sqlx::Error> for ApiError {
+    fn from(value: sqlx::Error) -> Self {
+        backend::BackendError::from(value).into()
+    }
+}
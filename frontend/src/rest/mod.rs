@@ -0,0 +1,144 @@
+//! Versioned JSON api mounted under `/api/v1`
+//!
+//! Unlike the leptos server functions these endpoints are meant for third party use,
+//! their request and response types only change together with the version prefix.
+//! Requests are authenticated by [`super::middleware::CheckSession`] using either
+//! the `session` cookie or an `Authorization: Bearer` personal access token,
+//! except for the action urls under `/api/v1/actions` which carry their own secret.
+//! The OpenAPI document is available at `/api/v1/openapi.json`.
+
+use actix_web::{web, HttpMessage, HttpRequest};
+use backend::access_token::{DbAccessToken, TokenScope};
+
+use super::{countable::validate, middleware as mw, UserSession};
+
+mod actions;
+mod counters;
+mod error;
+mod openapi;
+mod phases;
+mod preferences;
+
+pub use error::ApiError;
+
+pub type Pool = web::Data<backend::PgPool>;
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// Registers all `/api/v1` routes, mount this before the generic `/api` scope
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1")
+            .app_data(
+                web::JsonConfig::default()
+                    .error_handler(|err, _| ApiError::bad_request(err).into()),
+            )
+            .app_data(
+                web::PathConfig::default().error_handler(|err, _| ApiError::not_found(err).into()),
+            )
+            .app_data(
+                web::QueryConfig::default()
+                    .error_handler(|err, _| ApiError::bad_request(err).into()),
+            )
+            .route("/openapi.json", web::get().to(openapi::document))
+            .configure(actions::config)
+            .service(
+                web::scope("")
+                    .wrap(mw::CheckSession)
+                    .configure(counters::config)
+                    .configure(phases::config)
+                    .configure(preferences::config)
+                    .default_service(web::to(|| async {
+                        Err::<actix_web::HttpResponse, _>(ApiError::not_found("Unknown endpoint"))
+                    })),
+            ),
+    );
+}
+
+/// Returns the session set by [`mw::CheckSession`],
+/// requests using an access token additionally need to carry `scope`
+pub fn authorize(req: &HttpRequest, scope: TokenScope) -> ApiResult<UserSession> {
+    let extensions = req.extensions();
+
+    if let Some(token) = extensions.get::<DbAccessToken>() {
+        if !token.has_scope(scope) {
+            return Err(ApiError::forbidden(format!(
+                "Access token is missing the `{scope}` scope"
+            )));
+        }
+    }
+
+    extensions
+        .get::<UserSession>()
+        .cloned()
+        .ok_or(ApiError::unauthorized("Missing session"))
+}
+
+/// Checks the values of `phase` with the limits the edit page uses,
+/// the step size is only checked when a request sets it as older phases may still have 0
+fn validate_phase(phase: &backend::DbPhase) -> ApiResult<()> {
+    validate::countable(&phase.clone().into())?;
+    Ok(())
+}
+
+/// Errors with `Unauthorized` when `owner` is not the user making the request
+fn check_owner(session: &UserSession, owner: uuid::Uuid) -> ApiResult<()> {
+    if session.user_uuid != owner {
+        Err(backend::BackendError::Unauthorized)?
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::StatusCode, test::TestRequest};
+
+    use super::*;
+
+    fn request(scopes: Option<&[TokenScope]>) -> HttpRequest {
+        let req = TestRequest::default().to_http_request();
+        let session = UserSession {
+            user_uuid: uuid::Uuid::new_v4(),
+            username: String::from("user"),
+            token: uuid::Uuid::new_v4(),
+        };
+        if let Some(scopes) = scopes {
+            req.extensions_mut().insert(DbAccessToken {
+                uuid: session.token,
+                user_uuid: session.user_uuid,
+                name: String::from("Token"),
+                scopes: scopes.iter().map(|s| s.to_string()).collect(),
+                created_at: chrono::Utc::now().naive_utc(),
+                expire_on: None,
+                last_used: None,
+            });
+        }
+        req.extensions_mut().insert(session);
+        req
+    }
+
+    #[test]
+    fn count_tokens_can_not_edit() {
+        let req = request(Some(&[TokenScope::Count]));
+
+        assert!(authorize(&req, TokenScope::Read).is_ok());
+        assert!(authorize(&req, TokenScope::Count).is_ok());
+        let err = authorize(&req, TokenScope::Edit).unwrap_err();
+        assert_eq!(err.status, StatusCode::FORBIDDEN);
+        assert!(err.message.contains("edit"));
+    }
+
+    #[test]
+    fn sessions_without_token_pass_every_scope() {
+        let req = request(None);
+        assert!(authorize(&req, TokenScope::Edit).is_ok());
+        assert!(authorize(&req, TokenScope::Admin).is_ok());
+    }
+
+    #[test]
+    fn requests_need_a_session() {
+        let req = TestRequest::default().to_http_request();
+        let err = authorize(&req, TokenScope::Read).unwrap_err();
+        assert_eq!(err.status, StatusCode::UNAUTHORIZED);
+    }
+}
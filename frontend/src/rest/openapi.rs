@@ -0,0 +1,487 @@
+use actix_web::HttpResponse;
+use serde_json::{json, Value};
+
+use crate::countable::validate;
+
+/// Serves the OpenAPI 3 description of the `/api/v1` routes
+pub async fn document() -> HttpResponse {
+    HttpResponse::Ok().json(spec())
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+fn ok(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": json_content(schema) })
+}
+
+fn error(description: &str) -> Value {
+    json!({ "description": description, "content": json_content(schema_ref("Error")) })
+}
+
+/// Responses shared by every authenticated endpoint, `extra` is merged on top
+fn responses(extra: Value) -> Value {
+    let mut responses = json!({
+        "400": error("Malformed request or a value outside of its limits"),
+        "401": error("Missing or invalid session or access token"),
+        "403": error("Access token is missing the required scope or the data belongs to another user"),
+        "500": error("Internal server error"),
+    });
+
+    if let (Some(base), Value::Object(extra)) = (responses.as_object_mut(), extra) {
+        base.extend(extra);
+    }
+
+    responses
+}
+
+fn operation(summary: &str, scope: &str, tag: &str, responses_extra: Value) -> Value {
+    json!({
+        "summary": summary,
+        "tags": [tag],
+        "description": format!("Requires the `{scope}` scope when using an access token."),
+        "responses": responses(responses_extra),
+    })
+}
+
+fn with_body(mut op: Value, schema: &str, required: bool) -> Value {
+    op["requestBody"] = json!({
+        "required": required,
+        "content": json_content(schema_ref(schema)),
+    });
+    op
+}
+
+fn id_param(description: &str) -> Value {
+    json!([{
+        "name": "id",
+        "in": "path",
+        "required": true,
+        "description": description,
+        "schema": { "type": "string", "format": "uuid" },
+    }])
+}
+
+fn archived_param() -> Value {
+    json!([{
+        "name": "archived",
+        "in": "query",
+        "required": false,
+        "description": "Include archived entries",
+        "schema": { "type": "boolean", "default": false },
+    }])
+}
+
+fn hunt_types() -> Value {
+    json!([
+        "OldOdds",
+        "NewOdds",
+        "SOS",
+        "DexNav",
+        "MasudaGenIV",
+        "MasudaGenV",
+        "MasudaGenVI",
+        "MassOutbreak",
+        "MassiveMassOutbreak",
+        "Outbreak",
+        "ChainFishing",
+        "Horde",
+        "RadarGenIV",
+        "RadarGenVI",
+        "RadarBDSP"
+    ])
+}
+
+fn paths() -> Value {
+    let counter = || schema_ref("Counter");
+    let phase = || schema_ref("Phase");
+    let counters = || json!({ "type": "array", "items": schema_ref("Counter") });
+    let phases = || json!({ "type": "array", "items": schema_ref("Phase") });
+
+    let mut list_counters = operation(
+        "List counters",
+        "read",
+        "counters",
+        json!({ "200": ok("All counters of the user", counters()) }),
+    );
+    list_counters["parameters"] = archived_param();
+
+    let mut list_phases = operation(
+        "List phases",
+        "read",
+        "phases",
+        json!({ "200": ok("All phases of the user", phases()) }),
+    );
+    list_phases["parameters"] = archived_param();
+
+    let confirm_action = json!({
+        "summary": "Page with a button that applies the action url",
+        "tags": ["actions"],
+        "description": "Opening an action url does not change anything, \
+            so link previews and prefetching can not count. The page sends the `POST` instead. \
+            Devices that can only open a url set `confirm` to `false` to apply the action directly.",
+        "security": [],
+        "parameters": [{
+            "name": "secret",
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" },
+        }, {
+            "name": "confirm",
+            "in": "query",
+            "required": false,
+            "schema": { "type": "boolean", "default": true },
+        }],
+        "responses": {
+            "200": {
+                "description": "HTML page with a button to apply the action, \
+                    or the phase after applying the action when `confirm` is `false`",
+                "content": {
+                    "text/html": {},
+                    "application/json": { "schema": schema_ref("ActionResult") },
+                },
+            },
+            "404": error("The secret can not be an action url"),
+            "500": error("Internal server error"),
+        },
+    });
+    let action = json!({
+        "summary": "Apply the increment, decrement or timer toggle of an action url",
+        "tags": ["actions"],
+        "description": "Action urls are created per counter or phase from its context menu, \
+            the secret in the path replaces any other authentication.",
+        "security": [],
+        "parameters": [{
+            "name": "secret",
+            "in": "path",
+            "required": true,
+            "schema": { "type": "string" },
+        }],
+        "responses": {
+            "200": ok("The phase after applying the action", schema_ref("ActionResult")),
+            "404": error("No action url with this secret exists"),
+            "500": error("Internal server error"),
+        },
+    });
+
+    let counter_action = |summary: &str, scope: &str| {
+        operation(
+            summary,
+            scope,
+            "counters",
+            json!({
+                "200": ok("The updated counter", counter()),
+                "404": error("Counter does not exist"),
+            }),
+        )
+    };
+    let phase_action = |summary: &str, scope: &str| {
+        operation(
+            summary,
+            scope,
+            "phases",
+            json!({
+                "200": ok("The updated phase", phase()),
+                "404": error("Phase does not exist"),
+            }),
+        )
+    };
+
+    json!({
+        "/counters": {
+            "get": list_counters,
+            "post": with_body(operation(
+                "Create a counter with a single phase",
+                "edit",
+                "counters",
+                json!({ "201": ok("The new counter", counter()) }),
+            ), "NewCounter", true),
+        },
+        "/counters/{id}": {
+            "parameters": id_param("Counter uuid"),
+            "get": operation(
+                "Get a counter",
+                "read",
+                "counters",
+                json!({ "200": ok("The counter", counter()), "404": error("Counter does not exist") }),
+            ),
+            "patch": with_body(
+                counter_action("Update a counter, `count` and `time` are applied to the newest phases first", "edit"),
+                "CounterPatch",
+                true,
+            ),
+        },
+        "/counters/{id}/increment": {
+            "parameters": id_param("Counter uuid"),
+            "post": with_body(counter_action("Add to the count of the newest phase", "count"), "Increment", false),
+        },
+        "/counters/{id}/time": {
+            "parameters": id_param("Counter uuid"),
+            "post": with_body(counter_action("Add to the time of the newest phase", "count"), "AddTime", true),
+        },
+        "/counters/{id}/archive": {
+            "parameters": id_param("Counter uuid"),
+            "post": counter_action("Archive a counter and all of its phases", "edit"),
+        },
+        "/counters/{id}/phases": {
+            "parameters": id_param("Counter uuid"),
+            "get": operation(
+                "List the phases of a counter, oldest first",
+                "read",
+                "counters",
+                json!({ "200": ok("Phases of the counter", phases()), "404": error("Counter does not exist") }),
+            ),
+        },
+        "/phases": {
+            "get": list_phases,
+            "post": with_body(operation(
+                "Add a phase to a counter",
+                "edit",
+                "phases",
+                json!({ "201": ok("The new phase", phase()), "404": error("Parent counter does not exist") }),
+            ), "NewPhase", true),
+        },
+        "/phases/{id}": {
+            "parameters": id_param("Phase uuid"),
+            "get": operation(
+                "Get a phase",
+                "read",
+                "phases",
+                json!({ "200": ok("The phase", phase()), "404": error("Phase does not exist") }),
+            ),
+            "patch": with_body(phase_action("Update a phase", "edit"), "PhasePatch", true),
+        },
+        "/phases/{id}/increment": {
+            "parameters": id_param("Phase uuid"),
+            "post": with_body(phase_action("Add to the count of a phase", "count"), "Increment", false),
+        },
+        "/phases/{id}/time": {
+            "parameters": id_param("Phase uuid"),
+            "post": with_body(phase_action("Add to the time of a phase", "count"), "AddTime", true),
+        },
+        "/phases/{id}/archive": {
+            "parameters": id_param("Phase uuid"),
+            "post": phase_action("Archive a phase", "edit"),
+        },
+        "/actions/{secret}": {
+            "get": confirm_action,
+            "post": action,
+        },
+        "/preferences": {
+            "get": operation(
+                "Get the preferences of the user",
+                "read",
+                "preferences",
+                json!({ "200": ok("The preferences", schema_ref("Preferences")) }),
+            ),
+            "patch": with_body(operation(
+                "Update the preferences of the user",
+                "admin",
+                "preferences",
+                json!({ "200": ok("The updated preferences", schema_ref("Preferences")) }),
+            ), "PreferencesPatch", true),
+        },
+    })
+}
+
+fn schemas() -> Value {
+    let uuid = || json!({ "type": "string", "format": "uuid" });
+    let date =
+        || json!({ "type": "string", "format": "date-time", "description": "UTC without offset" });
+    let hunt_type = || json!({ "type": "string", "enum": hunt_types() });
+    let name =
+        || json!({ "type": "string", "minLength": 1, "maxLength": validate::MAX_NAME_LENGTH });
+    let step_size =
+        || json!({ "type": "integer", "minimum": 1, "maximum": validate::MAX_STEP_SIZE });
+
+    json!({
+        "Error": {
+            "type": "object",
+            "required": ["error", "message"],
+            "properties": {
+                "error": {
+                    "type": "string",
+                    "enum": ["bad_request", "unauthorized", "forbidden", "not_found", "conflict", "internal"],
+                },
+                "message": { "type": "string" },
+            },
+        },
+        "Counter": {
+            "type": "object",
+            "properties": {
+                "uuid": uuid(),
+                "name": { "type": "string" },
+                "count": { "type": "integer", "description": "Sum of all phases" },
+                "time": { "type": "integer", "format": "int64", "description": "Sum of all phases in milliseconds" },
+                "phases": { "type": "array", "items": uuid(), "description": "Oldest first" },
+                "created_at": date(),
+                "last_edit": date(),
+                "is_deleted": { "type": "boolean" },
+            },
+        },
+        "Phase": {
+            "type": "object",
+            "properties": {
+                "uuid": uuid(),
+                "parent_uuid": uuid(),
+                "name": { "type": "string" },
+                "count": { "type": "integer" },
+                "time": { "type": "integer", "format": "int64", "description": "Milliseconds" },
+                "hunt_type": hunt_type(),
+                "has_charm": { "type": "boolean" },
+                "step_size": { "type": "integer" },
+                "success": { "type": "boolean" },
+                "dexnav_encounters": { "type": "integer", "nullable": true },
+                "created_at": date(),
+                "last_edit": date(),
+                "is_deleted": { "type": "boolean" },
+            },
+        },
+        "ActionResult": {
+            "type": "object",
+            "properties": {
+                "action": { "type": "string", "enum": ["increment", "decrement", "toggle_timer"] },
+                "phase": schema_ref("Phase"),
+                "timer_running": { "type": "boolean" },
+            },
+        },
+        "Preferences": {
+            "type": "object",
+            "properties": {
+                "use_default_accent_color": { "type": "boolean" },
+                "accent_color": { "type": "string", "nullable": true, "example": "#a1b2c3" },
+                "show_separator": { "type": "boolean" },
+                "multi_select": { "type": "boolean" },
+                "save_on_pause": { "type": "boolean" },
+                "milestone_half": { "type": "boolean" },
+                "milestone_odds": { "type": "boolean" },
+                "milestone_double_odds": { "type": "boolean" },
+                "milestone_counts": { "type": "array", "items": { "type": "integer" } },
+                "milestone_notify": { "type": "boolean" },
+                "auto_advance": { "type": "boolean" },
+                "record_found": { "type": "boolean" },
+            },
+        },
+        "NewCounter": {
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": name(),
+                "hunt_type": hunt_type(),
+                "has_charm": { "type": "boolean" },
+            },
+        },
+        "CounterPatch": {
+            "type": "object",
+            "properties": {
+                "name": name(),
+                "count": { "type": "integer", "minimum": 0 },
+                "time": { "type": "integer", "format": "int64", "minimum": 0 },
+                "hunt_type": hunt_type(),
+                "has_charm": { "type": "boolean" },
+                "step_size": step_size(),
+            },
+        },
+        "NewPhase": {
+            "type": "object",
+            "required": ["parent_uuid"],
+            "description": "Omitted fields are copied from the newest phase of the parent",
+            "properties": {
+                "parent_uuid": uuid(),
+                "name": name(),
+                "hunt_type": hunt_type(),
+                "has_charm": { "type": "boolean" },
+                "step_size": step_size(),
+            },
+        },
+        "PhasePatch": {
+            "type": "object",
+            "properties": {
+                "name": name(),
+                "count": { "type": "integer", "minimum": 0 },
+                "time": { "type": "integer", "format": "int64", "minimum": 0 },
+                "hunt_type": hunt_type(),
+                "has_charm": { "type": "boolean" },
+                "step_size": step_size(),
+                "success": { "type": "boolean" },
+            },
+        },
+        "Increment": {
+            "type": "object",
+            "properties": {
+                "amount": {
+                    "type": "integer",
+                    "description": "Defaults to the step size of the phase, negative values decrement",
+                },
+            },
+        },
+        "AddTime": {
+            "type": "object",
+            "required": ["millis"],
+            "properties": {
+                "millis": {
+                    "type": "integer",
+                    "format": "int64",
+                    "description": "Milliseconds to add, negative values subtract",
+                },
+            },
+        },
+        "PreferencesPatch": {
+            "type": "object",
+            "properties": {
+                "use_default_accent_color": { "type": "boolean" },
+                "accent_color": { "type": "string", "example": "#a1b2c3" },
+                "show_separator": { "type": "boolean" },
+                "multi_select": { "type": "boolean" },
+                "save_on_pause": { "type": "boolean" },
+                "milestone_half": { "type": "boolean" },
+                "milestone_odds": { "type": "boolean" },
+                "milestone_double_odds": { "type": "boolean" },
+                "milestone_counts": { "type": "array", "items": { "type": "integer" } },
+                "milestone_notify": { "type": "boolean" },
+                "auto_advance": { "type": "boolean" },
+                "record_found": { "type": "boolean" },
+            },
+        },
+    })
+}
+
+pub fn spec() -> Value {
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "TallyWeb API",
+            "version": crate::TALLYWEB_VERSION,
+            "description": "Access counters, phases and preferences of a TallyWeb account.",
+        },
+        "servers": [{ "url": "/api/v1" }],
+        "security": [{ "bearer": [] }, { "session": [] }],
+        "tags": [
+            { "name": "counters" },
+            { "name": "phases" },
+            { "name": "preferences" },
+            { "name": "actions" },
+        ],
+        "paths": paths(),
+        "components": {
+            "schemas": schemas(),
+            "securitySchemes": {
+                "bearer": {
+                    "type": "http",
+                    "scheme": "bearer",
+                    "description": "Personal access token created on the account settings page",
+                },
+                "session": {
+                    "type": "apiKey",
+                    "in": "cookie",
+                    "name": "session",
+                },
+            },
+        },
+    })
+}
@@ -0,0 +1,319 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/phases")
+            .route("", web::get().to(list))
+            .route("", web::post().to(create))
+            .route("/{id}", web::get().to(get))
+            .route("/{id}", web::patch().to(update))
+            .route("/{id}/increment", web::post().to(increment))
+            .route("/{id}/time", web::post().to(add_time))
+            .route("/{id}/archive", web::post().to(archive)),
+    );
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiPhase {
+    pub uuid: uuid::Uuid,
+    pub parent_uuid: uuid::Uuid,
+    pub name: String,
+    pub count: i32,
+    /// milliseconds
+    pub time: i64,
+    pub hunt_type: String,
+    pub has_charm: bool,
+    pub step_size: i32,
+    pub success: bool,
+    pub dexnav_encounters: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+    pub last_edit: chrono::NaiveDateTime,
+    pub is_deleted: bool,
+}
+
+impl From<backend::DbPhase> for ApiPhase {
+    fn from(value: backend::DbPhase) -> Self {
+        Self {
+            uuid: value.uuid,
+            parent_uuid: value.parent_uuid,
+            name: value.name,
+            count: value.count,
+            time: value.time,
+            hunt_type: value.hunt_type.to_string(),
+            has_charm: value.has_charm,
+            step_size: value.step_size,
+            success: value.success,
+            dexnav_encounters: value.dexnav_encounters,
+            created_at: value.created_at,
+            last_edit: value.last_edit,
+            is_deleted: value.is_deleted,
+        }
+    }
+}
+
+/// Only accepts the exact names used in the database,
+/// `backend::Hunttype::from` silently falls back to `NewOdds` instead
+pub fn parse_hunttype(name: String) -> ApiResult<backend::Hunttype> {
+    let hunt_type = backend::Hunttype::from(name.clone());
+    if hunt_type.to_string() != name {
+        return Err(ApiError::bad_request(format!("Unknown hunt_type `{name}`")));
+    }
+    if matches!(hunt_type, backend::Hunttype::Custom) {
+        return Err(ApiError::bad_request(String::from(
+            "Custom methods can only be picked on the edit page",
+        )));
+    }
+
+    Ok(hunt_type)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListQuery {
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewPhase {
+    pub parent_uuid: uuid::Uuid,
+    pub name: Option<String>,
+    /// defaults to the values of the newest phase of the parent
+    pub hunt_type: Option<String>,
+    pub has_charm: Option<bool>,
+    pub step_size: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PhasePatch {
+    pub name: Option<String>,
+    pub count: Option<i32>,
+    pub time: Option<i64>,
+    pub hunt_type: Option<String>,
+    pub has_charm: Option<bool>,
+    pub step_size: Option<i32>,
+    pub success: Option<bool>,
+}
+
+async fn owned(
+    tx: &mut backend::PgTx,
+    session: &UserSession,
+    id: uuid::Uuid,
+) -> ApiResult<backend::DbPhase> {
+    let phase = backend::phase::get(tx, id).await?;
+    check_owner(session, phase.owner_uuid)?;
+    Ok(phase)
+}
+
+async fn respond(mut tx: backend::PgTx, id: uuid::Uuid) -> ApiResult<HttpResponse> {
+    let phase = backend::phase::get(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(ApiPhase::from(phase)))
+}
+
+async fn list(
+    req: HttpRequest,
+    pool: Pool,
+    query: web::Query<ListQuery>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Read)?;
+    let mut tx = pool.begin().await?;
+
+    let phases = backend::phase::all_by_user(&mut tx, session.user_uuid)
+        .await?
+        .into_iter()
+        .filter(|p| query.archived || !p.is_deleted)
+        .map(ApiPhase::from)
+        .collect::<Vec<_>>();
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(phases))
+}
+
+async fn get(req: HttpRequest, pool: Pool, id: web::Path<uuid::Uuid>) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Read)?;
+    let mut tx = pool.begin().await?;
+
+    owned(&mut tx, &session, *id).await?;
+
+    respond(tx, *id).await
+}
+
+async fn create(
+    req: HttpRequest,
+    pool: Pool,
+    body: web::Json<NewPhase>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Edit)?;
+    let body = body.into_inner();
+    let mut tx = pool.begin().await?;
+
+    let parent = backend::counter::get(&mut tx, body.parent_uuid).await?;
+    check_owner(&session, parent.owner_uuid)?;
+
+    let siblings = backend::counter::get_children(&mut tx, parent.uuid).await?;
+    let last = siblings.last();
+
+    let hunt_type = match body.hunt_type {
+        Some(name) => parse_hunttype(name)?,
+        None => last
+            .map(|p| p.hunt_type.clone())
+            .unwrap_or(backend::Hunttype::NewOdds),
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    let phase = backend::DbPhase {
+        uuid: uuid::Uuid::new_v4(),
+        owner_uuid: session.user_uuid,
+        parent_uuid: parent.uuid,
+        name: body
+            .name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or(format!("Phase {}", siblings.len() + 1)),
+        count: 0,
+        time: 0,
+        hunt_type,
+        game: last.and_then(|p| p.game),
+        research_level: last.map(|p| p.research_level).unwrap_or_default(),
+        outbreak_cleared: last.map(|p| p.outbreak_cleared).unwrap_or_default(),
+        sparkling_power: last.map(|p| p.sparkling_power).unwrap_or_default(),
+        custom_method: last.and_then(|p| p.custom_method),
+        has_charm: body
+            .has_charm
+            .unwrap_or(last.map(|p| p.has_charm).unwrap_or_default()),
+        dexnav_encounters: None,
+        success: false,
+        created_at: now,
+        last_edit: now,
+        is_deleted: false,
+        step_size: body
+            .step_size
+            .unwrap_or(last.map(|p| p.step_size).unwrap_or(1)),
+        revision: 0,
+    };
+    validate_phase(&phase)?;
+    if let Some(step_size) = body.step_size {
+        validate::step_size(step_size)?;
+    }
+
+    let id = phase.uuid;
+    let step_size = phase.step_size;
+    backend::phase::update(&mut tx, phase).await?;
+    backend::phase::set_step(&mut tx, id, step_size).await?;
+    backend::counter::edited(&mut tx, parent.uuid).await?;
+
+    let phase = backend::phase::get(&mut tx, id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(ApiPhase::from(phase)))
+}
+
+async fn update(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+    body: web::Json<PhasePatch>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Edit)?;
+    let body = body.into_inner();
+    let mut tx = pool.begin().await?;
+
+    let mut phase = owned(&mut tx, &session, *id).await?;
+
+    if let Some(name) = body.name {
+        phase.name = name.trim().to_string();
+    }
+    if let Some(count) = body.count {
+        phase.count = count;
+    }
+    if let Some(time) = body.time {
+        phase.time = time;
+    }
+    if let Some(hunt_type) = body.hunt_type {
+        phase.hunt_type = parse_hunttype(hunt_type)?;
+    }
+    if let Some(has_charm) = body.has_charm {
+        phase.has_charm = has_charm;
+    }
+    if let Some(success) = body.success {
+        phase.success = success;
+    }
+    validate_phase(&phase)?;
+    if let Some(step_size) = body.step_size {
+        validate::step_size(step_size)?;
+    }
+    let step_size = body.step_size.unwrap_or(phase.step_size);
+    let parent = phase.parent_uuid;
+
+    backend::phase::update(&mut tx, phase).await?;
+    backend::phase::set_step(&mut tx, *id, step_size).await?;
+    backend::counter::edited(&mut tx, parent).await?;
+
+    respond(tx, *id).await
+}
+
+async fn increment(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+    body: Option<web::Json<counters::Increment>>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Count)?;
+    let body = body.map(|b| b.into_inner()).unwrap_or_default();
+    let mut tx = pool.begin().await?;
+
+    let phase = owned(&mut tx, &session, *id).await?;
+
+    let amount = body.amount.unwrap_or(phase.step_size);
+    backend::operation::record(
+        &mut tx,
+        session.user_uuid,
+        *id,
+        backend::operation::OperationKind::AddCount(amount),
+    )
+    .await?;
+    backend::counter::edited(&mut tx, phase.parent_uuid).await?;
+
+    respond(tx, *id).await
+}
+
+async fn add_time(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+    body: web::Json<counters::AddTime>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Count)?;
+    let mut tx = pool.begin().await?;
+
+    let phase = owned(&mut tx, &session, *id).await?;
+
+    backend::operation::record(
+        &mut tx,
+        session.user_uuid,
+        *id,
+        backend::operation::OperationKind::AddTime(body.millis),
+    )
+    .await?;
+    backend::counter::edited(&mut tx, phase.parent_uuid).await?;
+
+    respond(tx, *id).await
+}
+
+async fn archive(
+    req: HttpRequest,
+    pool: Pool,
+    id: web::Path<uuid::Uuid>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Edit)?;
+    let mut tx = pool.begin().await?;
+
+    let phase = owned(&mut tx, &session, *id).await?;
+    backend::phase::archive(&mut tx, *id).await?;
+    backend::counter::edited(&mut tx, phase.parent_uuid).await?;
+
+    respond(tx, *id).await
+}
@@ -0,0 +1,178 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    cfg.route("/preferences", web::get().to(get))
+        .route("/preferences", web::patch().to(update));
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiPreferences {
+    pub use_default_accent_color: bool,
+    /// hex color, `None` when the default accent color is used
+    pub accent_color: Option<String>,
+    pub show_separator: bool,
+    pub multi_select: bool,
+    pub save_on_pause: bool,
+    pub milestone_half: bool,
+    pub milestone_odds: bool,
+    pub milestone_double_odds: bool,
+    /// encounters that are marked besides the odds
+    pub milestone_counts: Vec<i32>,
+    pub milestone_notify: bool,
+    pub auto_advance: bool,
+    pub record_found: bool,
+}
+
+impl From<backend::DbPreferences> for ApiPreferences {
+    fn from(value: backend::DbPreferences) -> Self {
+        Self {
+            use_default_accent_color: value.use_default_accent_color,
+            accent_color: value.accent_color,
+            show_separator: value.show_separator,
+            multi_select: value.multi_select,
+            save_on_pause: value.save_on_pause,
+            milestone_half: value.milestone_half,
+            milestone_odds: value.milestone_odds,
+            milestone_double_odds: value.milestone_double_odds,
+            milestone_counts: value.milestone_counts,
+            milestone_notify: value.milestone_notify,
+            auto_advance: value.auto_advance,
+            record_found: value.record_found,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PreferencesPatch {
+    pub use_default_accent_color: Option<bool>,
+    pub accent_color: Option<String>,
+    pub show_separator: Option<bool>,
+    pub multi_select: Option<bool>,
+    pub save_on_pause: Option<bool>,
+    pub milestone_half: Option<bool>,
+    pub milestone_odds: Option<bool>,
+    pub milestone_double_odds: Option<bool>,
+    pub milestone_counts: Option<Vec<i32>>,
+    pub milestone_notify: Option<bool>,
+    pub auto_advance: Option<bool>,
+    pub record_found: Option<bool>,
+}
+
+/// Stored preferences of the user, or the defaults of a new account when none are saved yet
+async fn load(pool: &backend::PgPool, user: uuid::Uuid) -> ApiResult<backend::DbPreferences> {
+    match backend::DbPreferences::db_get(pool, user).await {
+        Ok(prefs) => Ok(prefs),
+        Err(backend::BackendError::DataNotFound(_)) => Ok(backend::DbPreferences {
+            user_uuid: user,
+            use_default_accent_color: true,
+            accent_color: None,
+            show_separator: false,
+            multi_select: false,
+            save_on_pause: true,
+            milestone_half: true,
+            milestone_odds: true,
+            milestone_double_odds: true,
+            milestone_counts: Vec::new(),
+            milestone_notify: false,
+            auto_advance: false,
+            record_found: false,
+        }),
+        Err(err) => Err(err)?,
+    }
+}
+
+async fn get(req: HttpRequest, pool: Pool) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Read)?;
+    let prefs = load(&pool, session.user_uuid).await?;
+
+    Ok(HttpResponse::Ok().json(ApiPreferences::from(prefs)))
+}
+
+async fn update(
+    req: HttpRequest,
+    pool: Pool,
+    body: web::Json<PreferencesPatch>,
+) -> ApiResult<HttpResponse> {
+    let session = authorize(&req, TokenScope::Admin)?;
+    let body = body.into_inner();
+    let mut prefs = load(&pool, session.user_uuid).await?;
+
+    if let Some(color) = body.accent_color {
+        let is_hex = color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !is_hex {
+            return Err(ApiError::bad_request(
+                "`accent_color` has to be a hex color like `#a1b2c3`",
+            ));
+        }
+        prefs.accent_color = Some(color);
+        prefs.use_default_accent_color = false;
+    }
+    if let Some(use_default) = body.use_default_accent_color {
+        prefs.use_default_accent_color = use_default;
+    }
+    if prefs.use_default_accent_color {
+        prefs.accent_color = None;
+    }
+    if let Some(show_separator) = body.show_separator {
+        prefs.show_separator = show_separator;
+    }
+    if let Some(multi_select) = body.multi_select {
+        prefs.multi_select = multi_select;
+    }
+    if let Some(save_on_pause) = body.save_on_pause {
+        prefs.save_on_pause = save_on_pause;
+    }
+    if let Some(half) = body.milestone_half {
+        prefs.milestone_half = half;
+    }
+    if let Some(odds) = body.milestone_odds {
+        prefs.milestone_odds = odds;
+    }
+    if let Some(double_odds) = body.milestone_double_odds {
+        prefs.milestone_double_odds = double_odds;
+    }
+    if let Some(mut counts) = body.milestone_counts {
+        counts.sort_unstable();
+        counts.dedup();
+        let milestones = crate::countable::milestone::Milestones {
+            counts,
+            ..Default::default()
+        };
+        if let Err(err) = milestones.validate() {
+            return Err(ApiError::bad_request(err));
+        }
+        prefs.milestone_counts = milestones.counts;
+    }
+    if let Some(notify) = body.milestone_notify {
+        prefs.milestone_notify = notify;
+    }
+    if let Some(auto_advance) = body.auto_advance {
+        prefs.auto_advance = auto_advance;
+    }
+    if let Some(record_found) = body.record_found {
+        prefs.record_found = record_found;
+    }
+
+    let response = ApiPreferences {
+        use_default_accent_color: prefs.use_default_accent_color,
+        accent_color: prefs.accent_color.clone(),
+        show_separator: prefs.show_separator,
+        multi_select: prefs.multi_select,
+        save_on_pause: prefs.save_on_pause,
+        milestone_half: prefs.milestone_half,
+        milestone_odds: prefs.milestone_odds,
+        milestone_double_odds: prefs.milestone_double_odds,
+        milestone_counts: prefs.milestone_counts.clone(),
+        milestone_notify: prefs.milestone_notify,
+        auto_advance: prefs.auto_advance,
+        record_found: prefs.record_found,
+    };
+    prefs.db_save(&pool).await?;
+
+    Ok(HttpResponse::Ok().json(response))
+}
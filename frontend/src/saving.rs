@@ -61,8 +61,11 @@ impl SaveHandler for ServerSaveHandler {
                     if let Some(id) = msg_id {
                         msg.fade_out(id);
                     }
+                    // changes to countables stay in the outbox until they reach the server,
+                    // see `countable::outbox`
                     if !is_offline(&err) {
-                        msg.without_timeout().set_server_err(&err);
+                        // validation errors are sent as json, see `AppError::InvalidField`
+                        msg.without_timeout().set_err(AppError::from(err.clone()));
                         on_error(&leptos::ServerFnErrorErr::from(err))
                     }
                 }
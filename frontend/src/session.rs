@@ -9,6 +9,15 @@ pub async fn actix_extract_user() -> Result<UserSession, ServerFnError> {
     use leptos_actix::{extract, redirect};
 
     let header = extract::<actix_web::HttpRequest>().await?;
+
+    // set by `middleware::CheckSession` on the `/api/session` scope
+    if let Some(session) = actix_web::HttpMessage::extensions(&header)
+        .get::<UserSession>()
+        .cloned()
+    {
+        return Ok(session);
+    }
+
     match header.cookie("session") {
         Some(session) => return Ok(serde_json::from_str(&session.value().to_string())?),
 
@@ -19,8 +28,53 @@ pub async fn actix_extract_user() -> Result<UserSession, ServerFnError> {
     };
 }
 
+/// Returns the current session,
+/// requests authenticated with a personal access token also need to carry `scope`
+#[cfg(feature = "ssr")]
+pub async fn require_scope(
+    scope: backend::access_token::TokenScope,
+) -> Result<UserSession, ServerFnError> {
+    let req = leptos_actix::extract::<actix_web::HttpRequest>().await?;
+
+    if !scope_allowed(&req, scope) {
+        return Err(AppError::Unauthorized)?;
+    }
+
+    actix_extract_user().await
+}
+
+/// Only personal access tokens are limited to their scopes.
+/// A cookie session is started by logging in with the password,
+/// so it may do everything the user can do and passes every scope.
+#[cfg(feature = "ssr")]
+pub fn scope_allowed(
+    req: &actix_web::HttpRequest,
+    scope: backend::access_token::TokenScope,
+) -> bool {
+    actix_web::HttpMessage::extensions(req)
+        .get::<backend::access_token::DbAccessToken>()
+        .map(|token| token.has_scope(scope))
+        .unwrap_or(true)
+}
+
+/// Session used to render pages,
+/// guests without an account get a session without token, see [`guest`]
+#[server]
+pub async fn page_session() -> Result<UserSession, ServerFnError> {
+    let req = leptos_actix::extract::<actix_web::HttpRequest>().await?;
+
+    if req.cookie("session").is_none()
+        && let Some(guest) = req.cookie(guest::GUEST_COOKIE)
+        && let Ok(owner) = uuid::Uuid::parse_str(guest.value())
+    {
+        return Ok(UserSession::guest(owner));
+    }
+
+    actix_extract_user().await
+}
+
 async fn get_user_signal() -> RwSignal<UserSession> {
-    match actix_extract_user().await {
+    match page_session().await {
         Ok(session) => session.into(),
 
         Err(err) => {
@@ -37,6 +91,21 @@ pub struct UserSession {
     pub token: uuid::Uuid,
 }
 
+impl UserSession {
+    pub fn guest(owner: uuid::Uuid) -> Self {
+        Self {
+            user_uuid: owner,
+            username: String::from("Guest"),
+            token: uuid::Uuid::nil(),
+        }
+    }
+
+    /// Guests have no token, their data is only stored in the browser
+    pub fn is_guest(&self) -> bool {
+        self.token.is_nil() && !self.user_uuid.is_nil()
+    }
+}
+
 #[component(transparent)]
 pub fn SessionFormInput(#[prop(into)] session: MaybeSignal<UserSession>) -> impl IntoView {
     let session_val = store_value(session);
@@ -51,17 +120,142 @@ pub fn SessionFormInput(#[prop(into)] session: MaybeSignal<UserSession>) -> impl
     }
 }
 
+const SAVED_ACCOUNTS_KEY: &str = "saved_accounts";
+
+/// Accounts logged in on this browser before, they can be switched to without logging in again
+pub fn saved_accounts() -> Vec<UserSession> {
+    use gloo_storage::{LocalStorage, Storage};
+
+    LocalStorage::get(SAVED_ACCOUNTS_KEY).unwrap_or_default()
+}
+
+/// Adds `session` to the saved accounts or updates its token
+pub fn remember_account(session: &UserSession) -> Result<(), AppError> {
+    use gloo_storage::{LocalStorage, Storage};
+
+    LocalStorage::set(SAVED_ACCOUNTS_KEY, with_account(saved_accounts(), session))?;
+
+    Ok(())
+}
+
+pub fn forget_account(user: uuid::Uuid) -> Result<(), AppError> {
+    use gloo_storage::{LocalStorage, Storage};
+
+    LocalStorage::set(SAVED_ACCOUNTS_KEY, without_account(saved_accounts(), user))?;
+
+    Ok(())
+}
+
+/// `accounts` with `session` as the newest one, replacing an older session of the same user
+fn with_account(accounts: Vec<UserSession>, session: &UserSession) -> Vec<UserSession> {
+    let mut accounts = without_account(accounts, session.user_uuid);
+    accounts.push(session.clone());
+    accounts
+}
+
+fn without_account(mut accounts: Vec<UserSession>, user: uuid::Uuid) -> Vec<UserSession> {
+    accounts.retain(|a| a.user_uuid != user);
+    accounts
+}
+
 #[component(transparent)]
 pub fn ProvideSessionSignal(children: ChildrenFn) -> impl IntoView {
     view! {
         <Await future=get_user_signal let:user>
 
             {
-                create_blocking_resource(*user, move |u| { api::check_user(u) });
-                provide_context(*user);
+                let user = *user;
+                create_blocking_resource(
+                    user,
+                    move |u| async move {
+                        if u.is_guest() { Ok(()) } else { api::check_user(u).await }
+                    },
+                );
+                provide_context(user);
+                // effects only run in the browser
+                create_effect(move |_| {
+                    let session = user.get();
+                    if session.is_guest() {
+                        guest::cancel_upgrade()
+                    } else if !session.user_uuid.is_nil() {
+                        let _ = remember_account(&session);
+                    }
+                });
                 children()
             }
 
         </Await>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(user: uuid::Uuid) -> UserSession {
+        UserSession {
+            user_uuid: user,
+            username: user.to_string(),
+            token: uuid::Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn saved_accounts_keep_the_newest_session() {
+        let (first, second) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let old = session(first);
+        let accounts = with_account(with_account(Vec::new(), &old), &session(second));
+
+        let renewed = session(first);
+        let accounts = with_account(accounts, &renewed);
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].user_uuid, second);
+        assert_eq!(accounts[1], renewed);
+
+        let accounts = without_account(accounts, second);
+        assert_eq!(accounts, vec![renewed]);
+        assert_eq!(without_account(accounts.clone(), second), accounts);
+    }
+
+    #[cfg(feature = "ssr")]
+    mod scopes {
+        use actix_web::HttpMessage;
+        use backend::access_token::{DbAccessToken, TokenScope};
+
+        use super::super::*;
+
+        const SCOPES: [TokenScope; 4] = [
+            TokenScope::Read,
+            TokenScope::Count,
+            TokenScope::Edit,
+            TokenScope::Admin,
+        ];
+
+        #[test]
+        fn cookie_sessions_pass_every_scope() {
+            let req = actix_web::test::TestRequest::default().to_http_request();
+            for scope in SCOPES {
+                assert!(scope_allowed(&req, scope), "{scope}");
+            }
+        }
+
+        #[test]
+        fn tokens_pass_their_scopes() {
+            let req = actix_web::test::TestRequest::default().to_http_request();
+            req.extensions_mut().insert(DbAccessToken {
+                uuid: uuid::Uuid::new_v4(),
+                user_uuid: uuid::Uuid::new_v4(),
+                name: String::from("Token"),
+                scopes: vec![TokenScope::Count.to_string()],
+                created_at: chrono::Utc::now().naive_utc(),
+                expire_on: None,
+                last_used: None,
+            });
+
+            assert!(scope_allowed(&req, TokenScope::Read));
+            assert!(scope_allowed(&req, TokenScope::Count));
+            assert!(!scope_allowed(&req, TokenScope::Edit));
+            assert!(!scope_allowed(&req, TokenScope::Admin));
+        }
+    }
+}
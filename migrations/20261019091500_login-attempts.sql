@@ -0,0 +1,9 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS login_attempts (
+    key             varchar     PRIMARY KEY,
+    failures        integer     NOT NULL DEFAULT 0,
+    last_failure    timestamp   NOT NULL DEFAULT now(),
+    locked_until    timestamp
+);
+
+COMMENT ON COLUMN login_attempts.key IS E'Either `ip:<address>` or `user:<username>`, every login attempt is tracked under both keys.';
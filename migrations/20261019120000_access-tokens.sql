@@ -0,0 +1,16 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS access_tokens (
+    uuid            uuid        PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_uuid       uuid        NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    name            varchar     NOT NULL,
+    token_hash      varchar     UNIQUE NOT NULL,
+    scopes          varchar[]   NOT NULL DEFAULT '{}',
+    created_at      timestamp   NOT NULL DEFAULT now(),
+    expire_on       timestamp,
+    last_used       timestamp
+);
+
+CREATE INDEX IF NOT EXISTS access_tokens_user_uuid_idx ON access_tokens (user_uuid);
+
+COMMENT ON COLUMN access_tokens.token_hash IS E'sha256 of the token, the token itself is only shown once on creation';
+COMMENT ON COLUMN access_tokens.scopes IS E'Any of `read`, `count`, `edit` or `admin`';
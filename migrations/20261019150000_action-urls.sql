@@ -0,0 +1,18 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS action_urls (
+    uuid            uuid        PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_uuid       uuid        NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    countable_uuid  uuid        NOT NULL,
+    action          varchar     NOT NULL,
+    token_hash      varchar     UNIQUE NOT NULL,
+    timer_started   timestamp,
+    created_at      timestamp   NOT NULL DEFAULT now(),
+    last_used       timestamp
+);
+
+CREATE INDEX IF NOT EXISTS action_urls_countable_uuid_idx ON action_urls (countable_uuid);
+
+COMMENT ON COLUMN action_urls.countable_uuid IS E'uuid of either a counter or a phase';
+COMMENT ON COLUMN action_urls.action IS E'Any of `increment`, `decrement` or `toggle_timer`';
+COMMENT ON COLUMN action_urls.token_hash IS E'sha256 of the secret part of the url';
+COMMENT ON COLUMN action_urls.timer_started IS E'Set while a `toggle_timer` url has started the timer';
@@ -0,0 +1,21 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS operations (
+    uuid            uuid        PRIMARY KEY,
+    owner_uuid      uuid        NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    countable_uuid  uuid        NOT NULL,
+    device_id       uuid        NOT NULL,
+    clock           bigint      NOT NULL,
+    kind            varchar     NOT NULL,
+    amount          bigint,
+    value           varchar,
+    created_at      timestamp   NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS operations_countable_kind_idx ON operations (countable_uuid, kind);
+CREATE INDEX IF NOT EXISTS operations_owner_uuid_idx ON operations (owner_uuid);
+
+COMMENT ON COLUMN operations.uuid IS E'Generated by the device, operations that were already applied are skipped';
+COMMENT ON COLUMN operations.clock IS E'Lamport clock of the device, orders conflicting operations together with `device_id`';
+COMMENT ON COLUMN operations.kind IS E'Any of `create`, `add_count`, `add_time`, `rename`, `set_hunttype`, `set_success` or `archive`';
+COMMENT ON COLUMN operations.amount IS E'Count or milliseconds added by `add_count` and `add_time`';
+COMMENT ON COLUMN operations.value IS E'New value of `rename`, `set_hunttype` and `set_success`';
@@ -0,0 +1,3 @@
+-- Add migration script here
+CREATE INDEX IF NOT EXISTS counters_owner_last_edit_idx ON counters (owner_uuid, last_edit);
+CREATE INDEX IF NOT EXISTS phases_owner_last_edit_idx ON phases (owner_uuid, last_edit);
@@ -0,0 +1,3 @@
+-- Add migration script here
+ALTER TABLE counters ADD COLUMN IF NOT EXISTS revision integer NOT NULL DEFAULT 0;
+ALTER TABLE phases ADD COLUMN IF NOT EXISTS revision integer NOT NULL DEFAULT 0;
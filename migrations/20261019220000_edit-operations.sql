@@ -0,0 +1,3 @@
+-- Add migration script here
+COMMENT ON COLUMN operations.kind IS E'Any of `create`, `add_count`, `add_time`, `rename`, `set_hunttype`, `set_success`, `set_step_size`, `set_charm` or `archive`';
+COMMENT ON COLUMN operations.value IS E'New value of `rename`, `set_hunttype`, `set_success`, `set_step_size` and `set_charm`';
@@ -0,0 +1,29 @@
+-- Add migration script here
+DO $$
+BEGIN
+    IF NOT EXISTS(SELECT oid FROM pg_type WHERE typname = 'game')
+    THEN
+        CREATE TYPE game AS ENUM (
+            'GoldSilverCrystal',
+            'RubySapphireEmerald',
+            'FireRedLeafGreen',
+            'DiamondPearlPlatinum',
+            'HeartGoldSoulSilver',
+            'BlackWhite',
+            'Black2White2',
+            'XY',
+            'OmegaRubyAlphaSapphire',
+            'SunMoon',
+            'UltraSunUltraMoon',
+            'LetsGo',
+            'SwordShield',
+            'BrilliantDiamondShiningPearl',
+            'LegendsArceus',
+            'ScarletViolet'
+        );
+    END IF;
+END $$;
+
+ALTER TABLE phases ADD COLUMN IF NOT EXISTS game game;
+
+COMMENT ON COLUMN phases.game IS E'Game the phase is hunted in, decides the base odds and whether the charm exists. NULL when unknown';
@@ -0,0 +1,12 @@
+-- Add migration script here
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'MassOutbreak';
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'MassiveMassOutbreak';
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'Outbreak';
+
+ALTER TABLE phases ADD COLUMN IF NOT EXISTS research_level smallint NOT NULL DEFAULT 0;
+ALTER TABLE phases ADD COLUMN IF NOT EXISTS outbreak_cleared integer NOT NULL DEFAULT 0;
+ALTER TABLE phases ADD COLUMN IF NOT EXISTS sparkling_power smallint NOT NULL DEFAULT 0;
+
+COMMENT ON COLUMN phases.research_level IS E'Pokédex research of `MassOutbreak` and `MassiveMassOutbreak`: 0 below level 10, 1 level 10, 2 perfect';
+COMMENT ON COLUMN phases.outbreak_cleared IS E'Pokémon defeated in the `Outbreak` before hunting';
+COMMENT ON COLUMN phases.sparkling_power IS E'Sparkling power level of the sandwich used in an `Outbreak`, 0 when none';
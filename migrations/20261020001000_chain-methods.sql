@@ -0,0 +1,8 @@
+-- Add migration script here
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'ChainFishing';
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'Horde';
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'RadarGenIV';
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'RadarGenVI';
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'RadarBDSP';
+
+COMMENT ON COLUMN phases.count IS E'Encounters of the phase, the length of the chain for `ChainFishing` and the `Radar` methods and the number of hordes for `Horde`';
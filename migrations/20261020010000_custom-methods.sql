@@ -0,0 +1,26 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS custom_methods (
+    uuid            uuid        PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_uuid       uuid        NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    name            varchar     NOT NULL,
+    odds            integer     NOT NULL DEFAULT 4096,
+    rolls           integer     NOT NULL DEFAULT 1,
+    charm_rolls     integer     NOT NULL DEFAULT 0,
+    table_counts    integer[]   NOT NULL DEFAULT '{}',
+    table_rolls     integer[]   NOT NULL DEFAULT '{}',
+    created_at      timestamp   NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS custom_methods_user_uuid_idx ON custom_methods (user_uuid);
+
+COMMENT ON COLUMN custom_methods.odds IS E'Base odds of a single roll, 1 in `odds`';
+COMMENT ON COLUMN custom_methods.rolls IS E'Rolls per encounter before the first entry of the table';
+COMMENT ON COLUMN custom_methods.charm_rolls IS E'Extra rolls per encounter with the shiny charm';
+COMMENT ON COLUMN custom_methods.table_counts IS E'Counts from which the rolls of the same index in `table_rolls` apply, ascending';
+COMMENT ON COLUMN custom_methods.table_rolls IS E'Rolls per encounter from the count of the same index in `table_counts` on';
+
+ALTER TYPE hunttype ADD VALUE IF NOT EXISTS 'Custom';
+
+ALTER TABLE phases ADD COLUMN IF NOT EXISTS custom_method uuid REFERENCES custom_methods(uuid) ON DELETE SET NULL;
+
+COMMENT ON COLUMN phases.custom_method IS E'Method of a `Custom` hunt type, NULL when the method was deleted';
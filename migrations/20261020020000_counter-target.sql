@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE counters ADD COLUMN IF NOT EXISTS target integer;
+
+COMMENT ON COLUMN counters.target IS E'Amount of shinies the hunt needs, NULL when every shiny is hunted on its own';
@@ -0,0 +1,7 @@
+-- Add migration script here
+ALTER TABLE preferences
+ADD COLUMN milestone_half boolean DEFAULT true NOT NULL,
+ADD COLUMN milestone_odds boolean DEFAULT true NOT NULL,
+ADD COLUMN milestone_double_odds boolean DEFAULT true NOT NULL,
+ADD COLUMN milestone_counts integer[] DEFAULT '{}' NOT NULL,
+ADD COLUMN milestone_notify boolean DEFAULT false NOT NULL;
@@ -0,0 +1,4 @@
+-- Add migration script here
+ALTER TABLE preferences
+ADD COLUMN auto_advance boolean DEFAULT false NOT NULL,
+ADD COLUMN record_found boolean DEFAULT false NOT NULL;
@@ -0,0 +1,10 @@
+-- Add migration script here
+CREATE TABLE countable_changes (
+    uuid        uuid        PRIMARY KEY,
+    owner_uuid  uuid        NOT NULL REFERENCES users(uuid) ON DELETE CASCADE,
+    -- id of the last transaction that changed or removed the countable
+    xid         bigint      NOT NULL,
+    removed     boolean     NOT NULL DEFAULT false
+);
+
+CREATE INDEX countable_changes_owner_xid ON countable_changes (owner_uuid, xid);
@@ -0,0 +1,3 @@
+-- Add migration script here
+DROP INDEX IF EXISTS counters_owner_last_edit_idx;
+DROP INDEX IF EXISTS phases_owner_last_edit_idx;